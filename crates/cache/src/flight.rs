@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+use std::hash::{BuildHasher, Hash, RandomState};
+use std::sync::Mutex;
+use std::time::Duration;
+
+use tokio::sync::watch;
+
+use crate::CachedEntry;
+
+/// State shared by requests for the same key, `None` until the leader finished.
+type Shared = Option<Option<CachedEntry>>;
+
+/// Requests computing a missing cache entry, used to coalesce concurrent misses of a key.
+///
+/// Flights are identified by the hash of their key, so they do not need to own a copy of it.
+pub(crate) struct Flights {
+    hasher: RandomState,
+    inner: Mutex<HashMap<u64, watch::Receiver<Shared>>>,
+}
+
+impl Default for Flights {
+    fn default() -> Self {
+        Self {
+            hasher: RandomState::new(),
+            inner: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+pub(crate) enum Flight<'a> {
+    /// The first request for the key, it computes the entry.
+    Leader(Leader<'a>),
+    /// Other requests for the key wait for the leader.
+    Follower(watch::Receiver<Shared>),
+}
+
+impl Flights {
+    /// Joins the flight of `key`, the caller becomes the leader if there is no flight yet.
+    pub(crate) fn join<K: Hash>(&self, key: &K) -> Flight<'_> {
+        let hash = self.hasher.hash_one(key);
+        let mut inner = self.inner.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(receiver) = inner.get(&hash) {
+            return Flight::Follower(receiver.clone());
+        }
+        let (sender, receiver) = watch::channel(None);
+        inner.insert(hash, receiver);
+        Flight::Leader(Leader {
+            flights: self,
+            hash,
+            sender,
+        })
+    }
+}
+
+/// Waits up to `timeout` for the leader and returns its entry, `None` if the response of the
+/// leader could not be cached, the leader was cancelled or it took too long.
+pub(crate) async fn wait(
+    mut receiver: watch::Receiver<Shared>,
+    timeout: Duration,
+) -> Option<CachedEntry> {
+    let shared = tokio::time::timeout(timeout, receiver.wait_for(Option::is_some)).await;
+    match shared {
+        Ok(Ok(shared)) => shared.clone().flatten(),
+        Ok(Err(_)) => None,
+        Err(_) => {
+            tracing::debug!("waiting for the cache entry timed out");
+            None
+        }
+    }
+}
+
+/// Leader of a flight, the flight is removed when it is dropped, waking up the followers even if
+/// the leader is cancelled.
+pub(crate) struct Leader<'a> {
+    flights: &'a Flights,
+    hash: u64,
+    sender: watch::Sender<Shared>,
+}
+
+impl Leader<'_> {
+    /// Shares the entry computed by the leader with the followers.
+    pub(crate) fn finish(self, entry: Option<CachedEntry>) {
+        self.sender.send_replace(Some(entry));
+    }
+}
+
+impl Drop for Leader<'_> {
+    fn drop(&mut self) {
+        self.flights
+            .inner
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(&self.hash);
+    }
+}
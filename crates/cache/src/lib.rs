@@ -4,7 +4,8 @@
 //! This middleware will cache the response's StatusCode, Headers and Body.
 //!
 //! You can define your custom [`CacheIssuer`] to determine which responses should be cached,
-//! or you can use the default [`RequestIssuer`].
+//! or you can use the default [`RequestIssuer`]. Wrap it with [`CacheControlIssuer`] to never cache
+//! responses of authenticated requests.
 //!
 //! The default cache store is [`MokaStore`], which is a wrapper of [`moka`].
 //! You can define your own cache store by implementing [`CacheStore`].
@@ -20,14 +21,20 @@ use std::collections::VecDeque;
 use std::error::Error as StdError;
 use std::future::Future;
 use std::hash::Hash;
+use std::time::Duration;
 
 use bytes::Bytes;
 use salvo_core::handler::Skipper;
-use salvo_core::http::{HeaderMap, ResBody, StatusCode};
+use salvo_core::http::header::{AUTHORIZATION, CACHE_CONTROL};
+use salvo_core::http::{HeaderMap, HeaderName, ResBody, StatusCode};
 use salvo_core::{async_trait, Depot, Error, FlowCtrl, Handler, Request, Response};
 
+mod flight;
+use flight::{Flight, Flights};
 mod skipper;
 pub use skipper::MethodSkipper;
+mod tiered_store;
+pub use tiered_store::TieredStore;
 
 #[macro_use]
 mod cfg;
@@ -145,6 +152,80 @@ impl CacheIssuer for RequestIssuer {
     }
 }
 
+/// Issuer refusing to cache requests which carry credentials, so per-user responses are never
+/// cached under a shared key.
+///
+/// A request is not cached if it has one of the sensitive headers or cookies, by default the
+/// `Authorization` header and the `salvo.session.id` cookie. Other requests get their key from
+/// the inner issuer.
+///
+/// # Example
+///
+/// ```
+/// use salvo_cache::{CacheControlIssuer, RequestIssuer};
+///
+/// let issuer = CacheControlIssuer::new(RequestIssuer::default()).add_sensitive_cookie("token");
+/// ```
+#[derive(Clone, Debug)]
+pub struct CacheControlIssuer<I> {
+    inner: I,
+    sensitive_headers: Vec<HeaderName>,
+    sensitive_cookies: Vec<String>,
+}
+impl<I> CacheControlIssuer<I> {
+    /// Create a new `CacheControlIssuer` wrapping the `inner` issuer.
+    pub fn new(inner: I) -> Self {
+        Self {
+            inner,
+            sensitive_headers: vec![AUTHORIZATION],
+            sensitive_cookies: vec!["salvo.session.id".into()],
+        }
+    }
+    /// Sets the headers which mark a request as authenticated.
+    pub fn sensitive_headers(mut self, headers: Vec<HeaderName>) -> Self {
+        self.sensitive_headers = headers;
+        self
+    }
+    /// Adds a header which marks a request as authenticated.
+    pub fn add_sensitive_header(mut self, header: HeaderName) -> Self {
+        self.sensitive_headers.push(header);
+        self
+    }
+    /// Sets the cookie names which mark a request as authenticated.
+    pub fn sensitive_cookies(mut self, names: Vec<impl Into<String>>) -> Self {
+        self.sensitive_cookies = names.into_iter().map(Into::into).collect();
+        self
+    }
+    /// Adds a cookie name which marks a request as authenticated.
+    pub fn add_sensitive_cookie(mut self, name: impl Into<String>) -> Self {
+        self.sensitive_cookies.push(name.into());
+        self
+    }
+
+    fn is_sensitive(&self, req: &Request) -> bool {
+        self.sensitive_headers
+            .iter()
+            .any(|name| req.headers().contains_key(name))
+            || self
+                .sensitive_cookies
+                .iter()
+                .any(|name| req.cookie(name).is_some())
+    }
+}
+
+impl<I> CacheIssuer for CacheControlIssuer<I>
+where
+    I: CacheIssuer,
+{
+    type Key = I::Key;
+    async fn issue(&self, req: &mut Request, depot: &Depot) -> Option<Self::Key> {
+        if self.is_sensitive(req) {
+            return None;
+        }
+        self.inner.issue(req, depot).await
+    }
+}
+
 /// Store cache.
 pub trait CacheStore: Send + Sync + 'static {
     /// Error type for CacheStore.
@@ -162,6 +243,17 @@ pub trait CacheStore: Send + Sync + 'static {
         key: Self::Key,
         data: CachedEntry,
     ) -> impl Future<Output = Result<(), Self::Error>> + Send;
+    /// Get the cache item from the store by the owned key type.
+    ///
+    /// [`Cache`] loads entries through this method. The default implementation calls
+    /// [`CacheStore::load_entry`], stores which need an owned key when loading, such as
+    /// [`TieredStore`] promoting entries, can override it.
+    fn load_entry_by_key(&self, key: &Self::Key) -> impl Future<Output = Option<CachedEntry>> + Send
+    where
+        Self::Key: Sync,
+    {
+        self.load_entry(key)
+    }
 }
 
 /// `CachedBody` is used to save response body to `CachedStore`.
@@ -240,8 +332,15 @@ impl CachedEntry {
     }
 }
 
+/// Default time coalesced requests wait for the first request of their key, 10 seconds.
+pub const DEFAULT_SINGLE_FLIGHT_TIMEOUT: Duration = Duration::from_secs(10);
+
 /// Cache middleware.
 ///
+/// Concurrent requests missing the same key are coalesced by default: only the first one runs
+/// the handler, the others wait for it and share its response, so a slow backend is not hit by
+/// all of them at once. See [`Cache::single_flight`].
+///
 /// # Example
 ///
 /// ```
@@ -264,6 +363,13 @@ pub struct Cache<S, I> {
     pub issuer: I,
     /// Skipper.
     pub skipper: Box<dyn Skipper>,
+    /// Whether request `Cache-Control: no-cache`/`no-store` directives bypass the cache.
+    pub respect_request_cache_control: bool,
+    /// Whether concurrent misses of the same key are coalesced.
+    pub single_flight: bool,
+    /// How long coalesced requests wait for the first request before running the handler.
+    pub single_flight_timeout: Duration,
+    flights: Flights,
 }
 
 impl<S, I> Cache<S, I> {
@@ -275,6 +381,10 @@ impl<S, I> Cache<S, I> {
             store,
             issuer,
             skipper: Box::new(skipper),
+            respect_request_cache_control: false,
+            single_flight: true,
+            single_flight_timeout: DEFAULT_SINGLE_FLIGHT_TIMEOUT,
+            flights: Flights::default(),
         }
     }
     /// Sets skipper and returns new `Cache`.
@@ -283,6 +393,56 @@ impl<S, I> Cache<S, I> {
         self.skipper = Box::new(skipper);
         self
     }
+    /// Sets whether request `Cache-Control` directives are respected and returns new `Cache`.
+    ///
+    /// When enabled, a request with `no-cache` skips the cached entry and refreshes it with the
+    /// new response, a request with `no-store` skips the cache without touching the entry. It is
+    /// disabled by default, because any client could use it to bypass the cache.
+    #[inline]
+    pub fn respect_request_cache_control(mut self, respect: bool) -> Self {
+        self.respect_request_cache_control = respect;
+        self
+    }
+    /// Sets whether concurrent misses of the same key are coalesced and returns new `Cache`.
+    ///
+    /// When enabled, the first request missing a key runs the handler while the other requests
+    /// for the key wait, up to [`single_flight_timeout`](Self::single_flight_timeout), and get
+    /// its response. If the response can not be cached, e.g. it is a
+    /// stream, the waiting requests run the handler themselves. It is enabled by default.
+    #[inline]
+    pub fn single_flight(mut self, single_flight: bool) -> Self {
+        self.single_flight = single_flight;
+        self
+    }
+    /// Sets how long coalesced requests wait for the first request and returns new `Cache`.
+    ///
+    /// Requests still waiting after it run the handler themselves, so a stuck handler does not
+    /// hold up every request for the key. It is [`DEFAULT_SINGLE_FLIGHT_TIMEOUT`] by default.
+    #[inline]
+    pub fn single_flight_timeout(mut self, timeout: Duration) -> Self {
+        self.single_flight_timeout = timeout;
+        self
+    }
+}
+
+/// Returns `(no_cache, no_store)` directives of the request `Cache-Control` headers.
+fn request_cache_directives(req: &Request) -> (bool, bool) {
+    let mut no_cache = false;
+    let mut no_store = false;
+    for value in req.headers().get_all(CACHE_CONTROL) {
+        let Ok(value) = value.to_str() else {
+            continue;
+        };
+        for directive in value.split(',') {
+            let directive = directive.trim();
+            if directive.eq_ignore_ascii_case("no-cache") {
+                no_cache = true;
+            } else if directive.eq_ignore_ascii_case("no-store") {
+                no_store = true;
+            }
+        }
+    }
+    (no_cache, no_store)
 }
 
 #[async_trait]
@@ -307,16 +467,43 @@ where
                 return;
             }
         };
-        let cache = match self.store.load_entry(&key).await {
+        let (no_cache, no_store) = if self.respect_request_cache_control {
+            request_cache_directives(req)
+        } else {
+            (false, false)
+        };
+        let mut cache = if no_cache || no_store {
+            None
+        } else {
+            self.store.load_entry_by_key(&key).await
+        };
+        let mut leader = None;
+        if cache.is_none() && self.single_flight && !no_cache && !no_store {
+            match self.flights.join(&key) {
+                Flight::Leader(flight) => {
+                    // The entry may be saved by a leader which finished after it was loaded.
+                    cache = self.store.load_entry_by_key(&key).await;
+                    leader = Some(flight);
+                }
+                Flight::Follower(receiver) => {
+                    cache = flight::wait(receiver, self.single_flight_timeout).await;
+                }
+            }
+        }
+        let cache = match cache {
             Some(cache) => cache,
             None => {
                 ctrl.call_next(req, depot, res).await;
-                if !res.body.is_stream() && !res.body.is_error() {
+                let mut shared = None;
+                if !no_store && !res.body.is_stream() && !res.body.is_error() {
                     let headers = res.headers().clone();
                     let body = TryInto::<CachedBody>::try_into(&res.body);
                     match body {
                         Ok(body) => {
                             let cached_data = CachedEntry::new(res.status_code, headers, body);
+                            if leader.is_some() {
+                                shared = Some(cached_data.clone());
+                            }
                             if let Err(e) = self.store.save_entry(key, cached_data).await {
                                 tracing::error!(error = ?e, "cache failed");
                             }
@@ -324,6 +511,9 @@ where
                         Err(e) => tracing::error!(error = ?e, "cache failed"),
                     }
                 }
+                if let Some(leader) = leader {
+                    leader.finish(shared);
+                }
                 return;
             }
         };
@@ -390,4 +580,144 @@ mod tests {
 
         assert_ne!(content0, content2);
     }
+
+    #[tokio::test]
+    async fn test_respect_request_cache_control() {
+        let cache = Cache::new(
+            MokaStore::builder()
+                .time_to_live(std::time::Duration::from_secs(60))
+                .build(),
+            RequestIssuer::default(),
+        )
+        .respect_request_cache_control(true);
+        let router = Router::new().hoop(cache).goal(cached);
+        let service = Service::new(router);
+
+        let get = |cache_control: Option<&'static str>| {
+            let mut client = TestClient::get("http://127.0.0.1:5801");
+            if let Some(cache_control) = cache_control {
+                client = client.add_header("cache-control", cache_control, true);
+            }
+            async { client.send(&service).await.take_string().await.unwrap() }
+        };
+
+        let content0 = get(None).await;
+        assert_eq!(get(None).await, content0);
+
+        let content1 = get(Some("no-store")).await;
+        assert_ne!(content1, content0);
+        assert_eq!(get(None).await, content0);
+
+        let content2 = get(Some("max-age=0, no-cache")).await;
+        assert_ne!(content2, content0);
+        assert_eq!(get(None).await, content2);
+    }
+
+    #[tokio::test]
+    async fn test_cache_control_issuer() {
+        let cache = Cache::new(
+            MokaStore::builder()
+                .time_to_live(std::time::Duration::from_secs(60))
+                .build(),
+            CacheControlIssuer::new(RequestIssuer::default()),
+        );
+        let router = Router::new().hoop(cache).goal(cached);
+        let service = Service::new(router);
+
+        let get = |header: Option<(&'static str, &'static str)>| {
+            let mut client = TestClient::get("http://127.0.0.1:5801");
+            if let Some((name, value)) = header {
+                client = client.add_header(name, value, true);
+            }
+            async { client.send(&service).await.take_string().await.unwrap() }
+        };
+
+        let authorized = get(Some(("authorization", "Bearer token"))).await;
+        let anonymous = get(None).await;
+        assert_ne!(anonymous, authorized);
+        assert_eq!(get(None).await, anonymous);
+
+        assert_ne!(
+            get(Some(("authorization", "Bearer token"))).await,
+            anonymous
+        );
+        assert_ne!(
+            get(Some(("cookie", "salvo.session.id=abc"))).await,
+            anonymous
+        );
+    }
+
+    #[tokio::test]
+    async fn test_single_flight() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+        #[handler]
+        async fn slow() -> String {
+            let call = CALLS.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(tokio::time::Duration::from_millis(200)).await;
+            format!("call {call}")
+        }
+
+        let cache = Cache::new(MokaStore::builder().build(), RequestIssuer::default());
+        let router = Router::new().hoop(cache).goal(slow);
+        let service = std::sync::Arc::new(Service::new(router));
+
+        let tasks = (0..8)
+            .map(|_| {
+                let service = service.clone();
+                tokio::spawn(async move {
+                    TestClient::get("http://127.0.0.1:5801")
+                        .send(&*service)
+                        .await
+                        .take_string()
+                        .await
+                        .unwrap()
+                })
+            })
+            .collect::<Vec<_>>();
+        for task in tasks {
+            assert_eq!(task.await.unwrap(), "call 0");
+        }
+        assert_eq!(CALLS.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_single_flight_timeout() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+
+        #[handler]
+        async fn stuck() -> &'static str {
+            CALLS.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(tokio::time::Duration::from_millis(500)).await;
+            "done"
+        }
+
+        let cache = Cache::new(MokaStore::builder().build(), RequestIssuer::default())
+            .single_flight_timeout(Duration::from_millis(50));
+        let router = Router::new().hoop(cache).goal(stuck);
+        let service = std::sync::Arc::new(Service::new(router));
+
+        let tasks = (0..4)
+            .map(|_| {
+                let service = service.clone();
+                tokio::spawn(async move {
+                    TestClient::get("http://127.0.0.1:5801")
+                        .send(&*service)
+                        .await
+                        .take_string()
+                        .await
+                        .unwrap()
+                })
+            })
+            .collect::<Vec<_>>();
+        for task in tasks {
+            assert_eq!(task.await.unwrap(), "done");
+        }
+        // The waiting requests gave up on the stuck one and ran the handler themselves.
+        assert_eq!(CALLS.load(Ordering::SeqCst), 4);
+    }
 }
@@ -0,0 +1,131 @@
+//! Two tiers store module.
+use std::borrow::Borrow;
+use std::hash::Hash;
+
+use salvo_core::Error;
+
+use super::{CacheStore, CachedEntry};
+
+/// A store composed of a small and fast first tier backed by a larger second tier.
+///
+/// Entries are looked up in the first tier, and fall back to the second tier on miss. An entry
+/// found in the second tier is promoted into the first tier, so it is served from there next time.
+/// Entries are saved into both tiers.
+///
+/// Entries evicted from the first tier are still served from the second tier. Failing to promote
+/// an entry is only logged, the entry is served anyway.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use salvo_core::Router;
+/// use salvo_cache::{Cache, MokaStore, RequestIssuer, TieredStore};
+///
+/// let store = TieredStore::new(
+///     MokaStore::builder().max_capacity(100).build(),
+///     MokaStore::builder().max_capacity(10_000).time_to_live(Duration::from_secs(600)).build(),
+/// );
+/// let cache = Cache::new(store, RequestIssuer::default());
+/// let router = Router::new().hoop(cache);
+/// ```
+#[derive(Debug)]
+pub struct TieredStore<S1, S2> {
+    first: S1,
+    second: S2,
+}
+
+impl<S1, S2> TieredStore<S1, S2> {
+    /// Create a new `TieredStore`.
+    #[inline]
+    pub fn new(first: S1, second: S2) -> Self {
+        Self { first, second }
+    }
+
+    /// Get the first tier store.
+    #[inline]
+    pub fn first(&self) -> &S1 {
+        &self.first
+    }
+
+    /// Get the second tier store.
+    #[inline]
+    pub fn second(&self) -> &S2 {
+        &self.second
+    }
+}
+
+impl<S1, S2, K> CacheStore for TieredStore<S1, S2>
+where
+    S1: CacheStore<Key = K>,
+    S2: CacheStore<Key = K>,
+    K: Hash + Eq + Send + Sync + Clone + 'static,
+{
+    type Error = Error;
+    type Key = K;
+
+    async fn load_entry<Q>(&self, key: &Q) -> Option<CachedEntry>
+    where
+        Self::Key: Borrow<Q>,
+        Q: Hash + Eq + Sync,
+    {
+        match self.first.load_entry(key).await {
+            Some(entry) => Some(entry),
+            None => self.second.load_entry(key).await,
+        }
+    }
+
+    async fn load_entry_by_key(&self, key: &Self::Key) -> Option<CachedEntry> {
+        if let Some(entry) = self.first.load_entry_by_key(key).await {
+            return Some(entry);
+        }
+        let entry = self.second.load_entry_by_key(key).await?;
+        if let Err(e) = self.first.save_entry(key.clone(), entry.clone()).await {
+            tracing::warn!(error = ?e, "promote cache entry failed");
+        }
+        Some(entry)
+    }
+
+    async fn save_entry(&self, key: Self::Key, entry: CachedEntry) -> Result<(), Self::Error> {
+        let first = self.first.save_entry(key.clone(), entry.clone()).await;
+        self.second
+            .save_entry(key, entry)
+            .await
+            .map_err(Error::other)?;
+        first.map_err(Error::other)
+    }
+}
+
+#[cfg(all(test, feature = "moka-store"))]
+mod tests {
+    use std::time::Duration;
+
+    use salvo_core::http::HeaderMap;
+
+    use super::*;
+    use crate::{CachedBody, MokaStore};
+
+    #[tokio::test]
+    async fn test_tiered_store_promote() {
+        let store = TieredStore::new(
+            MokaStore::builder()
+                .time_to_live(Duration::from_millis(200))
+                .build(),
+            MokaStore::builder()
+                .time_to_live(Duration::from_secs(60))
+                .build(),
+        );
+        let key = "key".to_owned();
+        let entry = CachedEntry::new(None, HeaderMap::new(), CachedBody::Once("hello".into()));
+        store.save_entry(key.clone(), entry).await.unwrap();
+        assert!(store.first().load_entry(&key).await.is_some());
+
+        tokio::time::sleep(Duration::from_millis(300)).await;
+        assert!(store.first().load_entry(&key).await.is_none());
+
+        let entry = store.load_entry_by_key(&key).await.unwrap();
+        assert!(matches!(entry.body, CachedBody::Once(bytes) if bytes == "hello"));
+        assert!(store.first().load_entry(&key).await.is_some());
+    }
+}
@@ -0,0 +1,165 @@
+//! Decompress the body of a request.
+use std::io::{Result as IoResult, Write};
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::task::{ready, Context, Poll};
+
+#[cfg(feature = "brotli")]
+use brotli::DecompressorWriter as BrotliDecoder;
+use bytes::Bytes;
+#[cfg(feature = "gzip")]
+use flate2::write::GzDecoder;
+#[cfg(feature = "deflate")]
+use flate2::write::ZlibDecoder;
+#[cfg(feature = "zstd")]
+use zstd::stream::write::Decoder as ZstdDecoder;
+
+use salvo_core::http::body::{Body, Frame, ReqBody, SizeHint};
+use salvo_core::BoxedError;
+
+use super::encoder::Writer;
+use super::CompressionAlgo;
+
+pub(super) enum Decoder {
+    #[cfg(feature = "brotli")]
+    Brotli(Box<BrotliDecoder<Writer>>),
+    #[cfg(feature = "deflate")]
+    Deflate(ZlibDecoder<Writer>),
+    #[cfg(feature = "gzip")]
+    Gzip(GzDecoder<Writer>),
+    #[cfg(feature = "zstd")]
+    Zstd(ZstdDecoder<'static, Writer>),
+}
+
+impl Decoder {
+    pub(super) fn new(algo: CompressionAlgo) -> IoResult<Self> {
+        Ok(match algo {
+            #[cfg(feature = "brotli")]
+            CompressionAlgo::Brotli => {
+                Self::Brotli(Box::new(BrotliDecoder::new(Writer::new(), 32 * 1024)))
+            }
+            #[cfg(feature = "deflate")]
+            CompressionAlgo::Deflate => Self::Deflate(ZlibDecoder::new(Writer::new())),
+            #[cfg(feature = "gzip")]
+            CompressionAlgo::Gzip => Self::Gzip(GzDecoder::new(Writer::new())),
+            #[cfg(feature = "zstd")]
+            CompressionAlgo::Zstd => Self::Zstd(ZstdDecoder::new(Writer::new())?),
+        })
+    }
+
+    fn writer(&mut self) -> &mut Writer {
+        match self {
+            #[cfg(feature = "brotli")]
+            Self::Brotli(decoder) => decoder.get_mut(),
+            #[cfg(feature = "deflate")]
+            Self::Deflate(decoder) => decoder.get_mut(),
+            #[cfg(feature = "gzip")]
+            Self::Gzip(decoder) => decoder.get_mut(),
+            #[cfg(feature = "zstd")]
+            Self::Zstd(decoder) => decoder.get_mut(),
+        }
+    }
+
+    pub(super) fn decode(&mut self, chunk: &[u8]) -> IoResult<Bytes> {
+        match self {
+            #[cfg(feature = "brotli")]
+            Self::Brotli(decoder) => {
+                decoder.write_all(chunk)?;
+                decoder.flush()?;
+            }
+            #[cfg(feature = "deflate")]
+            Self::Deflate(decoder) => {
+                decoder.write_all(chunk)?;
+                decoder.flush()?;
+            }
+            #[cfg(feature = "gzip")]
+            Self::Gzip(decoder) => {
+                decoder.write_all(chunk)?;
+                decoder.flush()?;
+            }
+            #[cfg(feature = "zstd")]
+            Self::Zstd(decoder) => {
+                decoder.write_all(chunk)?;
+                decoder.flush()?;
+            }
+        }
+        Ok(self.writer().take())
+    }
+
+    pub(super) fn finish(&mut self) -> IoResult<Bytes> {
+        match self {
+            #[cfg(feature = "brotli")]
+            Self::Brotli(decoder) => decoder.close()?,
+            #[cfg(feature = "deflate")]
+            Self::Deflate(decoder) => decoder.try_finish()?,
+            #[cfg(feature = "gzip")]
+            Self::Gzip(decoder) => decoder.try_finish()?,
+            #[cfg(feature = "zstd")]
+            Self::Zstd(decoder) => decoder.flush()?,
+        }
+        Ok(self.writer().take())
+    }
+}
+
+/// Request body decoded while it is read.
+pub(super) struct DecodeBody {
+    body: ReqBody,
+    // The decoders are not all `Sync`, which is required by boxed request bodies.
+    decoder: Mutex<Decoder>,
+    eof: bool,
+}
+
+impl DecodeBody {
+    pub(super) fn new(body: ReqBody, decoder: Decoder) -> Self {
+        Self {
+            body,
+            decoder: Mutex::new(decoder),
+            eof: false,
+        }
+    }
+}
+
+impl Body for DecodeBody {
+    type Data = Bytes;
+    type Error = BoxedError;
+
+    fn poll_frame(
+        self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let this = self.get_mut();
+        loop {
+            if this.eof {
+                return Poll::Ready(None);
+            }
+            let decoder = this.decoder.get_mut().expect("decoder lock poisoned");
+            match ready!(Pin::new(&mut this.body).poll_frame(cx)) {
+                Some(Ok(frame)) => match frame.into_data() {
+                    Ok(chunk) => {
+                        let data = decoder.decode(&chunk)?;
+                        if !data.is_empty() {
+                            return Poll::Ready(Some(Ok(Frame::data(data))));
+                        }
+                    }
+                    Err(frame) => return Poll::Ready(Some(Ok(frame))),
+                },
+                Some(Err(e)) => return Poll::Ready(Some(Err(e.into()))),
+                None => {
+                    this.eof = true;
+                    let data = decoder.finish()?;
+                    if !data.is_empty() {
+                        return Poll::Ready(Some(Ok(Frame::data(data))));
+                    }
+                }
+            }
+        }
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.eof
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        SizeHint::default()
+    }
+}
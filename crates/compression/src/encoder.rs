@@ -19,14 +19,14 @@ pub(super) struct Writer {
 
 impl Writer {
     #[allow(dead_code)]
-    fn new() -> Writer {
+    pub(super) fn new() -> Writer {
         Writer {
             buf: BytesMut::with_capacity(8192),
         }
     }
 
     #[allow(dead_code)]
-    fn take(&mut self) -> Bytes {
+    pub(super) fn take(&mut self) -> Bytes {
         self.buf.split().freeze()
     }
 }
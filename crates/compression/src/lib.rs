@@ -6,18 +6,23 @@
 
 use std::fmt::{self, Display, Formatter};
 use std::str::FromStr;
+use std::sync::Arc;
 
 use indexmap::IndexMap;
 
-use salvo_core::http::body::ResBody;
+use salvo_core::handler::{none_skipper, Skipper};
+use salvo_core::http::body::{ReqBody, ResBody};
 use salvo_core::http::header::{
-    HeaderValue, ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_LENGTH, CONTENT_TYPE,
+    HeaderValue, ACCEPT_ENCODING, ACCEPT_RANGES, CACHE_CONTROL, CONTENT_ENCODING, CONTENT_LENGTH,
+    CONTENT_RANGE, CONTENT_TYPE,
 };
-use salvo_core::http::{self, mime, Mime, StatusCode};
+use salvo_core::http::{self, mime, Mime, StatusCode, StatusError};
 use salvo_core::{async_trait, Depot, FlowCtrl, Handler, Request, Response};
 
+mod decoder;
 mod encoder;
 mod stream;
+use decoder::{DecodeBody, Decoder};
 use encoder::Encoder;
 use stream::EncodeStream;
 
@@ -121,18 +126,67 @@ impl From<CompressionAlgo> for HeaderValue {
     }
 }
 
+/// Key used to record in depot that the response should not be compressed.
+pub const DISABLE_COMPRESSION_KEY: &str = "::salvo::compression::disabled";
+
+/// Extension for Depot.
+pub trait CompressionDepotExt {
+    /// Disables compression of the current response, even if [`Compression`] is in the chain.
+    ///
+    /// It is useful for handlers whose output is already compressed or should be sent as is.
+    fn disable_compression(&mut self) -> &mut Self;
+    /// Returns `true` if compression of the current response is disabled.
+    fn is_compression_disabled(&self) -> bool;
+}
+
+impl CompressionDepotExt for Depot {
+    #[inline]
+    fn disable_compression(&mut self) -> &mut Self {
+        self.insert(DISABLE_COMPRESSION_KEY, true);
+        self
+    }
+    #[inline]
+    fn is_compression_disabled(&self) -> bool {
+        self.get::<bool>(DISABLE_COMPRESSION_KEY)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
 /// Compression
-#[derive(Clone, Debug)]
+///
+/// Responses of handlers calling [`CompressionDepotExt::disable_compression`] are not compressed.
+///
+/// Partial responses (`206 Partial Content` or with a `Content-Range` header) are never
+/// compressed, because the range is relative to the uncompressed content. Compressed responses
+/// are streamed without `Content-Length` and are sent with `Accept-Ranges: none` if the handler
+/// advertised range support.
+#[derive(Clone)]
 #[non_exhaustive]
 pub struct Compression {
     /// Compression algorithms to use.
     pub algos: IndexMap<CompressionAlgo, CompressionLevel>,
     /// Content types to compress.
     pub content_types: Vec<Mime>,
+    /// Content types never compressed, even if they match [`content_types`](Self::content_types).
+    pub excluded_content_types: Vec<Mime>,
     /// Sets minimum compression size, if body less than this value, no compression.
     pub min_length: usize,
     /// Ignore request algorithms order in `Accept-Encoding` header and always server's config.
     pub force_priority: bool,
+    /// Skipper used to bypass compression for some requests.
+    pub skipper: Arc<dyn Skipper>,
+}
+impl fmt::Debug for Compression {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Compression")
+            .field("algos", &self.algos)
+            .field("content_types", &self.content_types)
+            .field("excluded_content_types", &self.excluded_content_types)
+            .field("min_length", &self.min_length)
+            .field("force_priority", &self.force_priority)
+            .finish()
+    }
 }
 
 impl Default for Compression {
@@ -158,8 +212,10 @@ impl Default for Compression {
                 "application/xml".parse().expect("invalid mime type"),
                 "application/rss+xml".parse().expect("invalid mime type"),
             ],
+            excluded_content_types: vec![mime::TEXT_EVENT_STREAM],
             min_length: 0,
             force_priority: false,
+            skipper: Arc::new(none_skipper),
         }
     }
 }
@@ -259,6 +315,14 @@ impl Compression {
         self
     }
 
+    /// Sets skipper, skipped requests are passed to the next handlers and their responses are
+    /// never compressed.
+    #[inline]
+    pub fn skipper(mut self, skipper: impl Skipper) -> Self {
+        self.skipper = Arc::new(skipper);
+        self
+    }
+
     /// Sets `Compression` with content types list.
     #[inline]
     pub fn content_types(mut self, content_types: &[Mime]) -> Self {
@@ -266,32 +330,50 @@ impl Compression {
         self
     }
 
+    /// Sets content types which are never compressed, the default is `text/event-stream`,
+    /// because encoders buffer data and would delay the events.
+    #[inline]
+    pub fn excluded_content_types(mut self, content_types: &[Mime]) -> Self {
+        self.excluded_content_types = content_types.to_vec();
+        self
+    }
+
     fn negotiate(
         &self,
         req: &Request,
         res: &Response,
     ) -> Option<(CompressionAlgo, CompressionLevel)> {
-        if req.headers().contains_key(&CONTENT_ENCODING) {
+        if req.headers().contains_key(&CONTENT_ENCODING) || is_no_transform(res) {
             return None;
         }
 
-        if !self.content_types.is_empty() {
-            let content_type = res
-                .headers()
-                .get(CONTENT_TYPE)
-                .and_then(|v| v.to_str().ok())
-                .unwrap_or_default();
-            if content_type.is_empty() {
+        let content_type = res
+            .headers()
+            .get(CONTENT_TYPE)
+            .and_then(|v| v.to_str().ok())
+            .unwrap_or_default()
+            .parse::<Mime>()
+            .ok();
+        let matches = |content_type: &Mime, citem: &Mime| {
+            citem.type_() == content_type.type_()
+                && (citem.subtype() == "*" || citem.subtype() == content_type.subtype())
+        };
+        if let Some(content_type) = &content_type {
+            if self
+                .excluded_content_types
+                .iter()
+                .any(|citem| matches(content_type, citem))
+            {
                 return None;
             }
-            if let Ok(content_type) = content_type.parse::<Mime>() {
-                if !self.content_types.iter().any(|citem| {
-                    citem.type_() == content_type.type_()
-                        && (citem.subtype() == "*" || citem.subtype() == content_type.subtype())
-                }) {
-                    return None;
-                }
-            } else {
+        }
+        if !self.content_types.is_empty() {
+            let content_type = content_type?;
+            if !self
+                .content_types
+                .iter()
+                .any(|citem| matches(&content_type, citem))
+            {
                 return None;
             }
         }
@@ -327,6 +409,18 @@ impl Compression {
     }
 }
 
+/// Returns `true` if the response has the `Cache-Control: no-transform` directive, which is used
+/// by streaming responses that must not be buffered by intermediaries.
+fn is_no_transform(res: &Response) -> bool {
+    res.headers().get_all(CACHE_CONTROL).iter().any(|value| {
+        value.to_str().is_ok_and(|value| {
+            value
+                .split(',')
+                .any(|directive| directive.trim().eq_ignore_ascii_case("no-transform"))
+        })
+    })
+}
+
 #[async_trait]
 impl Handler for Compression {
     async fn handle(
@@ -336,16 +430,28 @@ impl Handler for Compression {
         res: &mut Response,
         ctrl: &mut FlowCtrl,
     ) {
+        if self.skipper.skipped(req, depot) {
+            return;
+        }
         ctrl.call_next(req, depot, res).await;
-        if ctrl.is_ceased() || res.headers().contains_key(CONTENT_ENCODING) {
+        if ctrl.is_ceased()
+            || depot.is_compression_disabled()
+            || res.headers().contains_key(CONTENT_ENCODING)
+        {
             return;
         }
 
         if let Some(code) = res.status_code {
-            if code == StatusCode::SWITCHING_PROTOCOLS || code == StatusCode::NO_CONTENT {
+            if code == StatusCode::SWITCHING_PROTOCOLS
+                || code == StatusCode::NO_CONTENT
+                || code == StatusCode::PARTIAL_CONTENT
+            {
                 return;
             }
         }
+        if res.headers().contains_key(CONTENT_RANGE) {
+            return;
+        }
 
         match res.take_body() {
             ResBody::None => {
@@ -415,6 +521,117 @@ impl Handler for Compression {
             }
         }
         res.headers_mut().remove(CONTENT_LENGTH);
+        if res.headers().contains_key(ACCEPT_RANGES) {
+            res.headers_mut()
+                .insert(ACCEPT_RANGES, HeaderValue::from_static("none"));
+        }
+    }
+}
+
+/// Decompression middleware decoding request bodies sent with the `Content-Encoding` header.
+///
+/// The request body is decoded while it is read, the `Content-Encoding` and `Content-Length`
+/// headers are removed, so the next handlers see the plain body. Requests with an encoding
+/// which is not enabled are rejected with `415 Unsupported Media Type`.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct Decompression {
+    /// Decompression algorithms which are accepted.
+    pub algos: Vec<CompressionAlgo>,
+}
+
+impl Default for Decompression {
+    #[allow(clippy::vec_init_then_push)]
+    fn default() -> Self {
+        #[allow(unused_mut)]
+        let mut algos = Vec::new();
+        #[cfg(feature = "zstd")]
+        algos.push(CompressionAlgo::Zstd);
+        #[cfg(feature = "gzip")]
+        algos.push(CompressionAlgo::Gzip);
+        #[cfg(feature = "deflate")]
+        algos.push(CompressionAlgo::Deflate);
+        #[cfg(feature = "brotli")]
+        algos.push(CompressionAlgo::Brotli);
+        Self { algos }
+    }
+}
+
+impl Decompression {
+    /// Create a new `Decompression` accepting all enabled algorithms.
+    #[inline]
+    pub fn new() -> Self {
+        Default::default()
+    }
+
+    /// Sets the accepted algorithms.
+    #[inline]
+    pub fn algos(mut self, algos: impl Into<Vec<CompressionAlgo>>) -> Self {
+        self.algos = algos.into();
+        self
+    }
+
+    /// Returns the algorithms of the `Content-Encoding` header in the order they were applied,
+    /// or `None` if one of them is not accepted.
+    fn content_algos(&self, req: &Request) -> Option<Vec<CompressionAlgo>> {
+        let mut algos = Vec::new();
+        for value in req.headers().get_all(CONTENT_ENCODING) {
+            for encoding in value.to_str().ok()?.split(',') {
+                let encoding = encoding.trim().to_ascii_lowercase();
+                if encoding.is_empty() || encoding == "identity" {
+                    continue;
+                }
+                let algo = encoding.parse::<CompressionAlgo>().ok()?;
+                if !self.algos.contains(&algo) {
+                    return None;
+                }
+                algos.push(algo);
+            }
+        }
+        Some(algos)
+    }
+}
+
+#[async_trait]
+impl Handler for Decompression {
+    async fn handle(
+        &self,
+        req: &mut Request,
+        _depot: &mut Depot,
+        res: &mut Response,
+        ctrl: &mut FlowCtrl,
+    ) {
+        if !req.headers().contains_key(CONTENT_ENCODING) {
+            return;
+        }
+        let Some(algos) = self.content_algos(req) else {
+            res.render(
+                StatusError::unsupported_media_type().brief("Unsupported content encoding."),
+            );
+            ctrl.skip_rest();
+            return;
+        };
+        let mut body = req.take_body();
+        // Encodings are listed in the order they were applied, so they are decoded in reverse.
+        for algo in algos.into_iter().rev() {
+            match Decoder::new(algo) {
+                Ok(decoder) => {
+                    body = ReqBody::Boxed {
+                        inner: Box::pin(DecodeBody::new(body, decoder)),
+                        fusewire: None,
+                    };
+                }
+                Err(e) => {
+                    tracing::error!(error = ?e, "create decoder failed");
+                    res.render(StatusError::internal_server_error());
+                    ctrl.skip_rest();
+                    return;
+                }
+            }
+        }
+        req.replace_body(body);
+        req.headers_mut().remove(CONTENT_ENCODING);
+        req.headers_mut().remove(CONTENT_LENGTH);
     }
 }
 
@@ -471,4 +688,177 @@ mod tests {
         let content = res.take_string().await.unwrap();
         assert_eq!(content, "hello");
     }
+
+    #[tokio::test]
+    async fn test_skipper() {
+        let comp_handler = Compression::new()
+            .min_length(1)
+            .skipper(|req: &mut Request, _: &Depot| req.uri().path() == "/events");
+        let router = Router::with_hoop(comp_handler)
+            .push(Router::with_path("hello").get(hello))
+            .push(Router::with_path("events").get(hello));
+        let service = Service::new(router);
+
+        let res = TestClient::get("http://127.0.0.1:5801/hello")
+            .add_header(ACCEPT_ENCODING, "gzip", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.headers().get(CONTENT_ENCODING).unwrap(), "gzip");
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/events")
+            .add_header(ACCEPT_ENCODING, "gzip", true)
+            .send(&service)
+            .await;
+        assert!(res.headers().get(CONTENT_ENCODING).is_none());
+        assert_eq!(res.take_string().await.unwrap(), "hello");
+    }
+
+    #[tokio::test]
+    async fn test_decompression() {
+        use std::io::Write;
+
+        #[handler]
+        async fn echo(req: &mut Request) -> String {
+            let payload = req.payload().await.unwrap();
+            String::from_utf8(payload.to_vec()).unwrap()
+        }
+
+        let router = Router::with_hoop(Decompression::new()).post(echo);
+        let service = Service::new(router);
+
+        let mut gzip = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        gzip.write_all(b"hello world").unwrap();
+        let mut zstd = zstd::stream::write::Encoder::new(Vec::new(), 1).unwrap();
+        zstd.write_all(b"hello world").unwrap();
+        let mut brotli = brotli::CompressorWriter::new(Vec::new(), 4096, 5, 22);
+        brotli.write_all(b"hello world").unwrap();
+        let mut deflate = flate2::write::ZlibEncoder::new(Vec::new(), flate2::Compression::fast());
+        deflate.write_all(b"hello world").unwrap();
+        let cases = [
+            ("gzip", gzip.finish().unwrap()),
+            ("zstd", zstd.finish().unwrap()),
+            ("br", brotli.into_inner()),
+            ("deflate", deflate.finish().unwrap()),
+        ];
+        for (encoding, body) in cases {
+            let mut res = TestClient::post("http://127.0.0.1:5801/")
+                .add_header(CONTENT_ENCODING, encoding, true)
+                .add_header(CONTENT_TYPE, "text/plain", true)
+                .bytes(body)
+                .send(&service)
+                .await;
+            assert_eq!(
+                res.take_string().await.unwrap(),
+                "hello world",
+                "{encoding}"
+            );
+        }
+
+        let res = TestClient::post("http://127.0.0.1:5801/")
+            .add_header(CONTENT_ENCODING, "compress", true)
+            .bytes(b"hello world".to_vec())
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::UNSUPPORTED_MEDIA_TYPE));
+    }
+
+    #[tokio::test]
+    async fn test_event_stream_not_compressed() {
+        #[handler]
+        async fn events(res: &mut Response) {
+            res.add_header(CONTENT_TYPE, "text/event-stream", true)
+                .unwrap();
+            res.body("data: hello\n\n");
+        }
+        #[handler]
+        async fn no_transform(res: &mut Response) {
+            res.add_header(CACHE_CONTROL, "no-cache, no-transform", true)
+                .unwrap();
+            res.render(Text::Plain("hello"));
+        }
+
+        let router = Router::with_hoop(Compression::new())
+            .push(Router::with_path("events").get(events))
+            .push(Router::with_path("no-transform").get(no_transform));
+        let service = Service::new(router);
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/events")
+            .add_header(ACCEPT_ENCODING, "gzip", true)
+            .send(&service)
+            .await;
+        assert!(res.headers().get(CONTENT_ENCODING).is_none());
+        assert_eq!(res.take_string().await.unwrap(), "data: hello\n\n");
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/no-transform")
+            .add_header(ACCEPT_ENCODING, "gzip", true)
+            .send(&service)
+            .await;
+        assert!(res.headers().get(CONTENT_ENCODING).is_none());
+        assert_eq!(res.take_string().await.unwrap(), "hello");
+    }
+
+    #[tokio::test]
+    async fn test_partial_content_not_compressed() {
+        #[handler]
+        async fn ranged(req: &mut Request, res: &mut Response) {
+            res.add_header(ACCEPT_RANGES, "bytes", true).unwrap();
+            if req.headers().contains_key("range") {
+                res.status_code(StatusCode::PARTIAL_CONTENT);
+                res.add_header(CONTENT_RANGE, "bytes 0-4/11", true).unwrap();
+                res.render(Text::Plain("hello"));
+            } else {
+                res.render(Text::Plain("hello world"));
+            }
+        }
+
+        let router = Router::with_hoop(Compression::new().min_length(1))
+            .push(Router::with_path("ranged").get(ranged));
+        let service = Service::new(router);
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/ranged")
+            .add_header(ACCEPT_ENCODING, "gzip", true)
+            .add_header("range", "bytes=0-4", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::PARTIAL_CONTENT));
+        assert!(res.headers().get(CONTENT_ENCODING).is_none());
+        assert_eq!(res.headers().get(ACCEPT_RANGES).unwrap(), "bytes");
+        assert_eq!(res.take_string().await.unwrap(), "hello");
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/ranged")
+            .add_header(ACCEPT_ENCODING, "gzip", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.headers().get(CONTENT_ENCODING).unwrap(), "gzip");
+        assert_eq!(res.headers().get(ACCEPT_RANGES).unwrap(), "none");
+        assert!(res.headers().get(CONTENT_LENGTH).is_none());
+        assert_eq!(res.take_string().await.unwrap(), "hello world");
+    }
+
+    #[tokio::test]
+    async fn test_disable_compression() {
+        #[handler]
+        async fn optimal(depot: &mut Depot) -> &'static str {
+            depot.disable_compression();
+            "hello"
+        }
+
+        let router = Router::with_hoop(Compression::new().min_length(1))
+            .push(Router::with_path("hello").get(hello))
+            .push(Router::with_path("optimal").get(optimal));
+        let service = Service::new(router);
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/optimal")
+            .add_header(ACCEPT_ENCODING, "gzip", true)
+            .send(&service)
+            .await;
+        assert!(res.headers().get(CONTENT_ENCODING).is_none());
+        assert_eq!(res.take_string().await.unwrap(), "hello");
+
+        let res = TestClient::get("http://127.0.0.1:5801/hello")
+            .add_header(ACCEPT_ENCODING, "gzip", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.headers().get(CONTENT_ENCODING).unwrap(), "gzip");
+    }
 }
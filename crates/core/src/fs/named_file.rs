@@ -480,6 +480,16 @@ impl NamedFile {
         }
         let mut offset = 0;
 
+        // preconditions are evaluated before the range, so a resumed download of a changed file
+        // fails instead of mixing content of different versions.
+        if precondition_failed {
+            res.status_code(StatusCode::PRECONDITION_FAILED);
+            return;
+        } else if not_modified {
+            res.status_code(StatusCode::NOT_MODIFIED);
+            return;
+        }
+
         // check for range header
         let range = req_headers.get(RANGE);
         if let Some(range) = range {
@@ -499,14 +509,6 @@ impl NamedFile {
             };
         }
 
-        if precondition_failed {
-            res.status_code(StatusCode::PRECONDITION_FAILED);
-            return;
-        } else if not_modified {
-            res.status_code(StatusCode::NOT_MODIFIED);
-            return;
-        }
-
         if offset != 0 || length != self.metadata.len() || range.is_some() {
             res.status_code(StatusCode::PARTIAL_CONTENT);
             match ContentRange::bytes(offset..offset + length, self.metadata.len()) {
@@ -20,6 +20,12 @@ pub struct ExposeHeaders(ExposeHeadersInner);
 type JudgeFn = Arc<
     dyn for<'a> Fn(&'a HeaderValue, &'a Request, &'a Depot) -> HeaderValue + Send + Sync + 'static,
 >;
+type DynamicFn = Arc<
+    dyn for<'a> Fn(&'a HeaderValue, &'a Request, &'a Depot) -> Vec<HeaderName>
+        + Send
+        + Sync
+        + 'static,
+>;
 impl ExposeHeaders {
     /// Expose any / all headers by sending a wildcard (`*`)
     ///
@@ -45,11 +51,11 @@ impl ExposeHeaders {
         }
     }
 
-    /// Allow custom allow headers based on a given predicate
+    /// Allow custom expose headers based on a given predicate
     ///
-    /// See [`Cors::allow_headers`] for more details.
+    /// See [`Cors::expose_headers`] for more details.
     ///
-    /// [`Cors::allow_headers`]: super::Cors::allow_headers
+    /// [`Cors::expose_headers`]: super::Cors::expose_headers
     pub fn judge<F>(f: F) -> Self
     where
         F: Fn(&HeaderValue, &Request, &Depot) -> HeaderValue + Send + Sync + 'static,
@@ -57,6 +63,19 @@ impl ExposeHeaders {
         Self(ExposeHeadersInner::Judge(Arc::new(f)))
     }
 
+    /// Expose a list of headers computed for every request from its origin, the request and
+    /// the depot, the header is not sent if the list is empty.
+    ///
+    /// See [`Cors::expose_headers`] for more details.
+    ///
+    /// [`Cors::expose_headers`]: super::Cors::expose_headers
+    pub fn dynamic<F>(f: F) -> Self
+    where
+        F: Fn(&HeaderValue, &Request, &Depot) -> Vec<HeaderName> + Send + Sync + 'static,
+    {
+        Self(ExposeHeadersInner::Dynamic(Arc::new(f)))
+    }
+
     pub(super) fn is_wildcard(&self) -> bool {
         matches!(&self.0, ExposeHeadersInner::Exact(v) if v == WILDCARD)
     }
@@ -71,6 +90,9 @@ impl ExposeHeaders {
             ExposeHeadersInner::None => return None,
             ExposeHeadersInner::Exact(v) => v.clone(),
             ExposeHeadersInner::Judge(f) => f(origin?, req, depot),
+            ExposeHeadersInner::Dynamic(f) => {
+                separated_by_commas(f(origin?, req, depot).into_iter().map(Into::into))?
+            }
         };
 
         Some((header::ACCESS_CONTROL_EXPOSE_HEADERS, expose_headers))
@@ -83,6 +105,7 @@ impl Debug for ExposeHeaders {
             ExposeHeadersInner::None => f.debug_tuple("None").finish(),
             ExposeHeadersInner::Exact(inner) => f.debug_tuple("Exact").field(inner).finish(),
             ExposeHeadersInner::Judge(_) => f.debug_tuple("Judge").finish(),
+            ExposeHeadersInner::Dynamic(_) => f.debug_tuple("Dynamic").finish(),
         }
     }
 }
@@ -142,4 +165,5 @@ enum ExposeHeadersInner {
     None,
     Exact(HeaderValue),
     Judge(JudgeFn),
+    Dynamic(DynamicFn),
 }
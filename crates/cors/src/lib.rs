@@ -42,11 +42,13 @@ mod allow_methods;
 mod allow_origin;
 mod expose_headers;
 mod max_age;
+mod timing_allow_origin;
 mod vary;
 
 pub use self::{
     allow_credentials::AllowCredentials, allow_headers::AllowHeaders, allow_methods::AllowMethods,
-    allow_origin::AllowOrigin, expose_headers::ExposeHeaders, max_age::MaxAge, vary::Vary,
+    allow_origin::AllowOrigin, expose_headers::ExposeHeaders, max_age::MaxAge,
+    timing_allow_origin::TimingAllowOrigin, vary::Vary,
 };
 
 static WILDCARD: HeaderValue = HeaderValue::from_static("*");
@@ -87,7 +89,9 @@ pub struct Cors {
     allow_origin: AllowOrigin,
     expose_headers: ExposeHeaders,
     max_age: MaxAge,
+    timing_allow_origin: TimingAllowOrigin,
     vary: Vary,
+    same_origin_headers: bool,
 }
 impl Default for Cors {
     #[inline]
@@ -107,7 +111,9 @@ impl Cors {
             allow_origin: Default::default(),
             expose_headers: Default::default(),
             max_age: Default::default(),
+            timing_allow_origin: Default::default(),
             vary: Default::default(),
+            same_origin_headers: true,
         }
     }
 
@@ -205,6 +211,8 @@ impl Cors {
 
     /// Set the value of the [`Access-Control-Expose-Headers`][mdn] header.
     ///
+    /// Use [`ExposeHeaders::dynamic`] to expose headers depending on the request.
+    ///
     /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Access-Control-Expose-Headers
     #[inline]
     pub fn expose_headers(mut self, headers: impl Into<ExposeHeaders>) -> Self {
@@ -212,6 +220,29 @@ impl Cors {
         self
     }
 
+    /// Set the value of the [`Timing-Allow-Origin`][mdn] header.
+    ///
+    /// The header is only added to non-preflight responses. Use
+    /// [`TimingAllowOrigin::mirror_allow_origin`] to echo the origin accepted by
+    /// [`Cors::allow_origin`].
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use salvo_cors::{Cors, TimingAllowOrigin};
+    ///
+    /// let cors = Cors::new()
+    ///     .allow_origin("https://salvo.rs")
+    ///     .timing_allow_origin(TimingAllowOrigin::mirror_allow_origin());
+    /// ```
+    ///
+    /// [mdn]: https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Timing-Allow-Origin
+    #[inline]
+    pub fn timing_allow_origin(mut self, origin: impl Into<TimingAllowOrigin>) -> Self {
+        self.timing_allow_origin = origin.into();
+        self
+    }
+
     /// Set the value(s) of the [`Vary`][mdn] header.
     ///
     /// In contrast to the other headers, this one has a non-empty default of
@@ -227,6 +258,20 @@ impl Cors {
         self
     }
 
+    /// Sets whether to add CORS headers to same-origin requests.
+    ///
+    /// A request is considered same-origin when the scheme, host and port of its `Origin` header
+    /// match the request. The scheme of the request is taken from the `X-Forwarded-Proto` or
+    /// `Forwarded` header when present, so it is still right behind a TLS terminating proxy.
+    /// The `Vary` header is still added, so caches keep responses for different origins apart.
+    ///
+    /// Default is `true`.
+    #[inline]
+    pub fn same_origin_headers(mut self, same_origin_headers: bool) -> Self {
+        self.same_origin_headers = same_origin_headers;
+        self
+    }
+
     /// Returns a new `CorsHandler` using current cors settings.
     pub fn into_handler(self) -> CorsHandler {
         self.ensure_usable_cors_rules();
@@ -301,11 +346,14 @@ impl Handler for CorsHandler {
 
         let origin = req.headers().get(&header::ORIGIN);
         let mut headers = HeaderMap::new();
+        let skip_cors_headers = !self.cors.same_origin_headers && is_same_origin(origin, req);
 
-        // These headers are applied to both preflight and subsequent regular CORS requests:
-        // https://fetch.spec.whatwg.org/#http-responses
-        headers.extend(self.cors.allow_origin.to_header(origin, req, depot));
-        headers.extend(self.cors.allow_credentials.to_header(origin, req, depot));
+        if !skip_cors_headers {
+            // These headers are applied to both preflight and subsequent regular CORS requests:
+            // https://fetch.spec.whatwg.org/#http-responses
+            headers.extend(self.cors.allow_origin.to_header(origin, req, depot));
+            headers.extend(self.cors.allow_credentials.to_header(origin, req, depot));
+        }
 
         let mut vary_headers = self.cors.vary.values();
         if let Some(first) = vary_headers.next() {
@@ -324,13 +372,21 @@ impl Handler for CorsHandler {
         // Return results immediately upon preflight request
         if req.method() == Method::OPTIONS {
             // These headers are applied only to preflight requests
-            headers.extend(self.cors.allow_methods.to_header(origin, req, depot));
-            headers.extend(self.cors.allow_headers.to_header(origin, req, depot));
-            headers.extend(self.cors.max_age.to_header(origin, req, depot));
+            if !skip_cors_headers {
+                headers.extend(self.cors.allow_methods.to_header(origin, req, depot));
+                headers.extend(self.cors.allow_headers.to_header(origin, req, depot));
+                headers.extend(self.cors.max_age.to_header(origin, req, depot));
+            }
             res.status_code = Some(StatusCode::NO_CONTENT);
-        } else {
-            // This header is applied only to non-preflight requests
+        } else if !skip_cors_headers {
+            // These headers are applied only to non-preflight requests
             headers.extend(self.cors.expose_headers.to_header(origin, req, depot));
+            let allow_origin = headers.get(header::ACCESS_CONTROL_ALLOW_ORIGIN).cloned();
+            headers.extend(
+                self.cors
+                    .timing_allow_origin
+                    .to_header(allow_origin.as_ref()),
+            );
         }
         res.headers_mut().extend(headers);
 
@@ -340,6 +396,66 @@ impl Handler for CorsHandler {
     }
 }
 
+fn is_same_origin(origin: Option<&HeaderValue>, req: &Request) -> bool {
+    let Some(origin) = origin
+        .and_then(|o| o.to_str().ok())
+        .and_then(|o| o.parse::<salvo_core::http::uri::Uri>().ok())
+    else {
+        return false;
+    };
+    let (Some(origin_scheme), Some(origin_authority)) = (origin.scheme_str(), origin.authority())
+    else {
+        return false;
+    };
+    let scheme = forwarded_proto(req).unwrap_or_else(|| req.scheme().as_str());
+    if !origin_scheme.eq_ignore_ascii_case(scheme) {
+        return false;
+    }
+    let Some(host) = req
+        .headers()
+        .get(header::HOST)
+        .and_then(|host| host.to_str().ok())
+        .and_then(|host| host.parse::<salvo_core::http::uri::Authority>().ok())
+        .or_else(|| req.uri().authority().cloned())
+    else {
+        return false;
+    };
+    host.host().eq_ignore_ascii_case(origin_authority.host())
+        && effective_port(host.port_u16(), scheme)
+            == effective_port(origin_authority.port_u16(), origin_scheme)
+}
+
+/// Returns the scheme the client used according to `X-Forwarded-Proto` or `Forwarded`.
+fn forwarded_proto(req: &Request) -> Option<&str> {
+    let headers = req.headers();
+    if let Some(proto) = headers
+        .get("x-forwarded-proto")
+        .and_then(|value| value.to_str().ok())
+    {
+        return proto.split(',').next().map(str::trim);
+    }
+    let forwarded = headers.get(header::FORWARDED)?.to_str().ok()?;
+    forwarded
+        .split(',')
+        .next()?
+        .split(';')
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(key, _)| key.eq_ignore_ascii_case("proto"))
+        .map(|(_, value)| value.trim_matches('"'))
+}
+
+fn effective_port(port: Option<u16>, scheme: &str) -> Option<u16> {
+    port.or_else(|| {
+        if scheme.eq_ignore_ascii_case("https") || scheme.eq_ignore_ascii_case("wss") {
+            Some(443)
+        } else if scheme.eq_ignore_ascii_case("http") || scheme.eq_ignore_ascii_case("ws") {
+            Some(80)
+        } else {
+            None
+        }
+    })
+}
+
 /// Iterator over the three request headers that may be involved in a CORS preflight request.
 ///
 /// This is the default set of header names returned in the `vary` header
@@ -411,4 +527,192 @@ mod tests {
         );
         assert!(headers.get(ACCESS_CONTROL_ALLOW_HEADERS).is_none());
     }
+
+    #[tokio::test]
+    async fn test_dynamic_expose_headers() {
+        #[handler]
+        async fn hello() -> &'static str {
+            "hello"
+        }
+
+        let cors_handler = Cors::new()
+            .allow_origin(vec!["https://salvo.rs", "https://admin.salvo.rs"])
+            .expose_headers(ExposeHeaders::dynamic(|origin, req, _depot| {
+                let mut headers = vec![];
+                if origin == "https://admin.salvo.rs" {
+                    headers.push(HeaderName::from_static("x-admin-token"));
+                }
+                if req.uri().path().starts_with("/files") {
+                    headers.push(CONTENT_DISPOSITION);
+                }
+                headers
+            }))
+            .into_handler();
+        let router = Router::new()
+            .hoop(cors_handler)
+            .push(Router::with_path("hello").goal(hello))
+            .push(Router::with_path("files").goal(hello));
+        let service = Service::new(router);
+
+        let expose_headers = |path: &'static str, origin: &'static str| {
+            let service = &service;
+            async move {
+                TestClient::get(format!("http://127.0.0.1:5801/{path}"))
+                    .add_header("Origin", origin, true)
+                    .send(service)
+                    .await
+                    .headers()
+                    .get(ACCESS_CONTROL_EXPOSE_HEADERS)
+                    .map(|v| v.to_str().unwrap().to_owned())
+            }
+        };
+
+        assert_eq!(expose_headers("hello", "https://salvo.rs").await, None);
+        assert_eq!(
+            expose_headers("hello", "https://admin.salvo.rs")
+                .await
+                .as_deref(),
+            Some("x-admin-token")
+        );
+        assert_eq!(
+            expose_headers("files", "https://salvo.rs").await.as_deref(),
+            Some("content-disposition")
+        );
+        assert_eq!(
+            expose_headers("files", "https://admin.salvo.rs")
+                .await
+                .as_deref(),
+            Some("x-admin-token,content-disposition")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_same_origin_headers() {
+        #[handler]
+        async fn hello() -> &'static str {
+            "hello"
+        }
+
+        let cors_handler = Cors::new()
+            .allow_origin(vec!["https://salvo.rs", "https://api.salvo.rs"])
+            .same_origin_headers(false)
+            .into_handler();
+        let router = Router::new()
+            .hoop(cors_handler)
+            .push(Router::with_path("hello").goal(hello));
+        let service = Service::new(router);
+
+        let res = TestClient::get("https://api.salvo.rs/hello")
+            .add_header("Origin", "https://api.salvo.rs", true)
+            .send(&service)
+            .await;
+        assert!(res.headers().get(ACCESS_CONTROL_ALLOW_ORIGIN).is_none());
+        assert!(res.headers().get(VARY).is_some());
+
+        let res = TestClient::get("https://api.salvo.rs/hello")
+            .add_header("Origin", "https://salvo.rs", true)
+            .send(&service)
+            .await;
+        assert_eq!(
+            res.headers().get(ACCESS_CONTROL_ALLOW_ORIGIN).unwrap(),
+            "https://salvo.rs"
+        );
+
+        let res = TestClient::get("https://api.salvo.rs/hello")
+            .add_header("Origin", "https://api.salvo.rs:443", true)
+            .send(&service)
+            .await;
+        assert!(res.headers().get(ACCESS_CONTROL_ALLOW_ORIGIN).is_none());
+
+        let res = TestClient::get("http://api.salvo.rs/hello")
+            .add_header("Origin", "https://api.salvo.rs", true)
+            .add_header("X-Forwarded-Proto", "https", true)
+            .send(&service)
+            .await;
+        assert!(res.headers().get(ACCESS_CONTROL_ALLOW_ORIGIN).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_same_origin_headers_compare_scheme_and_port() {
+        #[handler]
+        async fn hello() -> &'static str {
+            "hello"
+        }
+
+        let cors_handler = Cors::new()
+            .allow_origin(vec!["http://api.salvo.rs", "https://api.salvo.rs:8443"])
+            .same_origin_headers(false)
+            .into_handler();
+        let router = Router::new()
+            .hoop(cors_handler)
+            .push(Router::with_path("hello").goal(hello));
+        let service = Service::new(router);
+
+        let res = TestClient::get("https://api.salvo.rs/hello")
+            .add_header("Origin", "http://api.salvo.rs", true)
+            .send(&service)
+            .await;
+        assert_eq!(
+            res.headers().get(ACCESS_CONTROL_ALLOW_ORIGIN).unwrap(),
+            "http://api.salvo.rs"
+        );
+
+        let res = TestClient::get("https://api.salvo.rs/hello")
+            .add_header("Origin", "https://api.salvo.rs:8443", true)
+            .send(&service)
+            .await;
+        assert_eq!(
+            res.headers().get(ACCESS_CONTROL_ALLOW_ORIGIN).unwrap(),
+            "https://api.salvo.rs:8443"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_timing_allow_origin() {
+        #[handler]
+        async fn hello() -> &'static str {
+            "hello"
+        }
+
+        let cors_handler = Cors::new()
+            .allow_origin(vec!["https://salvo.rs", "https://api.salvo.rs"])
+            .timing_allow_origin(TimingAllowOrigin::mirror_allow_origin())
+            .into_handler();
+        let router = Router::new()
+            .hoop(cors_handler)
+            .push(Router::with_path("hello").goal(hello));
+        let service = Service::new(router);
+
+        let res = TestClient::get("http://127.0.0.1:5801/hello")
+            .add_header("Origin", "https://salvo.rs", true)
+            .send(&service)
+            .await;
+        assert_eq!(
+            res.headers().get("timing-allow-origin").unwrap(),
+            "https://salvo.rs"
+        );
+
+        let res = TestClient::get("http://127.0.0.1:5801/hello")
+            .add_header("Origin", "https://google.com", true)
+            .send(&service)
+            .await;
+        assert!(res.headers().get("timing-allow-origin").is_none());
+
+        let res = TestClient::options("http://127.0.0.1:5801/hello")
+            .add_header("Origin", "https://salvo.rs", true)
+            .add_header("Access-Control-Request-Method", "GET", true)
+            .send(&service)
+            .await;
+        assert!(res.headers().get("timing-allow-origin").is_none());
+
+        let cors_handler = Cors::new().timing_allow_origin(Any).into_handler();
+        let router = Router::new()
+            .hoop(cors_handler)
+            .push(Router::with_path("hello").goal(hello));
+        let res = TestClient::get("http://127.0.0.1:5801/hello")
+            .add_header("Origin", "https://google.com", true)
+            .send(router)
+            .await;
+        assert_eq!(res.headers().get("timing-allow-origin").unwrap(), "*");
+    }
 }
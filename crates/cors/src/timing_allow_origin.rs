@@ -0,0 +1,104 @@
+use std::fmt::{self, Debug, Formatter};
+
+use salvo_core::http::header::{HeaderName, HeaderValue};
+
+use super::{Any, WILDCARD};
+
+static TIMING_ALLOW_ORIGIN: HeaderName = HeaderName::from_static("timing-allow-origin");
+
+/// Holds configuration for how to set the [`Timing-Allow-Origin`][mdn] header.
+///
+/// See [`Cors::timing_allow_origin`] for more details.
+///
+/// [mdn]: https://developer.mozilla.org/en-US/docs/Web/HTTP/Headers/Timing-Allow-Origin
+/// [`Cors::timing_allow_origin`]: super::Cors::timing_allow_origin
+#[derive(Clone, Default)]
+#[must_use]
+pub struct TimingAllowOrigin(TimingAllowOriginInner);
+
+impl TimingAllowOrigin {
+    /// Allow any origin to see timing information by sending a wildcard (`*`).
+    ///
+    /// See [`Cors::timing_allow_origin`] for more details.
+    ///
+    /// [`Cors::timing_allow_origin`]: super::Cors::timing_allow_origin
+    pub fn any() -> Self {
+        Self(TimingAllowOriginInner::Exact(WILDCARD.clone()))
+    }
+
+    /// Set a single origin which can see timing information.
+    ///
+    /// See [`Cors::timing_allow_origin`] for more details.
+    ///
+    /// [`Cors::timing_allow_origin`]: super::Cors::timing_allow_origin
+    pub fn exact(origin: HeaderValue) -> Self {
+        Self(TimingAllowOriginInner::Exact(origin))
+    }
+
+    /// Echo the request origin, but only when it is allowed by [`Cors::allow_origin`].
+    ///
+    /// See [`Cors::timing_allow_origin`] for more details.
+    ///
+    /// [`Cors::allow_origin`]: super::Cors::allow_origin
+    /// [`Cors::timing_allow_origin`]: super::Cors::timing_allow_origin
+    pub fn mirror_allow_origin() -> Self {
+        Self(TimingAllowOriginInner::MirrorAllowOrigin)
+    }
+
+    pub(super) fn to_header(
+        &self,
+        allow_origin: Option<&HeaderValue>,
+    ) -> Option<(HeaderName, HeaderValue)> {
+        let value = match &self.0 {
+            TimingAllowOriginInner::None => return None,
+            TimingAllowOriginInner::Exact(v) => v.clone(),
+            TimingAllowOriginInner::MirrorAllowOrigin => allow_origin?.clone(),
+        };
+
+        Some((TIMING_ALLOW_ORIGIN.clone(), value))
+    }
+}
+
+impl Debug for TimingAllowOrigin {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            TimingAllowOriginInner::None => f.debug_tuple("None").finish(),
+            TimingAllowOriginInner::Exact(inner) => f.debug_tuple("Exact").field(inner).finish(),
+            TimingAllowOriginInner::MirrorAllowOrigin => {
+                f.debug_tuple("MirrorAllowOrigin").finish()
+            }
+        }
+    }
+}
+
+impl From<Any> for TimingAllowOrigin {
+    fn from(_: Any) -> Self {
+        Self::any()
+    }
+}
+
+impl From<HeaderValue> for TimingAllowOrigin {
+    fn from(val: HeaderValue) -> Self {
+        Self::exact(val)
+    }
+}
+
+impl From<&str> for TimingAllowOrigin {
+    fn from(val: &str) -> Self {
+        Self::exact(HeaderValue::from_str(val).expect("invalid `HeaderValue`"))
+    }
+}
+
+impl From<&String> for TimingAllowOrigin {
+    fn from(val: &String) -> Self {
+        Self::exact(HeaderValue::from_str(val).expect("invalid `HeaderValue`"))
+    }
+}
+
+#[derive(Clone, Default)]
+enum TimingAllowOriginInner {
+    #[default]
+    None,
+    Exact(HeaderValue),
+    MirrorAllowOrigin,
+}
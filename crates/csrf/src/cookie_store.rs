@@ -19,6 +19,10 @@ pub struct CookieStore {
     pub path: String,
     /// CSRF cookie domain.
     pub domain: Option<String>,
+    /// CSRF cookie SameSite attribute.
+    pub same_site: SameSite,
+    /// CSRF cookie Secure attribute, it is set when the request scheme is `https` if it is `None`.
+    pub secure: Option<bool>,
 }
 impl Default for CookieStore {
     #[inline]
@@ -35,6 +39,8 @@ impl CookieStore {
             name: "salvo.csrf".into(),
             path: "/".into(),
             domain: None,
+            same_site: SameSite::Strict,
+            secure: None,
         }
     }
     /// Sets cookie name.
@@ -60,6 +66,21 @@ impl CookieStore {
         self.domain = Some(domain.into());
         self
     }
+
+    /// Sets cookie SameSite attribute, default is `Strict`.
+    pub fn same_site(mut self, same_site: SameSite) -> Self {
+        self.same_site = same_site;
+        self
+    }
+
+    /// Sets cookie Secure attribute.
+    ///
+    /// By default it is set only when the request scheme is `https`. Cookies whose name starts
+    /// with `__Host-` or `__Secure-` must be secure.
+    pub fn secure(mut self, secure: bool) -> Self {
+        self.secure = Some(secure);
+        self
+    }
 }
 impl CsrfStore for CookieStore {
     type Error = Error;
@@ -82,11 +103,13 @@ impl CsrfStore for CookieStore {
         token: &str,
         proof: &str,
     ) -> Result<(), Self::Error> {
-        let secure = req.uri().scheme() == Some(&Scheme::HTTPS);
+        let secure = self
+            .secure
+            .unwrap_or_else(|| req.uri().scheme() == Some(&Scheme::HTTPS));
         let expires = cookie::time::OffsetDateTime::now_utc() + self.ttl;
         let cookie_builder = Cookie::build((self.name.clone(), format!("{token}.{proof}")))
             .http_only(true)
-            .same_site(SameSite::Strict)
+            .same_site(self.same_site)
             .path(self.path.clone())
             .secure(secure)
             .expires(Expiration::DateTime(expires));
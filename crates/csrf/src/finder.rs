@@ -1,5 +1,4 @@
-use std::collections::HashMap;
-
+use salvo_core::http::mime;
 use salvo_core::{async_trait, Request};
 use serde_json::Value;
 
@@ -54,13 +53,43 @@ impl CsrfTokenFinder for FormFinder {
     }
 }
 
+/// Find token from `multipart/form-data` request body.
+///
+/// The parsed form data is cached in the request, so the other fields and files are still
+/// available to downstream handlers through `Request::form` and `Request::file`.
+#[derive(Clone, Debug)]
+pub struct MultipartFinder {
+    field_name: String,
+}
+impl MultipartFinder {
+    /// Create new `MultipartFinder`.
+    #[inline]
+    pub fn new(field_name: impl Into<String>) -> Self {
+        Self {
+            field_name: field_name.into(),
+        }
+    }
+}
+#[async_trait]
+impl CsrfTokenFinder for MultipartFinder {
+    #[inline]
+    async fn find_token(&self, req: &mut Request) -> Option<String> {
+        if req.content_type()?.type_() != mime::MULTIPART {
+            return None;
+        }
+        req.form(&self.field_name).await
+    }
+}
+
 /// Find token from request json body.
 #[derive(Clone, Debug)]
 pub struct JsonFinder {
     field_name: String,
 }
 impl JsonFinder {
-    /// Create new `FormFinder`.
+    /// Create new `JsonFinder`.
+    ///
+    /// The field name can be a dotted path like `meta.csrf` to find token in nested objects.
     #[inline]
     pub fn new(field_name: impl Into<String>) -> Self {
         Self {
@@ -71,15 +100,15 @@ impl JsonFinder {
 #[async_trait]
 impl CsrfTokenFinder for JsonFinder {
     async fn find_token(&self, req: &mut Request) -> Option<String> {
-        let data = req.parse_json::<HashMap<String, Value>>().await;
-        if let Ok(data) = data {
-            if let Some(value) = data.get(&self.field_name) {
-                if let Some(token) = value.as_str() {
-                    return Some(token.to_owned());
-                }
-            }
-        }
-        None
+        let data = req.parse_json::<Value>().await.ok()?;
+        let value = match data.get(&self.field_name) {
+            Some(value) => value,
+            None => self
+                .field_name
+                .split('.')
+                .try_fold(&data, |value, key| value.get(key))?,
+        };
+        value.as_str().map(|token| token.to_owned())
     }
 }
 
@@ -117,4 +146,59 @@ mod tests {
         let token = json_finder.find_token(&mut req).await;
         assert_eq!(token, Some("test_token".to_string()));
     }
+
+    #[tokio::test]
+    async fn test_json_finder_dotted_path() {
+        let json_finder = JsonFinder::new("meta.csrf");
+        let mut req = TestClient::get("http://test.com")
+            .raw_json(r#"{"meta":{"csrf":"test_token"}}"#)
+            .build();
+        let token = json_finder.find_token(&mut req).await;
+        assert_eq!(token, Some("test_token".to_string()));
+
+        let mut req = TestClient::get("http://test.com")
+            .raw_json(r#"{"meta":{"other":"test_token"}}"#)
+            .build();
+        assert_eq!(json_finder.find_token(&mut req).await, None);
+
+        let mut req = TestClient::get("http://test.com")
+            .raw_json(r#"{"meta":"test_token"}"#)
+            .build();
+        assert_eq!(json_finder.find_token(&mut req).await, None);
+
+        let mut req = TestClient::get("http://test.com")
+            .raw_json(r#"["test_token"]"#)
+            .build();
+        assert_eq!(json_finder.find_token(&mut req).await, None);
+    }
+
+    #[tokio::test]
+    async fn test_multipart_finder() {
+        let multipart_finder = MultipartFinder::new("csrf-token");
+        let mut req = TestClient::post("http://test.com")
+            .add_header(
+                "content-type",
+                "multipart/form-data; boundary=----WebKitFormBoundary0mkL0yrNNupCojyz",
+                true,
+            )
+            .body(
+                "------WebKitFormBoundary0mkL0yrNNupCojyz\r\n\
+Content-Disposition: form-data; name=\"csrf-token\"\r\n\r\ntest_token\r\n\
+------WebKitFormBoundary0mkL0yrNNupCojyz\r\n\
+Content-Disposition: form-data; name=\"file1\"; filename=\"err.txt\"\r\n\
+Content-Type: text/plain\r\n\r\n\
+file content\r\n\
+------WebKitFormBoundary0mkL0yrNNupCojyz--\r\n",
+            )
+            .build();
+        let token = multipart_finder.find_token(&mut req).await;
+        assert_eq!(token, Some("test_token".to_string()));
+        let file = req.file("file1").await.unwrap();
+        assert_eq!(file.name().unwrap(), "err.txt");
+
+        let mut req = TestClient::post("http://test.com")
+            .raw_form("csrf-token=test_token")
+            .build();
+        assert_eq!(multipart_finder.find_token(&mut req).await, None);
+    }
 }
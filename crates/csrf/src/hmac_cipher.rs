@@ -1,3 +1,5 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
 use base64::engine::general_purpose::URL_SAFE_NO_PAD;
 use base64::Engine;
 use hmac::{Hmac, Mac};
@@ -5,10 +7,13 @@ use sha2::Sha256;
 
 use super::CsrfCipher;
 
+const TIMESTAMPED_PROOF_VERSION: u8 = 1;
+
 /// A CSRF protection implementation that uses HMAC.
 pub struct HmacCipher {
     hmac_key: [u8; 32],
     token_size: usize,
+    max_age: Option<Duration>,
 }
 
 impl HmacCipher {
@@ -18,6 +23,7 @@ impl HmacCipher {
         Self {
             hmac_key,
             token_size: 32,
+            max_age: None,
         }
     }
 
@@ -29,10 +35,60 @@ impl HmacCipher {
         self
     }
 
+    /// Sets the max age of the token.
+    ///
+    /// When it is set, the issue time is encoded in the proof and signed together with the token,
+    /// tokens older than `max_age` or without issue time are rejected in `verify`.
+    #[inline]
+    pub fn max_age(mut self, max_age: Duration) -> Self {
+        self.max_age = Some(max_age);
+        self
+    }
+
     #[inline]
     fn hmac(&self) -> Hmac<Sha256> {
         Hmac::<Sha256>::new_from_slice(&self.hmac_key).expect("HMAC can take key of any size")
     }
+
+    fn timestamped_proof(&self, token: &[u8], issued_at: u64) -> Vec<u8> {
+        let mut hmac = self.hmac();
+        hmac.update(token);
+        hmac.update(&issued_at.to_be_bytes());
+        let mut proof = Vec::with_capacity(41);
+        proof.push(TIMESTAMPED_PROOF_VERSION);
+        proof.extend_from_slice(&issued_at.to_be_bytes());
+        proof.extend_from_slice(&hmac.finalize().into_bytes());
+        proof
+    }
+
+    fn verify_timestamped(&self, token: &[u8], proof: &[u8]) -> bool {
+        let Some((issued_at, mac)) = proof.get(1..).and_then(|proof| proof.split_first_chunk::<8>()) else {
+            return false;
+        };
+        let mut hmac = self.hmac();
+        hmac.update(token);
+        hmac.update(issued_at);
+        if hmac.verify_slice(mac).is_err() {
+            return false;
+        }
+        match self.max_age {
+            Some(max_age) => {
+                let issued_at = UNIX_EPOCH + Duration::from_secs(u64::from_be_bytes(*issued_at));
+                SystemTime::now()
+                    .duration_since(issued_at)
+                    .map(|age| age <= max_age)
+                    .unwrap_or(false)
+            }
+            None => true,
+        }
+    }
+}
+
+fn now_secs() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or_default()
 }
 
 impl CsrfCipher for HmacCipher {
@@ -41,7 +97,9 @@ impl CsrfCipher for HmacCipher {
             URL_SAFE_NO_PAD.decode(token.as_bytes()),
             URL_SAFE_NO_PAD.decode(proof.as_bytes()),
         ) {
-            if proof.len() != self.token_size {
+            if proof.first() == Some(&TIMESTAMPED_PROOF_VERSION) && proof.len() == 41 {
+                self.verify_timestamped(&token, &proof)
+            } else if self.max_age.is_some() || proof.len() != self.token_size {
                 false
             } else {
                 let mut hmac = self.hmac();
@@ -54,6 +112,10 @@ impl CsrfCipher for HmacCipher {
     }
     fn generate(&self) -> (String, String) {
         let token = self.random_bytes(self.token_size);
+        if self.max_age.is_some() {
+            let proof = self.timestamped_proof(&token, now_secs());
+            return (URL_SAFE_NO_PAD.encode(token), URL_SAFE_NO_PAD.encode(proof));
+        }
         let mut hmac = self.hmac();
         hmac.update(&token);
         let mac = hmac.finalize();
@@ -108,4 +170,24 @@ mod tests {
         let (token, proof) = hmac_cipher.generate();
         assert!(hmac_cipher.verify(&token, &proof));
     }
+
+    #[test]
+    fn test_max_age() {
+        let hmac_key = [0u8; 32];
+        let hmac_cipher = HmacCipher::new(hmac_key).max_age(Duration::from_secs(60));
+        let (token, proof) = hmac_cipher.generate();
+        assert!(hmac_cipher.verify(&token, &proof));
+        assert!(HmacCipher::new(hmac_key).verify(&token, &proof));
+
+        let raw_token = URL_SAFE_NO_PAD.decode(&token).unwrap();
+        let expired = hmac_cipher.timestamped_proof(&raw_token, now_secs() - 120);
+        assert!(!hmac_cipher.verify(&token, &URL_SAFE_NO_PAD.encode(expired)));
+
+        let mut tampered = hmac_cipher.timestamped_proof(&raw_token, now_secs() - 120);
+        tampered[1..9].copy_from_slice(&now_secs().to_be_bytes());
+        assert!(!hmac_cipher.verify(&token, &URL_SAFE_NO_PAD.encode(tampered)));
+
+        let (token, proof) = HmacCipher::new(hmac_key).generate();
+        assert!(!hmac_cipher.verify(&token, &proof));
+    }
 }
@@ -15,11 +15,13 @@ use std::future::Future;
 
 mod finder;
 
-pub use finder::{CsrfTokenFinder, FormFinder, HeaderFinder, JsonFinder};
+pub use finder::{CsrfTokenFinder, FormFinder, HeaderFinder, JsonFinder, MultipartFinder};
 
 use rand::distributions::Standard;
 use rand::Rng;
-use salvo_core::handler::Skipper;
+use salvo_core::handler::{none_skipper, Skipper};
+use salvo_core::http::header::{HeaderName, HeaderValue, ORIGIN, REFERER};
+use salvo_core::http::uri::Uri;
 use salvo_core::http::{Method, StatusCode};
 use salvo_core::{async_trait, Depot, FlowCtrl, Handler, Request, Response};
 
@@ -155,9 +157,9 @@ cfg_feature! {
 /// key used to insert auth decoded data to depot.
 pub const CSRF_TOKEN_KEY: &str = "salvo.csrf.token";
 
-fn default_skipper(req: &mut Request, _depot: &Depot) -> bool {
-    ![Method::POST, Method::PATCH, Method::DELETE, Method::PUT].contains(req.method())
-}
+/// Methods which require a csrf token by default.
+pub const DEFAULT_PROTECTED_METHODS: [Method; 4] =
+    [Method::POST, Method::PATCH, Method::DELETE, Method::PUT];
 
 /// Store proof.
 pub trait CsrfStore: Send + Sync + 'static {
@@ -207,12 +209,35 @@ impl CsrfDepotExt for Depot {
     }
 }
 
+/// How the `Origin`/`Referer` headers of state-changing requests are verified.
+///
+/// See [`Csrf::trusted_origins`] for more details.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum OriginCheck {
+    /// Origin is not verified, only the csrf token is verified.
+    #[default]
+    Disabled,
+    /// Origin is verified before the csrf token.
+    WithToken,
+    /// Only origin is verified, requests are not required to carry a csrf token.
+    Only,
+}
+
 /// Cross-Site Request Forgery (CSRF) protection middleware.
 pub struct Csrf<C, S> {
     cipher: C,
     store: S,
     skipper: Box<dyn Skipper>,
+    protected_methods: Vec<Method>,
     finders: Vec<Box<dyn CsrfTokenFinder>>,
+    on_failure: Option<Box<dyn Handler>>,
+    missing_token_status: StatusCode,
+    invalid_token_status: StatusCode,
+    response_header: Option<HeaderName>,
+    trusted_origins: Vec<String>,
+    origin_check: OriginCheck,
+    rotate: bool,
 }
 
 impl<C: CsrfCipher, S: CsrfStore> Csrf<C, S> {
@@ -222,9 +247,106 @@ impl<C: CsrfCipher, S: CsrfStore> Csrf<C, S> {
         Self {
             cipher,
             store,
-            skipper: Box::new(default_skipper),
+            skipper: Box::new(none_skipper),
+            protected_methods: DEFAULT_PROTECTED_METHODS.to_vec(),
             finders: vec![Box::new(finder)],
+            on_failure: None,
+            missing_token_status: StatusCode::FORBIDDEN,
+            invalid_token_status: StatusCode::FORBIDDEN,
+            response_header: None,
+            trusted_origins: vec![],
+            origin_check: OriginCheck::Disabled,
+            rotate: false,
+        }
+    }
+
+    /// Sets whether a fresh token is issued after every successful state-changing request.
+    ///
+    /// The old token is invalidated, so pages opened in other tabs which still hold it will be
+    /// rejected. It is disabled by default. The new token is available from
+    /// [`CsrfDepotExt::csrf_token`] and the [`Csrf::response_header`] if it is set.
+    #[inline]
+    pub fn rotate(mut self, rotate: bool) -> Self {
+        self.rotate = rotate;
+        self
+    }
+
+    /// Sets the origins which are trusted to send state-changing requests, e.g. `https://example.com`.
+    ///
+    /// The `Origin` header of the request, or the origin of `Referer` header when `Origin` is
+    /// absent, must match one of them, otherwise the request is rejected like an invalid token.
+    /// If the origin check is disabled, it is set to [`OriginCheck::WithToken`], use
+    /// [`Csrf::origin_check`] to verify origin only.
+    #[inline]
+    pub fn trusted_origins(mut self, origins: Vec<String>) -> Self {
+        self.trusted_origins = origins
+            .into_iter()
+            .map(|origin| origin.trim_end_matches('/').to_ascii_lowercase())
+            .collect();
+        if self.origin_check == OriginCheck::Disabled {
+            self.origin_check = OriginCheck::WithToken;
         }
+        self
+    }
+
+    /// Sets how the origin of state-changing requests is verified.
+    #[inline]
+    pub fn origin_check(mut self, origin_check: OriginCheck) -> Self {
+        self.origin_check = origin_check;
+        self
+    }
+
+    /// Sets the response header which the csrf token is written to on safe requests.
+    ///
+    /// It lets SPAs read the token from any safe response, without a dedicated route which
+    /// renders [`CsrfDepotExt::csrf_token`].
+    #[inline]
+    pub fn response_header(mut self, name: impl Into<HeaderName>) -> Self {
+        self.response_header = Some(name.into());
+        self
+    }
+
+    /// Sets skipper and returns new `Csrf`.
+    ///
+    /// Requests skipped by it are treated as safe requests, like the ones whose method is not
+    /// protected.
+    #[inline]
+    pub fn with_skipper(mut self, skipper: impl Skipper) -> Self {
+        self.skipper = Box::new(skipper);
+        self
+    }
+
+    /// Sets the methods which require a csrf token, it replaces [`DEFAULT_PROTECTED_METHODS`].
+    #[inline]
+    pub fn protected_methods(mut self, methods: Vec<Method>) -> Self {
+        self.protected_methods = methods;
+        self
+    }
+
+    /// Sets the status code returned when the request has no csrf token, default is `403`.
+    #[inline]
+    pub fn missing_token_status(mut self, status_code: StatusCode) -> Self {
+        self.missing_token_status = status_code;
+        self
+    }
+
+    /// Sets the status code returned when the csrf token is invalid or expired, default is `403`.
+    ///
+    /// Some frontends use `419` to trigger a token refresh.
+    #[inline]
+    pub fn invalid_token_status(mut self, status_code: StatusCode) -> Self {
+        self.invalid_token_status = status_code;
+        self
+    }
+
+    /// Sets the handler invoked when the csrf token is missing or invalid.
+    ///
+    /// It can be used to render a helpful error or redirect to a refresh page. By default the
+    /// response status code is set to `403 Forbidden` without body.
+    #[inline]
+    pub fn on_failure(mut self, handler: impl Handler) -> Self {
+        self.on_failure = Some(Box::new(handler));
+        self
     }
 
     /// Add finder to find csrf token.
@@ -256,6 +378,63 @@ impl<C: CsrfCipher, S: CsrfStore> Csrf<C, S> {
         }
         None
     }
+
+    fn skipped(&self, req: &mut Request, depot: &Depot) -> bool {
+        !self.protected_methods.contains(req.method()) || self.skipper.skipped(req, depot)
+    }
+
+    fn verify_origin(&self, req: &Request) -> bool {
+        let origin = if let Some(origin) = req.headers().get(ORIGIN) {
+            origin.to_str().ok().map(|origin| origin.to_owned())
+        } else {
+            req.headers()
+                .get(REFERER)
+                .and_then(|referer| referer.to_str().ok())
+                .and_then(|referer| referer.parse::<Uri>().ok())
+                .and_then(|uri| Some(format!("{}://{}", uri.scheme_str()?, uri.authority()?)))
+        };
+        let Some(origin) = origin else {
+            tracing::debug!("rejecting request due to missing origin");
+            return false;
+        };
+        let origin = origin.trim_end_matches('/').to_ascii_lowercase();
+        if self.trusted_origins.contains(&origin) {
+            true
+        } else {
+            tracing::debug!(origin, "rejecting request due to untrusted origin");
+            false
+        }
+    }
+
+    fn expose_token(&self, depot: &Depot, res: &mut Response) {
+        let (Some(name), Some(token)) = (&self.response_header, depot.csrf_token()) else {
+            return;
+        };
+        match HeaderValue::from_str(token) {
+            Ok(value) => {
+                res.headers_mut().insert(name.clone(), value);
+            }
+            Err(e) => {
+                tracing::error!(error = ?e, "invalid csrf token header value");
+            }
+        }
+    }
+
+    async fn reject(
+        &self,
+        req: &mut Request,
+        depot: &mut Depot,
+        res: &mut Response,
+        ctrl: &mut FlowCtrl,
+        status_code: StatusCode,
+    ) {
+        if let Some(on_failure) = &self.on_failure {
+            on_failure.handle(req, depot, res, ctrl).await;
+        } else {
+            res.status_code(status_code);
+        }
+        ctrl.skip_rest();
+    }
 }
 
 #[async_trait]
@@ -271,33 +450,56 @@ impl<C: CsrfCipher, S: CsrfStore> Handler for Csrf<C, S> {
             Some((token, proof)) => {
                 depot.insert(CSRF_TOKEN_KEY, token);
 
-                if !self.skipper.skipped(req, depot) {
+                if !self.skipped(req, depot) {
+                    if self.origin_check != OriginCheck::Disabled && !self.verify_origin(req) {
+                        self.reject(req, depot, res, ctrl, StatusCode::FORBIDDEN)
+                            .await;
+                        return;
+                    }
+                    if self.origin_check == OriginCheck::Only {
+                        ctrl.call_next(req, depot, res).await;
+                        return;
+                    }
                     if let Some(token) = &self.find_token(req).await {
                         tracing::debug!("csrf token: {token}");
                         if !self.cipher.verify(token, &proof) {
                             tracing::debug!(
                                 "rejecting request due to invalid or expired CSRF token"
                             );
-                            res.status_code(StatusCode::FORBIDDEN);
-                            ctrl.skip_rest();
+                            self.reject(req, depot, res, ctrl, self.invalid_token_status)
+                                .await;
                             return;
                         } else {
                             tracing::debug!("cipher verify CSRF token success");
                         }
+                        if self.rotate {
+                            let (token, proof) = self.cipher.generate();
+                            if let Err(e) = self.store.save(req, depot, res, &token, &proof).await {
+                                tracing::error!(error = ?e, "salvo csrf token failed");
+                            }
+                            depot.insert(CSRF_TOKEN_KEY, token);
+                            self.expose_token(depot, res);
+                        }
                     } else {
                         tracing::debug!("rejecting request due to missing CSRF token",);
-                        res.status_code(StatusCode::FORBIDDEN);
-                        ctrl.skip_rest();
+                        self.reject(req, depot, res, ctrl, self.missing_token_status)
+                            .await;
                         return;
                     }
+                } else {
+                    self.expose_token(depot, res);
                 }
                 ctrl.call_next(req, depot, res).await;
             }
             None => {
-                if !self.skipper.skipped(req, depot) {
-                    tracing::debug!("rejecting request due to missing CSRF token",);
-                    res.status_code(StatusCode::FORBIDDEN);
-                    ctrl.skip_rest();
+                if !self.skipped(req, depot) {
+                    if self.origin_check == OriginCheck::Only && self.verify_origin(req) {
+                        ctrl.call_next(req, depot, res).await;
+                    } else {
+                        tracing::debug!("rejecting request due to missing CSRF token",);
+                        self.reject(req, depot, res, ctrl, self.missing_token_status)
+                            .await;
+                    }
                 } else {
                     let (token, proof) = self.cipher.generate();
                     if let Err(e) = self.store.save(req, depot, res, &token, &proof).await {
@@ -305,6 +507,7 @@ impl<C: CsrfCipher, S: CsrfStore> Handler for Csrf<C, S> {
                     }
                     tracing::debug!("new token: {:?}", token);
                     depot.insert(CSRF_TOKEN_KEY, token);
+                    self.expose_token(depot, res);
                     ctrl.call_next(req, depot, res).await;
                 }
             }
@@ -652,4 +855,392 @@ mod tests {
             .await;
         assert_eq!(res.status_code.unwrap(), StatusCode::FORBIDDEN);
     }
+
+    #[tokio::test]
+    async fn test_on_failure() {
+        #[handler]
+        async fn csrf_failure(res: &mut Response) {
+            res.status_code(StatusCode::BAD_REQUEST);
+            res.render(Json(serde_json::json!({"error": "invalid csrf token"})));
+        }
+
+        let csrf = Csrf::new(
+            BcryptCipher::new(),
+            CookieStore::new(),
+            HeaderFinder::new("x-csrf-token"),
+        )
+        .on_failure(csrf_failure);
+        let router = Router::new().hoop(csrf).get(get_index).post(post_index);
+        let service = Service::new(router);
+
+        let mut res = TestClient::get("http://127.0.0.1:5801")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code.unwrap(), StatusCode::OK);
+        let csrf_token = res.take_string().await.unwrap();
+        let cookie = res.cookie("salvo.csrf").unwrap();
+
+        let mut res = TestClient::post("http://127.0.0.1:5801")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code.unwrap(), StatusCode::BAD_REQUEST);
+        assert_eq!(
+            res.take_string().await.unwrap(),
+            r#"{"error":"invalid csrf token"}"#
+        );
+
+        let mut res = TestClient::post("http://127.0.0.1:5801")
+            .add_header("x-csrf-token", csrf_token, true)
+            .add_header("cookie", cookie.to_string(), true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code.unwrap(), StatusCode::OK);
+        assert_eq!(res.take_string().await.unwrap(), "POST");
+    }
+
+    #[tokio::test]
+    async fn test_response_header() {
+        let csrf = Csrf::new(
+            BcryptCipher::new(),
+            CookieStore::new(),
+            HeaderFinder::new("x-csrf-token"),
+        )
+        .response_header(HeaderName::from_static("x-csrf-token"));
+        let router = Router::new().hoop(csrf).get(get_index).post(post_index);
+        let service = Service::new(router);
+
+        let mut res = TestClient::get("http://127.0.0.1:5801")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code.unwrap(), StatusCode::OK);
+        let csrf_token = res.take_string().await.unwrap();
+        assert_eq!(
+            res.headers().get("x-csrf-token").unwrap().to_str().unwrap(),
+            csrf_token
+        );
+        let cookie = res.cookie("salvo.csrf").unwrap();
+
+        let mut res = TestClient::post("http://127.0.0.1:5801")
+            .add_header("x-csrf-token", csrf_token, true)
+            .add_header("cookie", cookie.to_string(), true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code.unwrap(), StatusCode::OK);
+        assert_eq!(res.take_string().await.unwrap(), "POST");
+        assert!(res.headers().get("x-csrf-token").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_trusted_origins() {
+        let csrf = Csrf::new(
+            BcryptCipher::new(),
+            CookieStore::new(),
+            HeaderFinder::new("x-csrf-token"),
+        )
+        .trusted_origins(vec!["https://example.com/".into()]);
+        let router = Router::new().hoop(csrf).get(get_index).post(post_index);
+        let service = Service::new(router);
+
+        let mut res = TestClient::get("http://127.0.0.1:5801")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code.unwrap(), StatusCode::OK);
+        let csrf_token = res.take_string().await.unwrap();
+        let cookie = res.cookie("salvo.csrf").unwrap();
+
+        let post = |origin: Option<(&'static str, &'static str)>| {
+            let mut client = TestClient::post("http://127.0.0.1:5801")
+                .add_header("x-csrf-token", &csrf_token, true)
+                .add_header("cookie", cookie.to_string(), true);
+            if let Some((name, value)) = origin {
+                client = client.add_header(name, value, true);
+            }
+            client.send(&service)
+        };
+        let res = post(Some(("origin", "https://example.com"))).await;
+        assert_eq!(res.status_code.unwrap(), StatusCode::OK);
+        let res = post(Some(("referer", "https://EXAMPLE.com/form?a=1"))).await;
+        assert_eq!(res.status_code.unwrap(), StatusCode::OK);
+        let res = post(Some(("origin", "https://evil.com"))).await;
+        assert_eq!(res.status_code.unwrap(), StatusCode::FORBIDDEN);
+        let res = post(Some(("referer", "https://evil.com/form"))).await;
+        assert_eq!(res.status_code.unwrap(), StatusCode::FORBIDDEN);
+        let res = post(None).await;
+        assert_eq!(res.status_code.unwrap(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_origin_check_only() {
+        let csrf = Csrf::new(
+            BcryptCipher::new(),
+            CookieStore::new(),
+            HeaderFinder::new("x-csrf-token"),
+        )
+        .trusted_origins(vec!["https://example.com".into()])
+        .origin_check(OriginCheck::Only);
+        let router = Router::new().hoop(csrf).get(get_index).post(post_index);
+        let service = Service::new(router);
+
+        let mut res = TestClient::post("http://127.0.0.1:5801")
+            .add_header("origin", "https://example.com", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code.unwrap(), StatusCode::OK);
+        assert_eq!(res.take_string().await.unwrap(), "POST");
+
+        let res = TestClient::post("http://127.0.0.1:5801")
+            .add_header("origin", "https://evil.com", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code.unwrap(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_rotate() {
+        let csrf = Csrf::new(
+            BcryptCipher::new(),
+            CookieStore::new(),
+            HeaderFinder::new("x-csrf-token"),
+        )
+        .rotate(true)
+        .response_header(HeaderName::from_static("x-csrf-token"));
+        let router = Router::new().hoop(csrf).get(get_index).post(post_index);
+        let service = Service::new(router);
+
+        let mut res = TestClient::get("http://127.0.0.1:5801")
+            .send(&service)
+            .await;
+        let csrf_token = res.take_string().await.unwrap();
+        let cookie = res.cookie("salvo.csrf").unwrap().to_string();
+
+        let res = TestClient::post("http://127.0.0.1:5801")
+            .add_header("x-csrf-token", &csrf_token, true)
+            .add_header("cookie", &cookie, true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code.unwrap(), StatusCode::OK);
+        let new_token = res
+            .headers()
+            .get("x-csrf-token")
+            .unwrap()
+            .to_str()
+            .unwrap()
+            .to_owned();
+        assert_ne!(new_token, csrf_token);
+        let new_cookie = res.cookie("salvo.csrf").unwrap().to_string();
+        assert_ne!(new_cookie, cookie);
+
+        let res = TestClient::post("http://127.0.0.1:5801")
+            .add_header("x-csrf-token", &csrf_token, true)
+            .add_header("cookie", &new_cookie, true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code.unwrap(), StatusCode::FORBIDDEN);
+
+        let res = TestClient::post("http://127.0.0.1:5801")
+            .add_header("x-csrf-token", &new_token, true)
+            .add_header("cookie", &new_cookie, true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code.unwrap(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_cookie_store_attributes() {
+        let csrf = Csrf::new(
+            BcryptCipher::new(),
+            CookieStore::new()
+                .name("__Host-csrf")
+                .same_site(cookie::SameSite::Lax)
+                .secure(true),
+            HeaderFinder::new("x-csrf-token"),
+        );
+        let router = Router::new().hoop(csrf).get(get_index).post(post_index);
+        let service = Service::new(router);
+
+        let mut res = TestClient::get("http://127.0.0.1:5801")
+            .send(&service)
+            .await;
+        let csrf_token = res.take_string().await.unwrap();
+        let cookie = res.cookie("__Host-csrf").unwrap();
+        assert_eq!(cookie.path(), Some("/"));
+        assert_eq!(cookie.domain(), None);
+        assert_eq!(cookie.same_site(), Some(cookie::SameSite::Lax));
+        assert_eq!(cookie.secure(), Some(true));
+        assert!(res.cookie("salvo.csrf").is_none());
+
+        let res = TestClient::post("http://127.0.0.1:5801")
+            .add_header("x-csrf-token", csrf_token, true)
+            .add_header(
+                "cookie",
+                format!("{}={}", cookie.name(), cookie.value()),
+                true,
+            )
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code.unwrap(), StatusCode::OK);
+    }
+
+    #[tokio::test]
+    async fn test_protected_methods() {
+        let csrf = Csrf::new(
+            BcryptCipher::new(),
+            CookieStore::new(),
+            HeaderFinder::new("x-csrf-token"),
+        )
+        .protected_methods(vec![Method::DELETE]);
+        let router = Router::new()
+            .hoop(csrf)
+            .get(get_index)
+            .post(post_index)
+            .delete(post_index);
+        let service = Service::new(router);
+
+        let res = TestClient::post("http://127.0.0.1:5801")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code.unwrap(), StatusCode::OK);
+        let res = TestClient::delete("http://127.0.0.1:5801")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code.unwrap(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_protected_methods_with_skipper() {
+        let csrf = Csrf::new(
+            BcryptCipher::new(),
+            CookieStore::new(),
+            HeaderFinder::new("x-csrf-token"),
+        )
+        .with_skipper(|req: &mut Request, _: &Depot| req.uri().path() == "/search");
+        let router = Router::new()
+            .hoop(csrf)
+            .push(Router::with_path("search").post(post_index))
+            .push(Router::with_path("submit").post(post_index));
+        let service = Service::new(router);
+
+        let res = TestClient::post("http://127.0.0.1:5801/search")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code.unwrap(), StatusCode::OK);
+        let res = TestClient::post("http://127.0.0.1:5801/submit")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code.unwrap(), StatusCode::FORBIDDEN);
+    }
+
+    #[tokio::test]
+    async fn test_token_statuses() {
+        let page_expired = StatusCode::from_u16(419).unwrap();
+        let csrf = Csrf::new(
+            BcryptCipher::new(),
+            CookieStore::new(),
+            HeaderFinder::new("x-csrf-token"),
+        )
+        .missing_token_status(StatusCode::BAD_REQUEST)
+        .invalid_token_status(page_expired);
+        let router = Router::new().hoop(csrf).get(get_index).post(post_index);
+        let service = Service::new(router);
+
+        let res = TestClient::get("http://127.0.0.1:5801")
+            .send(&service)
+            .await;
+        let cookie = res.cookie("salvo.csrf").unwrap();
+
+        let res = TestClient::post("http://127.0.0.1:5801")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code.unwrap(), StatusCode::BAD_REQUEST);
+
+        let res = TestClient::post("http://127.0.0.1:5801")
+            .add_header("cookie", cookie.to_string(), true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code.unwrap(), StatusCode::BAD_REQUEST);
+
+        let res = TestClient::post("http://127.0.0.1:5801")
+            .add_header("x-csrf-token", "invalid", true)
+            .add_header("cookie", cookie.to_string(), true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code.unwrap(), page_expired);
+    }
+
+    #[cfg(feature = "session-store")]
+    #[tokio::test]
+    async fn test_session_store_bind_session_id() {
+        use salvo_session::{MemoryStore, SessionDepotExt, SessionHandler};
+
+        #[handler]
+        async fn login(depot: &mut Depot) -> &'static str {
+            depot.regenerate_session();
+            "LOGIN"
+        }
+
+        async fn check(bind_session_id: bool) -> StatusCode {
+            let session_handler = SessionHandler::builder(
+                MemoryStore::new(),
+                b"secretabsecretabsecretabsecretabsecretabsecretabsecretabsecretab",
+            )
+            .build()
+            .unwrap();
+            let csrf = Csrf::new(
+                BcryptCipher::new(),
+                SessionStore::new().bind_session_id(bind_session_id),
+                HeaderFinder::new("x-csrf-token"),
+            );
+            let router = Router::new()
+                .hoop(session_handler)
+                .hoop(csrf)
+                .get(get_index)
+                .post(post_index)
+                .push(Router::with_path("login").post(login));
+            let service = Service::new(router);
+            let session_cookie = |res: &Response| {
+                let cookie = res.cookie("salvo.session.id").unwrap();
+                format!("{}={}", cookie.name(), cookie.value())
+            };
+
+            let mut res = TestClient::get("http://127.0.0.1:5801")
+                .send(&service)
+                .await;
+            let anonymous_cookie = session_cookie(&res);
+            let token = res.take_string().await.unwrap();
+
+            let res = TestClient::post("http://127.0.0.1:5801/login")
+                .add_header("cookie", &anonymous_cookie, true)
+                .add_header("x-csrf-token", &token, true)
+                .send(&service)
+                .await;
+            assert_eq!(res.status_code.unwrap(), StatusCode::OK);
+            let login_cookie = session_cookie(&res);
+            assert_ne!(anonymous_cookie, login_cookie);
+
+            let res = TestClient::post("http://127.0.0.1:5801")
+                .add_header("cookie", &login_cookie, true)
+                .add_header("x-csrf-token", &token, true)
+                .send(&service)
+                .await;
+            let status_code = res.status_code.unwrap();
+
+            // A new token is issued for the new session id.
+            let mut res = TestClient::get("http://127.0.0.1:5801")
+                .add_header("cookie", &login_cookie, true)
+                .send(&service)
+                .await;
+            let token = res.take_string().await.unwrap();
+            let res = TestClient::post("http://127.0.0.1:5801")
+                .add_header("cookie", &login_cookie, true)
+                .add_header("x-csrf-token", &token, true)
+                .send(&service)
+                .await;
+            assert_eq!(res.status_code.unwrap(), StatusCode::OK);
+
+            status_code
+        }
+
+        assert_eq!(check(false).await, StatusCode::OK);
+        assert_eq!(check(true).await, StatusCode::FORBIDDEN);
+    }
 }
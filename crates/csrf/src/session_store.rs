@@ -7,6 +7,7 @@ use super::{CsrfCipher, CsrfStore};
 #[derive(Debug)]
 pub struct SessionStore {
     name: String,
+    bind_session_id: bool,
 }
 impl Default for SessionStore {
     fn default() -> Self {
@@ -19,16 +20,34 @@ impl SessionStore {
     pub fn new() -> Self {
         Self {
             name: "salvo.csrf".into(),
+            bind_session_id: false,
         }
     }
+
+    /// Sets whether the proof is bound to the id of the session it is saved in.
+    ///
+    /// A bound proof is only loaded while the session keeps that id, so a token minted before the
+    /// id changes, e.g. by [`regenerate_session`](SessionDepotExt::regenerate_session) on login,
+    /// is rejected and a new token is issued. Disabled by default.
+    pub fn bind_session_id(mut self, bind_session_id: bool) -> Self {
+        self.bind_session_id = bind_session_id;
+        self
+    }
+
+    fn session_id_key(&self) -> String {
+        format!("{}.session_id", self.name)
+    }
 }
 
 impl CsrfStore for SessionStore {
     type Error = Error;
     async fn load<C: CsrfCipher>(&self, _req: &mut Request, depot: &mut Depot, _cipher: &C) -> Option<(String, String)> {
-        depot
-            .session()
-            .and_then(|s| s.get::<String>(&self.name))
+        let session = depot.session()?;
+        if self.bind_session_id && session.get::<String>(&self.session_id_key()).as_deref() != Some(session.id()) {
+            return None;
+        }
+        session
+            .get::<String>(&self.name)
             .and_then(|s| s.split_once('.').map(|(t, p)| (t.into(), p.into())))
     }
     async fn save(
@@ -39,10 +58,12 @@ impl CsrfStore for SessionStore {
         token: &str,
         proof: &str,
     ) -> Result<(), Self::Error> {
-        depot
-            .session_mut()
-            .expect("session must be exist")
-            .insert(&self.name, format!("{token}.{proof}"))?;
+        let session = depot.session_mut().expect("session must be exist");
+        session.insert(&self.name, format!("{token}.{proof}"))?;
+        if self.bind_session_id {
+            let session_id = session.id().to_owned();
+            session.insert(&self.session_id_key(), session_id)?;
+        }
         Ok(())
     }
 }
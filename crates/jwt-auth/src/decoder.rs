@@ -2,9 +2,12 @@ use jsonwebtoken::errors::Error as JwtError;
 use jsonwebtoken::{decode, Algorithm, DecodingKey, TokenData, Validation};
 use serde::Deserialize;
 use std::future::Future;
+use std::pin::Pin;
 
 use salvo_core::Depot;
 
+use crate::JwtAuthError;
+
 /// JwtAuthDecoder is used to decode token to claims.
 pub trait JwtAuthDecoder {
     /// Error type.
@@ -20,10 +23,97 @@ pub trait JwtAuthDecoder {
         C: for<'de> Deserialize<'de>;
 }
 
+type BoxedError = Box<dyn std::error::Error + Send + Sync>;
+
+/// Object safe version of [`JwtAuthDecoder`] decoding claims to a [`serde_json::Value`], it is
+/// implemented for all decoders and used by [`MultiDecoder`].
+pub trait DynJwtAuthDecoder: Send + Sync {
+    /// Decode token to JSON claims.
+    fn decode_value<'a>(
+        &'a self,
+        token: &'a str,
+        depot: &'a mut Depot,
+    ) -> Pin<Box<dyn Future<Output = Result<TokenData<serde_json::Value>, BoxedError>> + Send + 'a>>;
+}
+impl<D> DynJwtAuthDecoder for D
+where
+    D: JwtAuthDecoder + Send + Sync,
+{
+    fn decode_value<'a>(
+        &'a self,
+        token: &'a str,
+        depot: &'a mut Depot,
+    ) -> Pin<Box<dyn Future<Output = Result<TokenData<serde_json::Value>, BoxedError>> + Send + 'a>>
+    {
+        Box::pin(async move {
+            self.decode::<serde_json::Value>(token, depot)
+                .await
+                .map_err(|e| Box::new(e) as BoxedError)
+        })
+    }
+}
+
+/// `MultiDecoder` tries several decoders in order and returns the result of the first one
+/// which succeeds.
+///
+/// It is useful when tokens from several issuers are accepted, e.g. while migrating between
+/// auth providers. If all decoders fail, [`JwtAuthError::DecodersFailed`] contains their errors.
+#[derive(Default)]
+pub struct MultiDecoder {
+    decoders: Vec<Box<dyn DynJwtAuthDecoder>>,
+}
+impl MultiDecoder {
+    /// Create a new `MultiDecoder`.
+    #[inline]
+    pub fn new(decoders: Vec<Box<dyn DynJwtAuthDecoder>>) -> Self {
+        Self { decoders }
+    }
+    /// Add a decoder, it is tried after the existing ones.
+    #[inline]
+    pub fn add_decoder(mut self, decoder: impl JwtAuthDecoder + Send + Sync + 'static) -> Self {
+        self.decoders.push(Box::new(decoder));
+        self
+    }
+}
+
+impl JwtAuthDecoder for MultiDecoder {
+    type Error = JwtAuthError;
+
+    async fn decode<C>(&self, token: &str, depot: &mut Depot) -> Result<TokenData<C>, Self::Error>
+    where
+        C: for<'de> Deserialize<'de>,
+    {
+        let mut errors = Vec::with_capacity(self.decoders.len());
+        for decoder in &self.decoders {
+            match decoder.decode_value(token, depot).await {
+                Ok(data) => {
+                    return Ok(TokenData {
+                        header: data.header,
+                        claims: serde_json::from_value(data.claims)?,
+                    });
+                }
+                Err(e) => errors.push(e),
+            }
+        }
+        Err(JwtAuthError::DecodersFailed(errors))
+    }
+}
+
+/// Returns the default validation of decoders, which also validates the `nbf` claim.
+pub(crate) fn default_validation(algorithm: Algorithm) -> Validation {
+    let mut validation = Validation::new(algorithm);
+    validation.validate_nbf = true;
+    validation
+}
+
+/// Function used to normalize a raw token before it is decoded.
+pub type TokenNormalizer = Box<dyn Fn(&str) -> String + Send + Sync>;
+
 /// ConstDecoder will decode token with a static secret.
 pub struct ConstDecoder {
     decoding_key: DecodingKey,
     validation: Validation,
+    normalizer: Option<TokenNormalizer>,
 }
 
 impl ConstDecoder {
@@ -31,7 +121,8 @@ impl ConstDecoder {
     pub fn new(decoding_key: DecodingKey) -> Self {
         Self {
             decoding_key,
-            validation: Validation::default(),
+            validation: default_validation(Algorithm::HS256),
+            normalizer: None,
         }
     }
     /// Create a new `ConstDecoder` with validation.
@@ -39,38 +130,89 @@ impl ConstDecoder {
         Self {
             decoding_key,
             validation,
+            normalizer: None,
         }
     }
 
+    /// Sets the validation options.
+    pub fn validation(mut self, validation: Validation) -> Self {
+        self.validation = validation;
+        self
+    }
+    /// Get validation options mutable reference.
+    pub fn validation_mut(&mut self) -> &mut Validation {
+        &mut self.validation
+    }
+    /// Sets the accepted audiences, tokens must have an `aud` claim matching one of them.
+    pub fn audience<T: ToString>(mut self, audience: &[T]) -> Self {
+        self.validation.set_audience(audience);
+        self
+    }
+    /// Sets the accepted issuers, tokens must have an `iss` claim matching one of them.
+    pub fn issuer<T: ToString>(mut self, issuer: &[T]) -> Self {
+        self.validation.set_issuer(issuer);
+        self
+    }
+    /// Sets the leeway in seconds added when validating time based claims, e.g. `exp` and `nbf`.
+    pub fn leeway(mut self, leeway: u64) -> Self {
+        self.validation.leeway = leeway;
+        self
+    }
+
+    /// Sets whether the `nbf` (not before) claim is validated, tokens which are not valid yet
+    /// are rejected with [`ErrorKind::ImmatureSignature`](jsonwebtoken::errors::ErrorKind).
+    ///
+    /// It is enabled by default, except when the validation is set explicitly.
+    pub fn validate_nbf(mut self, validate_nbf: bool) -> Self {
+        self.validation.validate_nbf = validate_nbf;
+        self
+    }
+
+    /// Sets a function used to normalize the raw token before decoding, e.g. strip whitespace
+    /// or fix base64 padding. No normalization is done by default.
+    ///
+    /// This loosens what is accepted as a token, so only use it for issuers known to produce
+    /// non-standard tokens.
+    pub fn normalizer<F>(mut self, normalizer: F) -> Self
+    where
+        F: Fn(&str) -> String + Send + Sync + 'static,
+    {
+        self.normalizer = Some(Box::new(normalizer));
+        self
+    }
+
     /// If you're using HMAC, use this.
     pub fn from_secret(secret: &[u8]) -> Self {
-        Self::with_validation(DecodingKey::from_secret(secret), Validation::default())
+        Self::with_validation(
+            DecodingKey::from_secret(secret),
+            default_validation(Algorithm::HS256),
+        )
     }
 
     /// If you're using HMAC with a base64 encoded secret, use this.
     pub fn from_base64_secret(secret: &str) -> Result<Self, JwtError> {
         DecodingKey::from_base64_secret(secret)
-            .map(|key| Self::with_validation(key, Validation::default()))
+            .map(|key| Self::with_validation(key, default_validation(Algorithm::HS256)))
     }
 
     /// If you are loading a public RSA key in a PEM format, use this.
     /// Only exists if the feature `use_pem` is enabled.
     pub fn from_rsa_pem(key: &[u8]) -> Result<Self, JwtError> {
         DecodingKey::from_rsa_pem(key)
-            .map(|key| Self::with_validation(key, Validation::new(Algorithm::RS256)))
+            .map(|key| Self::with_validation(key, default_validation(Algorithm::RS256)))
     }
 
     /// If you have (n, e) RSA public key components as strings, use this.
     pub fn from_rsa_components(modulus: &str, exponent: &str) -> Result<Self, JwtError> {
         DecodingKey::from_rsa_components(modulus, exponent)
-            .map(|key| Self::with_validation(key, Validation::new(Algorithm::PS512)))
+            .map(|key| Self::with_validation(key, default_validation(Algorithm::PS512)))
     }
 
     /// If you have (n, e) RSA public key components already decoded, use this.
     pub fn from_rsa_raw_components(modulus: &[u8], exponent: &[u8]) -> Self {
         Self::with_validation(
             DecodingKey::from_rsa_raw_components(modulus, exponent),
-            Validation::new(Algorithm::PS512),
+            default_validation(Algorithm::PS512),
         )
     }
 
@@ -78,27 +220,27 @@ impl ConstDecoder {
     /// Only exists if the feature `use_pem` is enabled.
     pub fn from_ec_pem(key: &[u8]) -> Result<Self, JwtError> {
         DecodingKey::from_ec_pem(key)
-            .map(|key| Self::with_validation(key, Validation::new(Algorithm::ES256)))
+            .map(|key| Self::with_validation(key, default_validation(Algorithm::ES256)))
     }
 
     /// If you have (x,y) ECDSA key components
     pub fn from_ec_components(x: &str, y: &str) -> Result<Self, JwtError> {
         DecodingKey::from_ec_components(x, y)
-            .map(|key| Self::with_validation(key, Validation::new(Algorithm::ES256)))
+            .map(|key| Self::with_validation(key, default_validation(Algorithm::ES256)))
     }
 
     /// If you have a EdDSA public key in PEM format, use this.
     /// Only exists if the feature `use_pem` is enabled.
     pub fn from_ed_pem(key: &[u8]) -> Result<Self, JwtError> {
         DecodingKey::from_ed_pem(key)
-            .map(|key| Self::with_validation(key, Validation::new(Algorithm::EdDSA)))
+            .map(|key| Self::with_validation(key, default_validation(Algorithm::EdDSA)))
     }
 
     /// If you know what you're doing and have a RSA DER encoded public key, use this.
     pub fn from_rsa_der(der: &[u8]) -> Self {
         Self::with_validation(
             DecodingKey::from_rsa_der(der),
-            Validation::new(Algorithm::RS256),
+            default_validation(Algorithm::RS256),
         )
     }
 
@@ -106,7 +248,7 @@ impl ConstDecoder {
     pub fn from_ec_der(der: &[u8]) -> Self {
         Self::with_validation(
             DecodingKey::from_ec_der(der),
-            Validation::new(Algorithm::ES256),
+            default_validation(Algorithm::ES256),
         )
     }
 
@@ -114,14 +256,14 @@ impl ConstDecoder {
     pub fn from_ed_der(der: &[u8]) -> Self {
         Self::with_validation(
             DecodingKey::from_ed_der(der),
-            Validation::new(Algorithm::EdDSA),
+            default_validation(Algorithm::EdDSA),
         )
     }
 
     /// From x part (base64 encoded) of the JWK encoding
     pub fn from_ed_components(x: &str) -> Result<Self, JwtError> {
         DecodingKey::from_ed_components(x)
-            .map(|key| Self::with_validation(key, Validation::new(Algorithm::EdDSA)))
+            .map(|key| Self::with_validation(key, default_validation(Algorithm::EdDSA)))
     }
 }
 
@@ -132,6 +274,134 @@ impl JwtAuthDecoder for ConstDecoder {
     where
         C: for<'de> Deserialize<'de>,
     {
-        decode::<C>(token, &self.decoding_key, &self.validation)
+        if let Some(normalizer) = &self.normalizer {
+            decode::<C>(&normalizer(token), &self.decoding_key, &self.validation)
+        } else {
+            decode::<C>(token, &self.decoding_key, &self.validation)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use jsonwebtoken::EncodingKey;
+    use serde::Serialize;
+    use time::{Duration, OffsetDateTime};
+
+    use super::*;
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct JwtClaims {
+        user: String,
+        exp: i64,
+    }
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct AudClaims {
+        user: String,
+        exp: i64,
+        aud: String,
+        iss: String,
+    }
+
+    #[tokio::test]
+    async fn test_normalizer() {
+        let claim = JwtClaims {
+            user: "root".into(),
+            exp: (OffsetDateTime::now_utc() + Duration::days(1)).unix_timestamp(),
+        };
+        let token = jsonwebtoken::encode(
+            &jsonwebtoken::Header::default(),
+            &claim,
+            &EncodingKey::from_secret(b"ABCDEF"),
+        )
+        .unwrap();
+        let padded = format!("  {token}\n");
+        let mut depot = Depot::new();
+
+        let decoder = ConstDecoder::from_secret(b"ABCDEF");
+        assert!(decoder
+            .decode::<JwtClaims>(&padded, &mut depot)
+            .await
+            .is_err());
+
+        let decoder = decoder.normalizer(|token| token.trim().to_owned());
+        let data = decoder
+            .decode::<JwtClaims>(&padded, &mut depot)
+            .await
+            .unwrap();
+        assert_eq!(data.claims.user, "root");
+    }
+
+    #[tokio::test]
+    async fn test_multi_decoder() {
+        let claim = JwtClaims {
+            user: "root".into(),
+            exp: (OffsetDateTime::now_utc() + Duration::days(1)).unix_timestamp(),
+        };
+        let encode = |secret: &[u8]| {
+            jsonwebtoken::encode(
+                &jsonwebtoken::Header::default(),
+                &claim,
+                &EncodingKey::from_secret(secret),
+            )
+            .unwrap()
+        };
+        let mut depot = Depot::new();
+
+        let decoder = MultiDecoder::new(vec![
+            Box::new(ConstDecoder::from_secret(b"internal")),
+            Box::new(ConstDecoder::from_secret(b"external")),
+        ]);
+        for secret in [&b"internal"[..], b"external"] {
+            let data = decoder
+                .decode::<JwtClaims>(&encode(secret), &mut depot)
+                .await
+                .unwrap();
+            assert_eq!(data.claims.user, "root");
+        }
+        let result = decoder
+            .decode::<JwtClaims>(&encode(b"unknown"), &mut depot)
+            .await;
+        assert!(matches!(result, Err(JwtAuthError::DecodersFailed(errors)) if errors.len() == 2));
+    }
+
+    #[tokio::test]
+    async fn test_audience_issuer() {
+        let encode = |aud: &str, iss: &str| {
+            let claim = AudClaims {
+                user: "root".into(),
+                exp: (OffsetDateTime::now_utc() + Duration::days(1)).unix_timestamp(),
+                aud: aud.into(),
+                iss: iss.into(),
+            };
+            jsonwebtoken::encode(
+                &jsonwebtoken::Header::default(),
+                &claim,
+                &EncodingKey::from_secret(b"ABCDEF"),
+            )
+            .unwrap()
+        };
+        let mut depot = Depot::new();
+        let decoder = ConstDecoder::from_secret(b"ABCDEF")
+            .audience(&["orders"])
+            .issuer(&["https://auth.example.com"])
+            .leeway(30);
+
+        let token = encode("orders", "https://auth.example.com");
+        assert!(decoder
+            .decode::<AudClaims>(&token, &mut depot)
+            .await
+            .is_ok());
+        let token = encode("billing", "https://auth.example.com");
+        assert!(decoder
+            .decode::<AudClaims>(&token, &mut depot)
+            .await
+            .is_err());
+        let token = encode("orders", "https://evil.example.com");
+        assert!(decoder
+            .decode::<AudClaims>(&token, &mut depot)
+            .await
+            .is_err());
     }
 }
@@ -1,7 +1,9 @@
 use std::borrow::Cow;
 
 use salvo_core::async_trait;
-use salvo_core::http::header::{HeaderName, AUTHORIZATION, PROXY_AUTHORIZATION};
+use salvo_core::http::header::{
+    HeaderName, AUTHORIZATION, PROXY_AUTHORIZATION, SEC_WEBSOCKET_PROTOCOL,
+};
 use salvo_core::http::{Method, Request};
 
 use super::ALL_METHODS;
@@ -23,6 +25,10 @@ pub struct HeaderFinder {
     pub cared_methods: Vec<Method>,
     /// Header names.
     pub header_names: Vec<HeaderName>,
+    /// Authentication scheme preceding the token, matched case-insensitively.
+    pub scheme: Cow<'static, str>,
+    /// Whether the scheme is required, otherwise a bare token without scheme is also accepted.
+    pub require_scheme: bool,
 }
 impl HeaderFinder {
     /// Create new `HeaderFinder`.
@@ -31,6 +37,38 @@ impl HeaderFinder {
         Self {
             cared_methods: ALL_METHODS.to_vec(),
             header_names: vec![AUTHORIZATION, PROXY_AUTHORIZATION],
+            scheme: Cow::Borrowed("Bearer"),
+            require_scheme: true,
+        }
+    }
+
+    /// Sets the authentication scheme and returns `Self`, the default is `Bearer`.
+    #[inline]
+    pub fn scheme(mut self, scheme: impl Into<Cow<'static, str>>) -> Self {
+        self.scheme = scheme.into();
+        self
+    }
+
+    /// Sets whether the scheme is required and returns `Self`, the default is `true`.
+    ///
+    /// If it is `false`, header values consisting of a bare token are accepted too. Values with
+    /// another scheme, e.g. `Basic`, are always ignored.
+    #[inline]
+    pub fn require_scheme(mut self, require_scheme: bool) -> Self {
+        self.require_scheme = require_scheme;
+        self
+    }
+
+    fn parse_token(&self, value: &str) -> Option<String> {
+        let value = value.trim();
+        match value.split_once(char::is_whitespace) {
+            Some((scheme, token)) if scheme.eq_ignore_ascii_case(&self.scheme) => {
+                let token = token.trim();
+                (!token.is_empty()).then(|| token.to_owned())
+            }
+            Some(_) => None,
+            None if !self.require_scheme && !value.is_empty() => Some(value.to_owned()),
+            None => None,
         }
     }
 
@@ -66,8 +104,8 @@ impl JwtTokenFinder for HeaderFinder {
         if self.cared_methods.contains(req.method()) {
             for header_name in &self.header_names {
                 if let Some(Ok(auth)) = req.headers().get(header_name).map(|auth| auth.to_str()) {
-                    if auth.starts_with("Bearer") {
-                        return auth.split_once(' ').map(|(_, token)| token.to_owned());
+                    if let Some(token) = self.parse_token(auth) {
+                        return Some(token);
                     }
                 }
             }
@@ -202,3 +240,137 @@ impl JwtTokenFinder for CookieFinder {
         }
     }
 }
+
+/// Subprotocol which should be accepted in the WebSocket handshake response, it is inserted into
+/// the request extensions by [`WebSocketProtocolFinder`].
+///
+/// [`JwtAuth`](crate::JwtAuth) echoes it in the `Sec-WebSocket-Protocol` response header when the
+/// token is valid, as browsers close the connection if none of the requested subprotocols is
+/// accepted.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct AcceptedWebSocketProtocol(pub String);
+
+/// `WebSocketProtocolFinder` is to find a JWT from the `Sec-WebSocket-Protocol` header.
+///
+/// Browser WebSocket clients can not set the `Authorization` header, so the token is passed as
+/// a subprotocol following a marker protocol, e.g.
+/// `new WebSocket(url, ["access_token", token])` sends
+/// `Sec-WebSocket-Protocol: access_token, <token>`. The marker protocol is accepted in the
+/// handshake response, see [`AcceptedWebSocketProtocol`].
+#[derive(Eq, PartialEq, Clone, Debug)]
+#[non_exhaustive]
+pub struct WebSocketProtocolFinder {
+    /// Cared methods list.
+    pub cared_methods: Vec<Method>,
+    /// Protocol preceding the token, matched case-insensitively.
+    pub marker: Cow<'static, str>,
+}
+impl Default for WebSocketProtocolFinder {
+    #[inline]
+    fn default() -> Self {
+        Self::new()
+    }
+}
+impl WebSocketProtocolFinder {
+    /// Create new `WebSocketProtocolFinder` using the `access_token` marker protocol.
+    #[inline]
+    pub fn new() -> Self {
+        Self {
+            cared_methods: vec![Method::GET],
+            marker: Cow::Borrowed("access_token"),
+        }
+    }
+    /// Sets the marker protocol and returns `Self`.
+    #[inline]
+    pub fn marker(mut self, marker: impl Into<Cow<'static, str>>) -> Self {
+        self.marker = marker.into();
+        self
+    }
+    /// Get cared methods list mutable reference.
+    #[inline]
+    pub fn cared_methods_mut(&mut self) -> &mut Vec<Method> {
+        &mut self.cared_methods
+    }
+    /// Sets cared methods list and returns `Self`.
+    #[inline]
+    pub fn cared_methods(mut self, methods: Vec<Method>) -> Self {
+        self.cared_methods = methods;
+        self
+    }
+}
+#[async_trait]
+impl JwtTokenFinder for WebSocketProtocolFinder {
+    async fn find_token(&self, req: &mut Request) -> Option<String> {
+        if !self.cared_methods.contains(req.method()) {
+            return None;
+        }
+        let (marker, token) = req
+            .headers()
+            .get_all(SEC_WEBSOCKET_PROTOCOL)
+            .iter()
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(str::trim)
+            .collect::<Vec<_>>()
+            .windows(2)
+            .find(|pair| pair[0].eq_ignore_ascii_case(&self.marker))
+            .map(|pair| (pair[0].to_owned(), pair[1].to_owned()))?;
+        req.extensions_mut()
+            .insert(AcceptedWebSocketProtocol(marker));
+        Some(token)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_header_finder_parse_token() {
+        let finder = HeaderFinder::new();
+        assert_eq!(finder.parse_token("Bearer abc"), Some("abc".into()));
+        assert_eq!(finder.parse_token("  bearer   abc \t"), Some("abc".into()));
+        assert_eq!(finder.parse_token("BEARER abc"), Some("abc".into()));
+        assert_eq!(finder.parse_token("Bearer"), None);
+        assert_eq!(finder.parse_token("Bearerabc"), None);
+        assert_eq!(finder.parse_token("Basic abc"), None);
+        assert_eq!(finder.parse_token("abc"), None);
+
+        let finder = HeaderFinder::new().require_scheme(false);
+        assert_eq!(finder.parse_token(" abc "), Some("abc".into()));
+        assert_eq!(finder.parse_token("Basic abc"), None);
+
+        let finder = HeaderFinder::new().scheme("JWT");
+        assert_eq!(finder.parse_token("jwt abc"), Some("abc".into()));
+        assert_eq!(finder.parse_token("Bearer abc"), None);
+    }
+
+    #[tokio::test]
+    async fn test_websocket_protocol_finder() {
+        let finder = WebSocketProtocolFinder::new();
+        let mut req = Request::default();
+        req.headers_mut().insert(
+            SEC_WEBSOCKET_PROTOCOL,
+            "chat, access_token, abc.def.ghi".parse().unwrap(),
+        );
+        assert_eq!(
+            finder.find_token(&mut req).await,
+            Some("abc.def.ghi".into())
+        );
+        assert_eq!(
+            req.extensions().get::<AcceptedWebSocketProtocol>(),
+            Some(&AcceptedWebSocketProtocol("access_token".into()))
+        );
+
+        let mut req = Request::default();
+        req.headers_mut().insert(
+            SEC_WEBSOCKET_PROTOCOL,
+            "chat, access_token".parse().unwrap(),
+        );
+        assert_eq!(finder.find_token(&mut req).await, None);
+        assert!(req
+            .extensions()
+            .get::<AcceptedWebSocketProtocol>()
+            .is_none());
+    }
+}
@@ -67,7 +67,10 @@
 //!             JwtAuthState::Unauthorized => {
 //!                 res.render(Text::Html(LOGIN_HTML));
 //!             }
-//!             JwtAuthState::Forbidden => {
+//!             JwtAuthState::Expired => {
+//!                 res.render(StatusError::unauthorized().brief("The token has expired."));
+//!             }
+//!             _ => {
 //!                 res.render(StatusError::forbidden());
 //!             }
 //!         }
@@ -108,19 +111,34 @@ use std::marker::PhantomData;
 
 #[doc(no_inline)]
 pub use jsonwebtoken::{
-    decode, errors::Error as JwtError, Algorithm, DecodingKey, TokenData, Validation,
+    decode,
+    errors::{Error as JwtError, ErrorKind},
+    Algorithm, DecodingKey, Header, TokenData, Validation,
 };
 use serde::de::DeserializeOwned;
 use thiserror::Error;
 
+use salvo_core::http::header::{HeaderValue, SEC_WEBSOCKET_PROTOCOL, WWW_AUTHENTICATE};
 use salvo_core::http::{Method, Request, Response, StatusError};
 use salvo_core::{async_trait, Depot, FlowCtrl, Handler};
 
 mod finder;
-pub use finder::{CookieFinder, FormFinder, HeaderFinder, JwtTokenFinder, QueryFinder};
+pub use finder::{
+    AcceptedWebSocketProtocol, CookieFinder, FormFinder, HeaderFinder, JwtTokenFinder, QueryFinder,
+    WebSocketProtocolFinder,
+};
 
 mod decoder;
-pub use decoder::{ConstDecoder, JwtAuthDecoder};
+pub use decoder::{ConstDecoder, DynJwtAuthDecoder, JwtAuthDecoder, MultiDecoder, TokenNormalizer};
+
+mod revocation;
+pub use revocation::JwtRevocationChecker;
+
+mod refresh;
+pub use refresh::{JwtRefresh, RefreshClaims, TokenPair, REFRESH_TOKEN_TYPE};
+
+mod require;
+pub use require::{ClaimsPredicate, RequireClaims};
 
 #[macro_use]
 mod cfg;
@@ -139,6 +157,10 @@ pub const JWT_AUTH_STATE_KEY: &str = "::salvo::jwt_auth::auth_state";
 pub const JWT_AUTH_TOKEN_KEY: &str = "::salvo::jwt_auth::auth_token";
 /// key used to insert auth error to depot.
 pub const JWT_AUTH_ERROR_KEY: &str = "::salvo::jwt_auth::auth_error";
+/// key used to insert auth error kind to depot.
+pub const JWT_AUTH_ERROR_KIND_KEY: &str = "::salvo::jwt_auth::auth_error_kind";
+/// key used to insert auth decoded header to depot.
+pub const JWT_AUTH_HEADER_KEY: &str = "::salvo::jwt_auth::auth_header";
 
 const ALL_METHODS: [Method; 9] = [
     Method::GET,
@@ -154,6 +176,7 @@ const ALL_METHODS: [Method; 9] = [
 
 /// JwtAuthError
 #[derive(Debug, Error)]
+#[non_exhaustive]
 pub enum JwtAuthError {
     /// HTTP client error
     #[cfg(feature = "oidc")]
@@ -197,17 +220,23 @@ pub enum JwtAuthError {
     /// Would typically result in a 401 HTTP Status code
     #[error("Token did not contain a KID field")]
     MissingKid,
+    /// All decoders of a [`MultiDecoder`] failed, contains the error of each decoder.
+    #[error("All decoders failed")]
+    DecodersFailed(Vec<Box<dyn std::error::Error + Send + Sync>>),
 }
 
 /// JwtAuthState
 #[derive(Copy, Clone, Eq, PartialEq, Debug)]
+#[non_exhaustive]
 pub enum JwtAuthState {
     /// Authorized. Used if decoding jwt token successfully.
     Authorized,
     /// Unauthorized. Used if no jwt token is provided.
     Unauthorized,
-    /// Forbidden. Used if decoding jwt token failed.
+    /// Forbidden. Used if decoding jwt token failed, except for expired tokens.
     Forbidden,
+    /// Expired. Used if the jwt token is valid but expired, so the client can refresh it.
+    Expired,
 }
 /// JwtAuthDepotExt
 pub trait JwtAuthDepotExt {
@@ -221,6 +250,14 @@ pub trait JwtAuthDepotExt {
     fn jwt_auth_state(&self) -> JwtAuthState;
     /// get jwt auth error from depot.
     fn jwt_auth_error(&self) -> Option<&JwtError>;
+    /// get jwt auth decoded header from depot.
+    fn jwt_auth_header(&self) -> Option<&Header>;
+    /// get the kind of the jwt auth error from depot, e.g. [`ErrorKind::ExpiredSignature`],
+    /// [`ErrorKind::ImmatureSignature`] or [`ErrorKind::InvalidSignature`].
+    ///
+    /// Unlike [`jwt_auth_error`](Self::jwt_auth_error), it is available for all decoders whose
+    /// error is or wraps a [`JwtError`].
+    fn jwt_auth_error_kind(&self) -> Option<&ErrorKind>;
 }
 
 impl JwtAuthDepotExt for Depot {
@@ -249,15 +286,40 @@ impl JwtAuthDepotExt for Depot {
     fn jwt_auth_error(&self) -> Option<&JwtError> {
         self.get(JWT_AUTH_ERROR_KEY).ok()
     }
+
+    #[inline]
+    fn jwt_auth_header(&self) -> Option<&Header> {
+        self.get(JWT_AUTH_HEADER_KEY).ok()
+    }
+
+    #[inline]
+    fn jwt_auth_error_kind(&self) -> Option<&ErrorKind> {
+        self.get(JWT_AUTH_ERROR_KIND_KEY).ok()
+    }
 }
 
+/// Function rendering the response when authentication fails, see [`JwtAuth::on_failure`].
+pub type FailureRenderer = Box<dyn Fn(JwtAuthState, &mut Response) + Send + Sync>;
+
 /// JwtAuth, used as middleware.
 #[non_exhaustive]
 pub struct JwtAuth<C, D> {
     /// Only write auth state to depot when set to `true`.
     ///
+    /// Requests without a token or with an invalid token are then passed to the next handlers
+    /// with the state [`JwtAuthState::Unauthorized`], [`JwtAuthState::Expired`] or
+    /// [`JwtAuthState::Forbidden`], and no response is rendered, see [`JwtAuth::optional`].
+    ///
     /// **Note**: If you set to `true`, you must handle auth state in next middlewares or handler.
     pub force_passed: bool,
+    /// The realm of the `WWW-Authenticate` challenge, the header is only sent if it is set.
+    pub realm: Option<String>,
+    /// The `error_description` of the `WWW-Authenticate` challenge sent for invalid tokens.
+    pub error_description: Option<String>,
+    /// The renderer used when authentication fails.
+    pub failure_renderer: Option<FailureRenderer>,
+    /// The checker of revoked tokens.
+    pub revocation_checker: Option<Box<dyn JwtRevocationChecker<C>>>,
     _claims: PhantomData<C>,
     /// The decoder.
     pub decoder: D,
@@ -275,11 +337,25 @@ where
     pub fn new(decoder: D) -> Self {
         JwtAuth {
             force_passed: false,
+            realm: None,
+            error_description: None,
+            failure_renderer: None,
+            revocation_checker: None,
             decoder,
             _claims: PhantomData::<C>,
             finders: vec![Box::new(HeaderFinder::new())],
         }
     }
+    /// Create new `JwtAuth` for optional authentication, it is the same as
+    /// `JwtAuth::new(decoder).force_passed(true)`.
+    ///
+    /// Valid tokens populate the depot as usual, but requests are never rejected, so the same
+    /// route can serve both logged-in and anonymous users by checking
+    /// [`JwtAuthDepotExt::jwt_auth_state`].
+    #[inline]
+    pub fn optional(decoder: D) -> Self {
+        Self::new(decoder).force_passed(true)
+    }
     /// Sets force_passed value and return Self.
     #[inline]
     pub fn force_passed(mut self, force_passed: bool) -> Self {
@@ -287,6 +363,45 @@ where
         self
     }
 
+    /// Sets the realm and enables the `WWW-Authenticate` header on failures, as defined by
+    /// RFC 6750.
+    ///
+    /// Requests without token get `Bearer realm="..."` and requests with an invalid token get
+    /// `Bearer realm="...", error="invalid_token"`, both with `401 Unauthorized`.
+    #[inline]
+    pub fn realm(mut self, realm: impl Into<String>) -> Self {
+        self.realm = Some(realm.into());
+        self
+    }
+    /// Sets the `error_description` added to the `WWW-Authenticate` header for invalid tokens.
+    ///
+    /// It is only used if the [realm](Self::realm) is set.
+    #[inline]
+    pub fn error_description(mut self, description: impl Into<String>) -> Self {
+        self.error_description = Some(description.into());
+        self
+    }
+    /// Sets a function rendering the response when authentication fails, instead of the
+    /// default `401 Unauthorized` or `403 Forbidden` status error.
+    ///
+    /// The `WWW-Authenticate` header is set before it is called.
+    #[inline]
+    pub fn on_failure<F>(mut self, renderer: F) -> Self
+    where
+        F: Fn(JwtAuthState, &mut Response) + Send + Sync + 'static,
+    {
+        self.failure_renderer = Some(Box::new(renderer));
+        self
+    }
+
+    /// Sets a checker called after the token is decoded, revoked tokens are handled like invalid
+    /// tokens and the auth state is set to [`JwtAuthState::Forbidden`].
+    #[inline]
+    pub fn revocation_checker(mut self, checker: impl JwtRevocationChecker<C> + 'static) -> Self {
+        self.revocation_checker = Some(Box::new(checker));
+        self
+    }
+
     /// Get decoder mutable reference.
     #[inline]
     pub fn decoder_mut(&mut self) -> &mut D {
@@ -305,6 +420,40 @@ where
         self
     }
 
+    fn reject(&self, state: JwtAuthState, res: &mut Response) {
+        if let Some(realm) = &self.realm {
+            let mut challenge = format!("Bearer realm=\"{}\"", escape_quoted(realm));
+            if matches!(state, JwtAuthState::Forbidden | JwtAuthState::Expired) {
+                challenge.push_str(", error=\"invalid_token\"");
+                if let Some(description) = &self.error_description {
+                    challenge.push_str(&format!(
+                        ", error_description=\"{}\"",
+                        escape_quoted(description)
+                    ));
+                }
+            }
+            match HeaderValue::from_str(&challenge) {
+                Ok(value) => {
+                    res.headers_mut().insert(WWW_AUTHENTICATE, value);
+                }
+                Err(e) => {
+                    tracing::error!(error = ?e, "invalid www-authenticate header value");
+                }
+            }
+        }
+        // Invalid and expired tokens get `403 Forbidden`, unless the `invalid_token` challenge is
+        // sent, which must come with `401 Unauthorized` (RFC 6750).
+        if let Some(renderer) = &self.failure_renderer {
+            renderer(state, res);
+        } else if matches!(state, JwtAuthState::Forbidden | JwtAuthState::Expired)
+            && self.realm.is_none()
+        {
+            res.render(StatusError::forbidden());
+        } else {
+            res.render(StatusError::unauthorized());
+        }
+    }
+
     async fn find_token(&self, req: &mut Request) -> Option<String> {
         for finder in &self.finders {
             if let Some(token) = finder.find_token(req).await {
@@ -315,6 +464,23 @@ where
     }
 }
 
+/// Returns the kind of the first [`JwtError`] in the error source chain.
+fn jwt_error_kind(error: &(dyn std::error::Error + 'static)) -> Option<ErrorKind> {
+    let mut source = Some(error);
+    while let Some(error) = source {
+        if let Some(error) = error.downcast_ref::<JwtError>() {
+            return Some(error.kind().clone());
+        }
+        source = error.source();
+    }
+    None
+}
+
+/// Escapes a value used in a quoted string of a header.
+fn escape_quoted(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
 #[async_trait]
 impl<C, D> Handler for JwtAuth<C, D>
 where
@@ -332,16 +498,51 @@ where
         if let Some(token) = token {
             match self.decoder.decode::<C>(&token, depot).await {
                 Ok(data) => {
+                    // Refresh tokens can only be exchanged for new tokens by `JwtRefresh`.
+                    let refused = if refresh::is_refresh_token(&data.header) {
+                        tracing::info!("jwt auth token is a refresh token");
+                        true
+                    } else if let Some(checker) = &self.revocation_checker {
+                        let revoked = checker.is_revoked(&data, depot).await;
+                        if revoked {
+                            tracing::info!("jwt auth token is revoked");
+                        }
+                        revoked
+                    } else {
+                        false
+                    };
+                    if refused {
+                        depot.insert(JWT_AUTH_STATE_KEY, JwtAuthState::Forbidden);
+                        if !self.force_passed {
+                            self.reject(JwtAuthState::Forbidden, res);
+                            ctrl.skip_rest();
+                        }
+                        return;
+                    }
+                    if let Some(AcceptedWebSocketProtocol(protocol)) = req.extensions().get() {
+                        if let Ok(protocol) = HeaderValue::from_str(protocol) {
+                            res.headers_mut().insert(SEC_WEBSOCKET_PROTOCOL, protocol);
+                        }
+                    }
+                    depot.insert(JWT_AUTH_HEADER_KEY, data.header.clone());
                     depot.insert(JWT_AUTH_DATA_KEY, data);
                     depot.insert(JWT_AUTH_STATE_KEY, JwtAuthState::Authorized);
                     depot.insert(JWT_AUTH_TOKEN_KEY, token);
                 }
                 Err(e) => {
                     tracing::info!(error = ?e, "jwt auth error");
-                    depot.insert(JWT_AUTH_STATE_KEY, JwtAuthState::Forbidden);
+                    let kind = jwt_error_kind(&e);
+                    let state = match kind {
+                        Some(ErrorKind::ExpiredSignature) => JwtAuthState::Expired,
+                        _ => JwtAuthState::Forbidden,
+                    };
+                    if let Some(kind) = kind {
+                        depot.insert(JWT_AUTH_ERROR_KIND_KEY, kind);
+                    }
+                    depot.insert(JWT_AUTH_STATE_KEY, state);
                     depot.insert(JWT_AUTH_ERROR_KEY, e);
                     if !self.force_passed {
-                        res.render(StatusError::forbidden());
+                        self.reject(state, res);
                         ctrl.skip_rest();
                     }
                 }
@@ -349,7 +550,7 @@ where
         } else {
             depot.insert(JWT_AUTH_STATE_KEY, JwtAuthState::Unauthorized);
             if !self.force_passed {
-                res.render(StatusError::unauthorized());
+                self.reject(JwtAuthState::Unauthorized, res);
                 ctrl.skip_rest();
             }
         }
@@ -439,4 +640,361 @@ mod tests {
         let content = access(&service, &token).await;
         assert!(content.contains("Forbidden"));
     }
+
+    #[tokio::test]
+    async fn test_www_authenticate() {
+        #[handler]
+        async fn hello() -> &'static str {
+            "hello"
+        }
+
+        let auth_handler: JwtAuth<JwtClaims, ConstDecoder> =
+            JwtAuth::new(ConstDecoder::from_secret(b"ABCDEF"))
+                .realm("api")
+                .error_description("The access token expired");
+        let service = Service::new(Router::new().hoop(auth_handler).get(hello));
+
+        let res = TestClient::get("http://127.0.0.1:5801/")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::UNAUTHORIZED));
+        assert_eq!(
+            res.headers().get(WWW_AUTHENTICATE).unwrap(),
+            "Bearer realm=\"api\""
+        );
+
+        let res = TestClient::get("http://127.0.0.1:5801/")
+            .add_header("Authorization", "Bearer invalid", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::UNAUTHORIZED));
+        assert_eq!(
+            res.headers().get(WWW_AUTHENTICATE).unwrap(),
+            "Bearer realm=\"api\", error=\"invalid_token\", error_description=\"The access token expired\""
+        );
+
+        let auth_handler: JwtAuth<JwtClaims, ConstDecoder> =
+            JwtAuth::new(ConstDecoder::from_secret(b"ABCDEF")).on_failure(|state, res| {
+                res.status_code(StatusCode::UNAUTHORIZED);
+                res.render(Json(serde_json::json!({ "state": format!("{state:?}") })));
+            });
+        let service = Service::new(Router::new().hoop(auth_handler).get(hello));
+        let mut res = TestClient::get("http://127.0.0.1:5801/")
+            .add_header("Authorization", "Bearer invalid", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::UNAUTHORIZED));
+        assert!(res.headers().get(WWW_AUTHENTICATE).is_none());
+        assert_eq!(res.take_string().await.unwrap(), r#"{"state":"Forbidden"}"#);
+    }
+
+    #[tokio::test]
+    async fn test_revocation_checker() {
+        #[derive(Debug, Serialize, Deserialize)]
+        struct JtiClaims {
+            jti: String,
+            exp: i64,
+        }
+
+        struct RevokedIds(Vec<String>);
+        #[async_trait]
+        impl JwtRevocationChecker<JtiClaims> for RevokedIds {
+            async fn is_revoked(&self, data: &TokenData<JtiClaims>, _depot: &Depot) -> bool {
+                self.0.contains(&data.claims.jti)
+            }
+        }
+
+        #[handler]
+        async fn hello() -> &'static str {
+            "hello"
+        }
+
+        let auth_handler: JwtAuth<JtiClaims, ConstDecoder> =
+            JwtAuth::new(ConstDecoder::from_secret(b"ABCDEF"))
+                .revocation_checker(RevokedIds(vec!["revoked".into()]));
+        let service = Service::new(Router::new().hoop(auth_handler).get(hello));
+
+        for (jti, status) in [
+            ("valid", StatusCode::OK),
+            ("revoked", StatusCode::FORBIDDEN),
+        ] {
+            let claim = JtiClaims {
+                jti: jti.into(),
+                exp: (OffsetDateTime::now_utc() + Duration::days(1)).unix_timestamp(),
+            };
+            let token = jsonwebtoken::encode(
+                &jsonwebtoken::Header::default(),
+                &claim,
+                &EncodingKey::from_secret(b"ABCDEF"),
+            )
+            .unwrap();
+            let res = TestClient::get("http://127.0.0.1:5801/")
+                .add_header("Authorization", format!("Bearer {token}"), true)
+                .send(&service)
+                .await;
+            assert_eq!(res.status_code, Some(status));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_jwt_auth_header() {
+        #[handler]
+        async fn kid(depot: &mut Depot) -> String {
+            depot
+                .jwt_auth_header()
+                .and_then(|header| header.kid.clone())
+                .unwrap_or_default()
+        }
+
+        let auth_handler: JwtAuth<JwtClaims, ConstDecoder> =
+            JwtAuth::new(ConstDecoder::from_secret(b"ABCDEF"));
+        let service = Service::new(Router::new().hoop(auth_handler).get(kid));
+
+        let claim = JwtClaims {
+            user: "root".into(),
+            exp: (OffsetDateTime::now_utc() + Duration::days(1)).unix_timestamp(),
+        };
+        let header = jsonwebtoken::Header {
+            kid: Some("key-2024".into()),
+            ..Default::default()
+        };
+        let token =
+            jsonwebtoken::encode(&header, &claim, &EncodingKey::from_secret(b"ABCDEF")).unwrap();
+        let content = TestClient::get("http://127.0.0.1:5801/")
+            .add_header("Authorization", format!("Bearer {token}"), true)
+            .send(&service)
+            .await
+            .take_string()
+            .await
+            .unwrap();
+        assert_eq!(content, "key-2024");
+    }
+
+    #[tokio::test]
+    async fn test_jwt_auth_optional() {
+        #[handler]
+        async fn greet(depot: &mut Depot) -> String {
+            match depot.jwt_auth_state() {
+                JwtAuthState::Authorized => {
+                    let data = depot.jwt_auth_data::<JwtClaims>().unwrap();
+                    format!("hello {}", data.claims.user)
+                }
+                state => format!("{state:?}"),
+            }
+        }
+
+        let auth_handler: JwtAuth<JwtClaims, ConstDecoder> =
+            JwtAuth::optional(ConstDecoder::from_secret(b"ABCDEF"));
+        let service = Service::new(Router::new().hoop(auth_handler).get(greet));
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        assert_eq!(res.take_string().await.unwrap(), "Unauthorized");
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/")
+            .add_header("Authorization", "Bearer invalid", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        assert_eq!(res.take_string().await.unwrap(), "Forbidden");
+
+        let claim = JwtClaims {
+            user: "root".into(),
+            exp: (OffsetDateTime::now_utc() + Duration::days(1)).unix_timestamp(),
+        };
+        let token = jsonwebtoken::encode(
+            &jsonwebtoken::Header::default(),
+            &claim,
+            &EncodingKey::from_secret(b"ABCDEF"),
+        )
+        .unwrap();
+        let mut res = TestClient::get("http://127.0.0.1:5801/")
+            .add_header("Authorization", format!("Bearer {token}"), true)
+            .send(&service)
+            .await;
+        assert_eq!(res.take_string().await.unwrap(), "hello root");
+    }
+
+    #[tokio::test]
+    async fn test_jwt_auth_error_kind() {
+        #[derive(Debug, Serialize, Deserialize)]
+        struct NbfClaims {
+            user: String,
+            exp: i64,
+            nbf: i64,
+        }
+
+        #[handler]
+        async fn error_kind(depot: &mut Depot) -> String {
+            format!("{:?}", depot.jwt_auth_error_kind())
+        }
+
+        let auth_handler: JwtAuth<NbfClaims, ConstDecoder> =
+            JwtAuth::optional(ConstDecoder::from_secret(b"ABCDEF"));
+        let service = Service::new(Router::new().hoop(auth_handler).get(error_kind));
+
+        let now = OffsetDateTime::now_utc();
+        let cases = [
+            (
+                now + Duration::days(1),
+                now - Duration::days(1),
+                b"ABCDEF",
+                "None",
+            ),
+            (
+                now - Duration::days(1),
+                now - Duration::days(2),
+                b"ABCDEF",
+                "Some(ExpiredSignature)",
+            ),
+            (
+                now + Duration::days(2),
+                now + Duration::days(1),
+                b"ABCDEF",
+                "Some(ImmatureSignature)",
+            ),
+            (
+                now + Duration::days(1),
+                now - Duration::days(1),
+                b"FEDCBA",
+                "Some(InvalidSignature)",
+            ),
+        ];
+        for (exp, nbf, secret, expected) in cases {
+            let claim = NbfClaims {
+                user: "root".into(),
+                exp: exp.unix_timestamp(),
+                nbf: nbf.unix_timestamp(),
+            };
+            let token = jsonwebtoken::encode(
+                &jsonwebtoken::Header::default(),
+                &claim,
+                &EncodingKey::from_secret(secret),
+            )
+            .unwrap();
+            let content = TestClient::get("http://127.0.0.1:5801/")
+                .add_header("Authorization", format!("Bearer {token}"), true)
+                .send(&service)
+                .await
+                .take_string()
+                .await
+                .unwrap();
+            assert_eq!(content, expected);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_jwt_auth_expired_state() {
+        #[handler]
+        async fn state(depot: &mut Depot) -> String {
+            format!("{:?}", depot.jwt_auth_state())
+        }
+
+        let auth_handler: JwtAuth<JwtClaims, ConstDecoder> =
+            JwtAuth::optional(ConstDecoder::from_secret(b"ABCDEF"));
+        let service = Service::new(Router::new().hoop(auth_handler).get(state));
+
+        let now = OffsetDateTime::now_utc();
+        let cases = [
+            (now + Duration::days(1), b"ABCDEF", "Authorized"),
+            (now - Duration::days(1), b"ABCDEF", "Expired"),
+            (now + Duration::days(1), b"FEDCBA", "Forbidden"),
+        ];
+        for (exp, secret, expected) in cases {
+            let claim = JwtClaims {
+                user: "root".into(),
+                exp: exp.unix_timestamp(),
+            };
+            let token = jsonwebtoken::encode(
+                &jsonwebtoken::Header::default(),
+                &claim,
+                &EncodingKey::from_secret(secret),
+            )
+            .unwrap();
+            let content = TestClient::get("http://127.0.0.1:5801/")
+                .add_header("Authorization", format!("Bearer {token}"), true)
+                .send(&service)
+                .await
+                .take_string()
+                .await
+                .unwrap();
+            assert_eq!(content, expected);
+        }
+
+        let claim = JwtClaims {
+            user: "root".into(),
+            exp: (now - Duration::days(1)).unix_timestamp(),
+        };
+        let token = jsonwebtoken::encode(
+            &jsonwebtoken::Header::default(),
+            &claim,
+            &EncodingKey::from_secret(b"ABCDEF"),
+        )
+        .unwrap();
+
+        // Without a realm, expired tokens are rejected like other invalid tokens.
+        let auth_handler: JwtAuth<JwtClaims, ConstDecoder> =
+            JwtAuth::new(ConstDecoder::from_secret(b"ABCDEF"));
+        let service = Service::new(Router::new().hoop(auth_handler).get(state));
+        let res = TestClient::get("http://127.0.0.1:5801/")
+            .add_header("Authorization", format!("Bearer {token}"), true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::FORBIDDEN));
+        assert!(res.headers().get("www-authenticate").is_none());
+
+        // With a realm, expired tokens get the `invalid_token` challenge and `401 Unauthorized`,
+        // so clients know to refresh them.
+        let auth_handler: JwtAuth<JwtClaims, ConstDecoder> =
+            JwtAuth::new(ConstDecoder::from_secret(b"ABCDEF")).realm("api");
+        let service = Service::new(Router::new().hoop(auth_handler).get(state));
+        let res = TestClient::get("http://127.0.0.1:5801/")
+            .add_header("Authorization", format!("Bearer {token}"), true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::UNAUTHORIZED));
+        assert_eq!(
+            res.headers().get("www-authenticate").unwrap(),
+            r#"Bearer realm="api", error="invalid_token""#
+        );
+    }
+
+    #[tokio::test]
+    async fn test_websocket_protocol_echo() {
+        #[handler]
+        async fn hello() -> &'static str {
+            "hello"
+        }
+
+        let auth_handler: JwtAuth<JwtClaims, ConstDecoder> =
+            JwtAuth::new(ConstDecoder::from_secret(b"ABCDEF"))
+                .finders(vec![Box::new(WebSocketProtocolFinder::new())]);
+        let service = Service::new(Router::new().hoop(auth_handler).get(hello));
+
+        let claim = JwtClaims {
+            user: "root".into(),
+            exp: (OffsetDateTime::now_utc() + Duration::days(1)).unix_timestamp(),
+        };
+        let token = jsonwebtoken::encode(
+            &jsonwebtoken::Header::default(),
+            &claim,
+            &EncodingKey::from_secret(b"ABCDEF"),
+        )
+        .unwrap();
+        let res = TestClient::get("http://127.0.0.1:5801/")
+            .add_header(
+                "Sec-WebSocket-Protocol",
+                format!("access_token, {token}"),
+                true,
+            )
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        assert_eq!(
+            res.headers().get(SEC_WEBSOCKET_PROTOCOL).unwrap(),
+            "access_token"
+        );
+    }
 }
@@ -3,8 +3,7 @@
 use std::future::Future;
 use std::str::FromStr;
 use std::sync::Arc;
-use std::time::SystemTime;
-use std::time::UNIX_EPOCH;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
 use base64::engine::general_purpose::URL_SAFE_NO_PAD;
 use base64::Engine;
@@ -20,6 +19,7 @@ use salvo_core::Depot;
 use serde::de::DeserializeOwned;
 use serde::Deserialize;
 use tokio::sync::{Notify, RwLock};
+use tokio::task::JoinHandle;
 
 use super::{JwtAuthDecoder, JwtAuthError};
 
@@ -36,6 +36,7 @@ pub struct OidcDecoder {
     http_client: HyperClient,
     cache: Arc<RwLock<JwkSetStore>>,
     cache_state: Arc<CacheState>,
+    cache_ttl: Option<Duration>,
     notifier: Arc<Notify>,
 }
 
@@ -68,6 +69,9 @@ where
     pub http_client: Option<HyperClient>,
     /// The validation options for the decoder.
     pub validation: Option<Validation>,
+    /// How long the fetched JWKS is cached, overriding the `Cache-Control` header of the JWKS
+    /// response.
+    pub cache_ttl: Option<Duration>,
 }
 impl<T> DecoderBuilder<T>
 where
@@ -79,6 +83,7 @@ where
             issuer,
             http_client: None,
             validation: None,
+            cache_ttl: None,
         }
     }
     /// Set the http client for the decoder.
@@ -92,19 +97,52 @@ where
         self
     }
 
+    /// Set the accepted audiences, tokens must have an `aud` claim matching one of them.
+    pub fn audience<A: ToString>(mut self, audience: &[A]) -> Self {
+        self.validation_mut().set_audience(audience);
+        self
+    }
+    /// Set the accepted issuers, tokens must have an `iss` claim matching one of them.
+    ///
+    /// The issuer passed to [`DecoderBuilder::new`] is only used to discover the keys.
+    pub fn accepted_issuers<A: ToString>(mut self, issuer: &[A]) -> Self {
+        self.validation_mut().set_issuer(issuer);
+        self
+    }
+    /// Set the leeway in seconds added when validating time based claims, e.g. `exp` and `nbf`.
+    pub fn leeway(mut self, leeway: u64) -> Self {
+        self.validation_mut().leeway = leeway;
+        self
+    }
+
+    fn validation_mut(&mut self) -> &mut Validation {
+        self.validation
+            .get_or_insert_with(|| crate::decoder::default_validation(Algorithm::RS256))
+    }
+
+    /// Set how long the fetched JWKS is cached.
+    ///
+    /// By default the `max-age` of the `Cache-Control` header of the JWKS response is used, or
+    /// one second if it is absent.
+    pub fn cache_ttl(mut self, ttl: Duration) -> Self {
+        self.cache_ttl = Some(ttl);
+        self
+    }
+
     /// Build a `OidcDecoder`.
     pub fn build(self) -> impl Future<Output = Result<OidcDecoder, JwtAuthError>> {
         let Self {
             issuer,
             http_client,
             validation,
+            cache_ttl,
         } = self;
         let issuer = issuer.as_ref().trim_end_matches('/').to_string();
 
         //Create an empty JWKS to initalize our Cache
         let jwks = JwkSet { keys: Vec::new() };
 
-        let validation = validation.unwrap_or_default();
+        let validation = validation.unwrap_or_else(|| crate::decoder::default_validation(Algorithm::RS256));
         let cache = Arc::new(RwLock::new(JwkSetStore::new(jwks, CachePolicy::default(), validation)));
         let cache_state = Arc::new(CacheState::new());
 
@@ -120,6 +158,7 @@ where
             http_client,
             cache,
             cache_state,
+            cache_ttl,
             notifier: Arc::new(Notify::new()),
         };
         async move {
@@ -143,6 +182,28 @@ impl OidcDecoder {
         DecoderBuilder::new(issuer)
     }
 
+    /// Fetches the JWKS immediately, e.g. right after a key rotation at the identity provider.
+    pub async fn prefetch(&self) -> Result<UpdateAction, JwtAuthError> {
+        self.update_cache().await
+    }
+
+    /// Spawns a task refreshing the JWKS in the background every `interval` plus a random delay
+    /// up to `jitter`, so that rotated keys are known before tokens signed with them arrive.
+    ///
+    /// The jitter prevents many instances from hitting the identity provider at the same time.
+    /// The task runs until the returned handle is aborted.
+    pub fn spawn_refresh(&self, interval: Duration, jitter: Duration) -> JoinHandle<()> {
+        let decoder = self.clone();
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(interval + random_jitter(jitter)).await;
+                if let Err(e) = decoder.update_cache().await {
+                    tracing::error!(error = ?e, "refresh jwks failed");
+                }
+            }
+        })
+    }
+
     fn config_url(&self) -> String {
         format!("{}/.well-known/openid-configuration", &self.issuer)
     }
@@ -166,7 +227,10 @@ impl OidcDecoder {
         let cache_policy = {
             // Determine it from the cache_control header
             let cache_control = res.headers().get(CACHE_CONTROL);
-            let cache_policy = CachePolicy::from_header_val(cache_control);
+            let mut cache_policy = CachePolicy::from_header_val(cache_control);
+            if let Some(cache_ttl) = self.cache_ttl {
+                cache_policy.max_age = cache_ttl;
+            }
             Some(cache_policy)
         };
         let jwks = res.into_body().collect().await?.to_bytes();
@@ -382,9 +446,138 @@ fn b64_decode<T: AsRef<[u8]>>(input: T) -> Result<Vec<u8>, base64::DecodeError>
     URL_SAFE_NO_PAD.decode(input.as_ref())
 }
 
+/// Returns a random duration up to `max`.
+fn random_jitter(max: Duration) -> Duration {
+    let max_millis = max.as_millis() as u64;
+    if max_millis == 0 {
+        return Duration::ZERO;
+    }
+    Duration::from_millis(fastrand::u64(..max_millis))
+}
+
 pub(crate) fn current_time() -> u64 {
     SystemTime::now()
         .duration_since(UNIX_EPOCH)
         .expect("Time Went Backwards")
         .as_secs()
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use salvo::conn::{Acceptor, Listener, TcpListener};
+    use salvo::http::header::HOST;
+    use salvo::prelude::*;
+    use serde::Serialize;
+
+    use super::*;
+
+    struct Issuer {
+        jwks_fetches: AtomicUsize,
+    }
+    #[handler]
+    impl Issuer {
+        async fn handle(&self, req: &mut Request, res: &mut Response) {
+            if req.uri().path() == "/.well-known/openid-configuration" {
+                let host = req.header::<String>(HOST).unwrap_or_default();
+                res.render(Json(serde_json::json!({ "jwks_uri": format!("http://{host}/jwks") })));
+                return;
+            }
+            // The keys are rotated after the first fetch.
+            let keys = if self.jwks_fetches.fetch_add(1, Ordering::SeqCst) == 0 {
+                serde_json::json!([])
+            } else {
+                serde_json::json!([{ "kty": "RSA", "kid": "rotated", "alg": "RS256", "n": "AQAB", "e": "AQAB" }])
+            };
+            res.add_header(CACHE_CONTROL, "max-age=60", true).unwrap();
+            res.render(Json(serde_json::json!({ "keys": keys })));
+        }
+    }
+
+    async fn spawn_issuer() -> String {
+        let acceptor = TcpListener::new("127.0.0.1:0").bind().await;
+        let issuer = format!("http://{}", acceptor.holdings()[0].local_addr.clone().into_std().unwrap());
+        let router = Router::with_path("{**rest}").get(Issuer {
+            jwks_fetches: AtomicUsize::new(0),
+        });
+        tokio::spawn(async move {
+            Server::new(acceptor).serve(router).await;
+        });
+        issuer
+    }
+
+    fn http_client() -> HyperClient {
+        let https = HttpsConnectorBuilder::new()
+            .with_native_roots()
+            .unwrap()
+            .https_or_http()
+            .enable_http1()
+            .build();
+        Client::builder(TokioExecutor::new()).build(https)
+    }
+
+    #[test]
+    fn test_builder_setters_keep_default_validation() {
+        #[derive(Debug, Serialize, Deserialize)]
+        struct Claims {
+            aud: String,
+            nbf: u64,
+            exp: u64,
+        }
+
+        let builder = OidcDecoder::builder("https://issuer.example").audience(&["api"]);
+        let info = DecodingInfo::new(
+            DecodingKey::from_secret(b"secret"),
+            Algorithm::HS256,
+            builder.validation.as_ref().unwrap(),
+        );
+        let now = current_time();
+        for (nbf, valid) in [(now + 3600, false), (now - 60, true)] {
+            let claims = Claims {
+                aud: "api".into(),
+                nbf,
+                exp: now + 7200,
+            };
+            let token = jsonwebtoken::encode(
+                &jsonwebtoken::Header::default(),
+                &claims,
+                &jsonwebtoken::EncodingKey::from_secret(b"secret"),
+            )
+            .unwrap();
+            assert_eq!(info.decode::<Claims>(&token).is_ok(), valid);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_cache_ttl() {
+        let issuer = spawn_issuer().await;
+        let decoder = OidcDecoder::builder(&issuer).http_client(http_client()).build().await.unwrap();
+        assert_eq!(decoder.cache.read().await.cache_policy.max_age, Duration::from_secs(60));
+
+        let decoder = OidcDecoder::builder(&issuer)
+            .http_client(http_client())
+            .cache_ttl(Duration::from_secs(300))
+            .build()
+            .await
+            .unwrap();
+        assert_eq!(decoder.cache.read().await.cache_policy.max_age, Duration::from_secs(300));
+    }
+
+    #[tokio::test]
+    async fn test_prefetch() {
+        let issuer = spawn_issuer().await;
+        let decoder = OidcDecoder::builder(&issuer).http_client(http_client()).build().await.unwrap();
+        assert_eq!(decoder.prefetch().await.unwrap(), UpdateAction::JwksUpdate);
+        assert_eq!(decoder.cache.read().await.jwks.keys.len(), 1);
+        assert_eq!(decoder.prefetch().await.unwrap(), UpdateAction::NoUpdate);
+    }
+
+    #[test]
+    fn test_random_jitter() {
+        assert_eq!(random_jitter(Duration::ZERO), Duration::ZERO);
+        for _ in 0..100 {
+            assert!(random_jitter(Duration::from_secs(5)) < Duration::from_secs(5));
+        }
+    }
+}
@@ -0,0 +1,382 @@
+use std::borrow::Cow;
+
+use jsonwebtoken::{EncodingKey, Header};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+use salvo_core::http::cookie::{Cookie, SameSite};
+use salvo_core::http::{Request, Response, StatusError};
+use salvo_core::writing::Json;
+use salvo_core::{async_trait, Depot, FlowCtrl, Handler};
+
+use crate::{
+    CookieFinder, FormFinder, JwtAuthDecoder, JwtRevocationChecker, JwtTokenFinder, TokenData,
+};
+
+/// The `typ` header of refresh tokens minted by [`JwtRefresh`].
+///
+/// [`JwtAuth`](crate::JwtAuth) refuses tokens with it, and [`JwtRefresh`] only accepts them, so
+/// access and refresh tokens can not be used in place of each other even if they are signed with
+/// the same key.
+pub const REFRESH_TOKEN_TYPE: &str = "refresh+jwt";
+
+/// Returns whether the token is a refresh token, see [`REFRESH_TOKEN_TYPE`].
+pub(crate) fn is_refresh_token(header: &Header) -> bool {
+    header
+        .typ
+        .as_deref()
+        .is_some_and(|typ| typ.eq_ignore_ascii_case(REFRESH_TOKEN_TYPE))
+}
+
+/// Claims of a refresh token used by [`JwtRefresh`].
+pub trait RefreshClaims: Serialize + DeserializeOwned + Send + Sync + 'static {
+    /// Claims of the minted access token.
+    type Access: Serialize;
+
+    /// Returns the claims of the new access token, including its `exp`.
+    fn access_claims(&self) -> Self::Access;
+
+    /// Returns the claims of the refresh token replacing this one, usually with a new `jti`
+    /// and `exp`.
+    fn rotate(&self) -> Self;
+}
+
+/// Tokens rendered by [`JwtRefresh`] as JSON.
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub struct TokenPair {
+    /// The new access token.
+    pub access_token: String,
+    /// The new refresh token, the old one should not be accepted anymore.
+    pub refresh_token: String,
+    /// The token type, always `Bearer`.
+    pub token_type: String,
+}
+
+/// `JwtRefresh` is a handler exchanging a refresh token for a new access token and a new
+/// refresh token.
+///
+/// The refresh token is read from the `refresh_token` form field or cookie by default and
+/// decoded with the decoder. If it is valid, a [`TokenPair`] is rendered as JSON, otherwise the
+/// response is `401 Unauthorized`.
+///
+/// Refresh tokens carry the [`REFRESH_TOKEN_TYPE`] `typ` header, tokens without it are refused.
+/// Use [`encode_refresh_token`](Self::encode_refresh_token) to mint the first refresh token,
+/// e.g. on login.
+///
+/// To make refresh tokens single use, set a [revocation checker](Self::revocation_checker)
+/// which marks the `jti` of the token as used and reports whether it was already used.
+#[non_exhaustive]
+pub struct JwtRefresh<C, D> {
+    /// The decoder of refresh tokens.
+    pub decoder: D,
+    /// The finders list of refresh tokens.
+    pub finders: Vec<Box<dyn JwtTokenFinder>>,
+    /// The key used to sign new tokens.
+    pub encoding_key: EncodingKey,
+    /// The header of new tokens, the `typ` of refresh tokens is set to [`REFRESH_TOKEN_TYPE`].
+    pub header: Header,
+    /// The checker of revoked refresh tokens.
+    pub revocation_checker: Option<Box<dyn JwtRevocationChecker<C>>>,
+    /// The name of the cookie the new refresh token is written to.
+    pub cookie_name: Option<Cow<'static, str>>,
+}
+
+impl<C, D> JwtRefresh<C, D>
+where
+    C: RefreshClaims,
+    D: JwtAuthDecoder + Send + Sync + 'static,
+{
+    /// Create new `JwtRefresh`.
+    #[inline]
+    pub fn new(decoder: D, encoding_key: EncodingKey) -> Self {
+        Self {
+            decoder,
+            finders: vec![
+                Box::new(FormFinder::new("refresh_token")),
+                Box::new(CookieFinder::new("refresh_token")),
+            ],
+            encoding_key,
+            header: Header::default(),
+            revocation_checker: None,
+            cookie_name: None,
+        }
+    }
+    /// Sets the header of new tokens, the default uses `HS256`.
+    #[inline]
+    pub fn header(mut self, header: Header) -> Self {
+        self.header = header;
+        self
+    }
+    /// Sets the finders list of refresh tokens.
+    #[inline]
+    pub fn finders(mut self, finders: Vec<Box<dyn JwtTokenFinder>>) -> Self {
+        self.finders = finders;
+        self
+    }
+    /// Sets the checker of revoked refresh tokens.
+    #[inline]
+    pub fn revocation_checker(mut self, checker: impl JwtRevocationChecker<C> + 'static) -> Self {
+        self.revocation_checker = Some(Box::new(checker));
+        self
+    }
+    /// Sets the name of an http only cookie the new refresh token is written to, in addition to
+    /// the JSON response.
+    #[inline]
+    pub fn cookie_name(mut self, name: impl Into<Cow<'static, str>>) -> Self {
+        self.cookie_name = Some(name.into());
+        self
+    }
+
+    /// Encode a refresh token accepted by this handler.
+    pub fn encode_refresh_token(&self, claims: &C) -> Result<String, jsonwebtoken::errors::Error> {
+        let mut header = self.header.clone();
+        header.typ = Some(REFRESH_TOKEN_TYPE.into());
+        jsonwebtoken::encode(&header, claims, &self.encoding_key)
+    }
+
+    async fn find_token(&self, req: &mut Request) -> Option<String> {
+        for finder in &self.finders {
+            if let Some(token) = finder.find_token(req).await {
+                return Some(token);
+            }
+        }
+        None
+    }
+
+    async fn verify(&self, req: &mut Request, depot: &mut Depot) -> Option<TokenData<C>> {
+        let token = self.find_token(req).await?;
+        let data = match self.decoder.decode::<C>(&token, depot).await {
+            Ok(data) => data,
+            Err(e) => {
+                tracing::info!(error = ?e, "jwt refresh token error");
+                return None;
+            }
+        };
+        if !is_refresh_token(&data.header) {
+            tracing::info!("jwt refresh token is not a refresh token");
+            return None;
+        }
+        if let Some(checker) = &self.revocation_checker {
+            if checker.is_revoked(&data, depot).await {
+                tracing::info!("jwt refresh token is revoked");
+                return None;
+            }
+        }
+        Some(data)
+    }
+
+    fn mint(&self, claims: &C) -> Result<TokenPair, jsonwebtoken::errors::Error> {
+        let access_token =
+            jsonwebtoken::encode(&self.header, &claims.access_claims(), &self.encoding_key)?;
+        let refresh_token = self.encode_refresh_token(&claims.rotate())?;
+        Ok(TokenPair {
+            access_token,
+            refresh_token,
+            token_type: "Bearer".into(),
+        })
+    }
+}
+
+#[async_trait]
+impl<C, D> Handler for JwtRefresh<C, D>
+where
+    C: RefreshClaims,
+    D: JwtAuthDecoder + Send + Sync + 'static,
+{
+    async fn handle(
+        &self,
+        req: &mut Request,
+        depot: &mut Depot,
+        res: &mut Response,
+        ctrl: &mut FlowCtrl,
+    ) {
+        let Some(data) = self.verify(req, depot).await else {
+            res.render(StatusError::unauthorized());
+            ctrl.skip_rest();
+            return;
+        };
+        match self.mint(&data.claims) {
+            Ok(pair) => {
+                if let Some(cookie_name) = &self.cookie_name {
+                    let cookie = Cookie::build((cookie_name.clone(), pair.refresh_token.clone()))
+                        .http_only(true)
+                        .secure(true)
+                        .same_site(SameSite::Strict)
+                        .path("/")
+                        .build();
+                    res.add_cookie(cookie);
+                }
+                res.render(Json(pair));
+            }
+            Err(e) => {
+                tracing::error!(error = ?e, "mint jwt tokens failed");
+                res.render(StatusError::internal_server_error());
+                ctrl.skip_rest();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use salvo_core::prelude::*;
+    use salvo_core::test::{ResponseExt, TestClient};
+    use time::{Duration, OffsetDateTime};
+
+    use super::*;
+    use crate::{ConstDecoder, JwtAuth};
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct AccessClaims {
+        sub: String,
+        exp: i64,
+    }
+    #[derive(Debug, Serialize, Deserialize)]
+    struct RefreshTokenClaims {
+        sub: String,
+        jti: u64,
+        exp: i64,
+    }
+    impl RefreshClaims for RefreshTokenClaims {
+        type Access = AccessClaims;
+        fn access_claims(&self) -> AccessClaims {
+            AccessClaims {
+                sub: self.sub.clone(),
+                exp: (OffsetDateTime::now_utc() + Duration::minutes(5)).unix_timestamp(),
+            }
+        }
+        fn rotate(&self) -> Self {
+            Self {
+                sub: self.sub.clone(),
+                jti: self.jti + 1,
+                exp: (OffsetDateTime::now_utc() + Duration::days(7)).unix_timestamp(),
+            }
+        }
+    }
+
+    #[derive(Default)]
+    struct UsedIds(Mutex<Vec<u64>>);
+    #[async_trait]
+    impl JwtRevocationChecker<RefreshTokenClaims> for UsedIds {
+        async fn is_revoked(&self, data: &TokenData<RefreshTokenClaims>, _depot: &Depot) -> bool {
+            let mut used = self.0.lock().unwrap();
+            if used.contains(&data.claims.jti) {
+                true
+            } else {
+                used.push(data.claims.jti);
+                false
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_jwt_refresh() {
+        let refresh: JwtRefresh<RefreshTokenClaims, ConstDecoder> = JwtRefresh::new(
+            ConstDecoder::from_secret(b"ABCDEF"),
+            EncodingKey::from_secret(b"ABCDEF"),
+        )
+        .revocation_checker(UsedIds::default())
+        .cookie_name("refresh_token");
+        let claims = RefreshTokenClaims {
+            sub: "root".into(),
+            jti: 1,
+            exp: (OffsetDateTime::now_utc() + Duration::days(7)).unix_timestamp(),
+        };
+        let token = refresh.encode_refresh_token(&claims).unwrap();
+        let service = Service::new(Router::with_path("refresh").post(refresh));
+
+        let mut res = TestClient::post("http://127.0.0.1:5801/refresh")
+            .add_header("Cookie", format!("refresh_token={token}"), true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        assert!(res.headers().get("set-cookie").is_some());
+        let pair: TokenPair = res.take_json().await.unwrap();
+        let decoder = ConstDecoder::from_secret(b"ABCDEF");
+        let access = decoder
+            .decode::<AccessClaims>(&pair.access_token, &mut Depot::new())
+            .await
+            .unwrap();
+        assert_eq!(access.claims.sub, "root");
+        let rotated = decoder
+            .decode::<RefreshTokenClaims>(&pair.refresh_token, &mut Depot::new())
+            .await
+            .unwrap();
+        assert_eq!(rotated.claims.jti, 2);
+
+        // The old refresh token can not be reused.
+        let res = TestClient::post("http://127.0.0.1:5801/refresh")
+            .add_header("Cookie", format!("refresh_token={token}"), true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::UNAUTHORIZED));
+
+        let res = TestClient::post("http://127.0.0.1:5801/refresh")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::UNAUTHORIZED));
+
+        // Access tokens signed with the same key are not refresh tokens.
+        let res = TestClient::post("http://127.0.0.1:5801/refresh")
+            .add_header(
+                "Cookie",
+                format!("refresh_token={}", pair.access_token),
+                true,
+            )
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::UNAUTHORIZED));
+        let untyped = jsonwebtoken::encode(
+            &Header::default(),
+            &RefreshTokenClaims {
+                sub: "root".into(),
+                jti: 10,
+                exp: (OffsetDateTime::now_utc() + Duration::days(7)).unix_timestamp(),
+            },
+            &EncodingKey::from_secret(b"ABCDEF"),
+        )
+        .unwrap();
+        let res = TestClient::post("http://127.0.0.1:5801/refresh")
+            .add_header("Cookie", format!("refresh_token={untyped}"), true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::UNAUTHORIZED));
+    }
+
+    #[tokio::test]
+    async fn test_jwt_auth_refuses_refresh_token() {
+        #[handler]
+        async fn hello() -> &'static str {
+            "hello"
+        }
+
+        let refresh: JwtRefresh<RefreshTokenClaims, ConstDecoder> = JwtRefresh::new(
+            ConstDecoder::from_secret(b"ABCDEF"),
+            EncodingKey::from_secret(b"ABCDEF"),
+        );
+        let claims = RefreshTokenClaims {
+            sub: "root".into(),
+            jti: 1,
+            exp: (OffsetDateTime::now_utc() + Duration::days(7)).unix_timestamp(),
+        };
+        let refresh_token = refresh.encode_refresh_token(&claims).unwrap();
+        let access_token = refresh.mint(&claims).unwrap().access_token;
+
+        let auth: JwtAuth<serde_json::Value, ConstDecoder> =
+            JwtAuth::new(ConstDecoder::from_secret(b"ABCDEF"));
+        let service = Service::new(Router::new().hoop(auth).get(hello));
+        let res = TestClient::get("http://127.0.0.1:5801/")
+            .add_header("Authorization", format!("Bearer {refresh_token}"), true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::FORBIDDEN));
+        let res = TestClient::get("http://127.0.0.1:5801/")
+            .add_header("Authorization", format!("Bearer {access_token}"), true)
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+    }
+}
@@ -0,0 +1,214 @@
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+use salvo_core::http::{Request, Response, StatusError};
+use salvo_core::{async_trait, Depot, FlowCtrl, Handler};
+
+use crate::JwtAuthDepotExt;
+
+/// Predicate used by [`RequireClaims`] to check the claims.
+pub type ClaimsPredicate<C> = Box<dyn Fn(&C) -> bool + Send + Sync>;
+
+/// `RequireClaims` is a middleware checking the claims decoded by [`JwtAuth`](crate::JwtAuth).
+///
+/// It should be hooped after `JwtAuth`, requests without decoded claims get `401 Unauthorized`
+/// and requests whose claims do not satisfy the requirement get `403 Forbidden`.
+///
+/// ```
+/// use salvo_core::prelude::*;
+/// use salvo_jwt_auth::RequireClaims;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct JwtClaims {
+///     username: String,
+///     scope: String,
+///     exp: i64,
+/// }
+///
+/// let admin = RequireClaims::<JwtClaims>::new(|claims| claims.username == "root");
+/// let writer = RequireClaims::<JwtClaims>::scopes(["posts:write"]);
+/// ```
+pub struct RequireClaims<C> {
+    predicate: ClaimsPredicate<C>,
+}
+
+impl<C> RequireClaims<C>
+where
+    C: DeserializeOwned + Send + Sync + 'static,
+{
+    /// Create a new `RequireClaims` which accepts requests whose claims satisfy the predicate.
+    pub fn new<F>(predicate: F) -> Self
+    where
+        F: Fn(&C) -> bool + Send + Sync + 'static,
+    {
+        Self {
+            predicate: Box::new(predicate),
+        }
+    }
+
+    /// Create a new `RequireClaims` which accepts requests whose serialized claims satisfy the
+    /// predicate.
+    pub fn json<F>(predicate: F) -> Self
+    where
+        C: Serialize,
+        F: Fn(&Value) -> bool + Send + Sync + 'static,
+    {
+        Self::new(move |claims: &C| {
+            serde_json::to_value(claims)
+                .map(|value| predicate(&value))
+                .unwrap_or(false)
+        })
+    }
+
+    /// Create a new `RequireClaims` which requires all the scopes.
+    ///
+    /// Scopes are read from the `scope` claim, a space separated string as defined by RFC 8693,
+    /// or from the `scp` claim, a string or an array of strings.
+    pub fn scopes<I, S>(scopes: I) -> Self
+    where
+        C: Serialize,
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let scopes = scopes.into_iter().map(Into::into).collect::<Vec<String>>();
+        Self::json(move |claims| {
+            let granted = claim_values(claims, "scope")
+                .into_iter()
+                .chain(claim_values(claims, "scp"))
+                .collect::<Vec<_>>();
+            scopes.iter().all(|scope| granted.contains(&scope.as_str()))
+        })
+    }
+
+    /// Create a new `RequireClaims` which requires any of the roles.
+    ///
+    /// Roles are read from the `roles` claim, a string or an array of strings.
+    pub fn roles<I, S>(roles: I) -> Self
+    where
+        C: Serialize,
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let roles = roles.into_iter().map(Into::into).collect::<Vec<String>>();
+        Self::json(move |claims| {
+            let granted = claim_values(claims, "roles");
+            roles.iter().any(|role| granted.contains(&role.as_str()))
+        })
+    }
+}
+
+/// Returns the values of a claim which is either a space separated string or an array of
+/// strings.
+fn claim_values<'a>(claims: &'a Value, name: &str) -> Vec<&'a str> {
+    match claims.get(name) {
+        Some(Value::String(value)) => value.split_whitespace().collect(),
+        Some(Value::Array(values)) => values.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    }
+}
+
+#[async_trait]
+impl<C> Handler for RequireClaims<C>
+where
+    C: DeserializeOwned + Send + Sync + 'static,
+{
+    async fn handle(
+        &self,
+        _req: &mut Request,
+        depot: &mut Depot,
+        res: &mut Response,
+        ctrl: &mut FlowCtrl,
+    ) {
+        match depot.jwt_auth_data::<C>() {
+            Some(data) if (self.predicate)(&data.claims) => {}
+            Some(_) => {
+                res.render(StatusError::forbidden());
+                ctrl.skip_rest();
+            }
+            None => {
+                res.render(StatusError::unauthorized());
+                ctrl.skip_rest();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use jsonwebtoken::EncodingKey;
+    use salvo_core::prelude::*;
+    use salvo_core::test::TestClient;
+    use serde::{Deserialize, Serialize};
+    use time::{Duration, OffsetDateTime};
+
+    use super::*;
+    use crate::{ConstDecoder, JwtAuth};
+
+    #[derive(Debug, Serialize, Deserialize)]
+    struct JwtClaims {
+        user: String,
+        scope: String,
+        roles: Vec<String>,
+        exp: i64,
+    }
+
+    #[handler]
+    async fn hello() -> &'static str {
+        "hello"
+    }
+
+    fn token(scope: &str, roles: &[&str]) -> String {
+        let claim = JwtClaims {
+            user: "root".into(),
+            scope: scope.into(),
+            roles: roles.iter().map(|r| r.to_string()).collect(),
+            exp: (OffsetDateTime::now_utc() + Duration::days(1)).unix_timestamp(),
+        };
+        jsonwebtoken::encode(
+            &jsonwebtoken::Header::default(),
+            &claim,
+            &EncodingKey::from_secret(b"ABCDEF"),
+        )
+        .unwrap()
+    }
+
+    async fn status(service: &Service, token: Option<String>) -> Option<StatusCode> {
+        let mut req = TestClient::get("http://127.0.0.1:5801/hello");
+        if let Some(token) = token {
+            req = req.add_header("Authorization", format!("Bearer {token}"), true);
+        }
+        req.send(service).await.status_code
+    }
+
+    #[tokio::test]
+    async fn test_require_claims() {
+        let auth_handler: JwtAuth<JwtClaims, ConstDecoder> =
+            JwtAuth::new(ConstDecoder::from_secret(b"ABCDEF")).force_passed(true);
+        let router = Router::new().hoop(auth_handler).push(
+            Router::with_path("hello")
+                .hoop(RequireClaims::<JwtClaims>::scopes([
+                    "posts:read",
+                    "posts:write",
+                ]))
+                .hoop(RequireClaims::<JwtClaims>::roles(["admin", "editor"]))
+                .get(hello),
+        );
+        let service = Service::new(router);
+
+        assert_eq!(status(&service, None).await, Some(StatusCode::UNAUTHORIZED));
+        let ok = token("posts:read posts:write", &["editor"]);
+        assert_eq!(status(&service, Some(ok)).await, Some(StatusCode::OK));
+        let missing_scope = token("posts:read", &["editor"]);
+        assert_eq!(
+            status(&service, Some(missing_scope)).await,
+            Some(StatusCode::FORBIDDEN)
+        );
+        let missing_role = token("posts:read posts:write", &["viewer"]);
+        assert_eq!(
+            status(&service, Some(missing_role)).await,
+            Some(StatusCode::FORBIDDEN)
+        );
+    }
+}
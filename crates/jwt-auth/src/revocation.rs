@@ -0,0 +1,13 @@
+use jsonwebtoken::TokenData;
+use salvo_core::{async_trait, Depot};
+
+/// `JwtRevocationChecker` checks whether a token is revoked after it is successfully decoded,
+/// see [`JwtAuth::revocation_checker`](crate::JwtAuth::revocation_checker).
+///
+/// Typical implementations look up the `jti` claim in a set of revoked token ids stored in a
+/// database or cache.
+#[async_trait]
+pub trait JwtRevocationChecker<C>: Send + Sync {
+    /// Returns `true` if the token is revoked.
+    async fn is_revoked(&self, data: &TokenData<C>, depot: &Depot) -> bool;
+}
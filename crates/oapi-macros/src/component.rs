@@ -3,12 +3,12 @@ use quote::{quote, quote_spanned, ToTokens};
 use syn::spanned::Spanned;
 
 use crate::doc_comment::CommentAttributes;
-use crate::feature::attributes::{AdditionalProperties, Description, Nullable};
+use crate::feature::attributes::{AdditionalProperties, Description, Format, Nullable};
 use crate::feature::validation::Minimum;
 use crate::feature::{pop_feature, Feature, FeaturesExt, IsInline, TryToTokensExt, Validatable};
-use crate::schema_type::{SchemaFormat, SchemaType, SchemaTypeInner};
+use crate::schema_type::{SchemaFormat, SchemaType, SchemaTypeInner, Variant};
 use crate::type_tree::{GenericType, TypeTree, ValueType};
-use crate::{Deprecated, DiagResult, Diagnostic, IntoInner, TryToTokens};
+use crate::{Deprecated, DiagLevel, DiagResult, Diagnostic, IntoInner, TryToTokens};
 
 #[derive(Debug)]
 pub(crate) struct ComponentSchemaProps<'c> {
@@ -67,7 +67,7 @@ impl ComponentSchema {
 
         match type_tree.generic_type {
             Some(GenericType::Map) => {
-                features.push(AdditionalProperties(true).into());
+                features.push(AdditionalProperties::FreeForm(true).into());
                 ComponentSchema::map_to_tokens(
                     &mut tokens,
                     features,
@@ -77,6 +77,23 @@ impl ComponentSchema {
                     deprecated_stream,
                 )?
             }
+            Some(GenericType::Vec | GenericType::LinkedList | GenericType::Set)
+                if features.iter().any(|feature| {
+                    matches!(
+                        feature,
+                        Feature::Format(Format(SchemaFormat::Variant(
+                            Variant::Binary | Variant::Byte
+                        )))
+                    )
+                }) =>
+            {
+                ComponentSchema::binary_to_tokens(
+                    &mut tokens,
+                    features,
+                    description,
+                    deprecated_stream,
+                )?
+            }
             Some(GenericType::Vec | GenericType::LinkedList | GenericType::Set) => {
                 ComponentSchema::vec_to_tokens(
                     &mut tokens,
@@ -238,6 +255,30 @@ impl ComponentSchema {
         Ok(())
     }
 
+    /// Binary data like `Vec<u8>` marked with `format = Binary` is documented as a string, so
+    /// multipart clients and Swagger UI treat it as a file.
+    fn binary_to_tokens(
+        tokens: &mut TokenStream,
+        mut features: Vec<Feature>,
+        description_stream: Option<&ComponentDescription<'_>>,
+        deprecated_stream: Option<TokenStream>,
+    ) -> DiagResult<()> {
+        let oapi = crate::oapi_crate();
+        let nullable: Option<Nullable> =
+            pop_feature!(features => Feature::Nullable(_)).into_inner();
+        let schema_type = ComponentSchema::get_schema_type_override(nullable, SchemaTypeInner::String)
+            .unwrap_or_else(|| quote! { .schema_type(#oapi::oapi::schema::SchemaType::basic(#oapi::oapi::schema::BasicType::String)) });
+
+        tokens.extend(quote! {
+            #oapi::oapi::Object::new()
+                #schema_type
+                #description_stream
+                #deprecated_stream
+        });
+        tokens.extend(features.try_to_token_stream()?);
+        Ok(())
+    }
+
     fn vec_to_tokens(
         tokens: &mut TokenStream,
         mut features: Vec<Feature>,
@@ -251,6 +292,7 @@ impl ComponentSchema {
         let xml = features.extract_vec_xml_feature(type_tree);
         let max_items = pop_feature!(features => Feature::MaxItems(_));
         let min_items = pop_feature!(features => Feature::MinItems(_));
+        let unique_items = pop_feature!(features => Feature::UniqueItems(_));
         let nullable: Option<Nullable> =
             pop_feature!(features => Feature::Nullable(_)).into_inner();
         let default = pop_feature!(features => Feature::Default(_))
@@ -265,7 +307,10 @@ impl ComponentSchema {
             .next()
             .expect("ComponentSchema Vec should have 1 child");
 
-        let unique = matches!(type_tree.generic_type, Some(GenericType::Set));
+        let unique = match &unique_items {
+            Some(Feature::UniqueItems(unique_items)) => unique_items.0,
+            _ => matches!(type_tree.generic_type, Some(GenericType::Set)),
+        };
 
         let component_schema = ComponentSchema::new(ComponentSchemaProps {
             type_tree: child,
@@ -309,6 +354,22 @@ impl ComponentSchema {
             #description_stream
         });
 
+        if let (Some(Feature::MinItems(min_items)), Some(Feature::MaxItems(max_items))) =
+            (&min_items, &max_items)
+        {
+            if min_items.0 > max_items.0 {
+                return Err(Diagnostic::spanned(
+                    min_items.1.span(),
+                    DiagLevel::Error,
+                    "`min_items` error: can not be greater than `max_items`",
+                ));
+            }
+        }
+
+        if let Some(unique_items) = unique_items {
+            validate(&unique_items)?;
+        }
+
         if let Some(max_items) = max_items {
             validate(&max_items)?;
             tokens.extend(max_items.try_to_token_stream()?)
@@ -429,12 +490,12 @@ impl ComponentSchema {
                             quote_spanned! {type_path.span()=>
                                 #oapi::oapi::schema::AllOf::new()
                                     #nullable_item
-                                    .item(<#type_path as #oapi::oapi::ToSchema>::to_schema(components))
+                                    .item(components.inline_schema::<#type_path>())
                                 #default
                             }
                         } else {
                             quote_spanned! {type_path.span() =>
-                                <#type_path as #oapi::oapi::ToSchema>::to_schema(components)
+                                components.inline_schema::<#type_path>()
                             }
                         };
                         schema.to_tokens(tokens);
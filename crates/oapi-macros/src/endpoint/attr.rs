@@ -5,7 +5,8 @@ use syn::{Expr, LitStr};
 
 use crate::operation::request_body::RequestBodyAttr;
 use crate::{
-    parse_utils, security_requirement::SecurityRequirementsAttr, Array, Parameter, Response, Token,
+    parse_utils, security_requirement::SecurityRequirementsAttr, Array, ExternalDocs, Parameter,
+    Response, Token,
 };
 
 #[derive(Default, Debug)]
@@ -22,11 +23,12 @@ pub(crate) struct EndpointAttr<'p> {
     pub(crate) deprecated: Option<bool>,
     pub(crate) description: Option<parse_utils::LitStrOrExpr>,
     pub(crate) summary: Option<parse_utils::LitStrOrExpr>,
+    pub(crate) external_docs: Option<ExternalDocs>,
 }
 
 impl Parse for EndpointAttr<'_> {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
-        const EXPECTED_ATTRIBUTE_MESSAGE: &str = "unexpected identifier, expected any of: operation_id, path, get, post, put, delete, options, head, patch, trace, connect, request_body, responses, params, tag, security, context_path, description, summary";
+        const EXPECTED_ATTRIBUTE_MESSAGE: &str = "unexpected identifier, expected any of: operation_id, path, get, post, put, delete, options, head, patch, trace, connect, request_body, responses, params, tag, security, context_path, description, summary, external_docs, deprecated";
         let mut attr = EndpointAttr::default();
 
         while !input.is_empty() {
@@ -88,6 +90,14 @@ impl Parse for EndpointAttr<'_> {
                     attr.description = Some(parse_utils::parse_next_lit_str_or_expr(input)?)
                 }
                 "summary" => attr.summary = Some(parse_utils::parse_next_lit_str_or_expr(input)?),
+                "deprecated" => {
+                    attr.deprecated = Some(parse_utils::parse_bool_or_true(input)?);
+                }
+                "external_docs" => {
+                    let external_docs;
+                    parenthesized!(external_docs in input);
+                    attr.external_docs = Some(external_docs.parse::<ExternalDocs>()?);
+                }
                 _ => {
                     return Err(syn::Error::new(ident.span(), EXPECTED_ATTRIBUTE_MESSAGE));
                 }
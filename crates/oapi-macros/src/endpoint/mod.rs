@@ -1,6 +1,6 @@
 use proc_macro2::{Span, TokenStream};
 use quote::{quote, ToTokens};
-use syn::{Expr, Ident, ImplItem, Item, Pat, ReturnType, Signature, Type};
+use syn::{Expr, FnArg, Ident, ImplItem, Item, Pat, ReturnType, Signature, Type};
 
 use crate::doc_comment::CommentAttributes;
 use crate::{omit_type_path_lifetimes, parse_input_type, Array, DiagResult, InputType, Operation};
@@ -87,6 +87,8 @@ pub(crate) fn generate(mut attr: EndpointAttr, input: Item) -> syn::Result<Token
             let attrs = &item_fn.attrs;
             let vis = &item_fn.vis;
             let sig = &mut item_fn.sig;
+            let (hfn, modifiers) = handle_fn(&salvo, &oapi, sig)?;
+            remove_arg_docs(sig);
             let body = &item_fn.block;
             let name = &sig.ident;
             let docs = item_fn
@@ -110,13 +112,10 @@ pub(crate) fn generate(mut attr: EndpointAttr, input: Item) -> syn::Result<Token
             };
 
             attr.doc_comments = Some(CommentAttributes::from_attributes(attrs).0);
-            attr.deprecated = if attrs.iter().any(|attr| attr.path().is_ident("deprecated")) {
-                Some(true)
-            } else {
-                None
-            };
+            if attrs.iter().any(|attr| attr.path().is_ident("deprecated")) {
+                attr.deprecated.get_or_insert(true);
+            }
 
-            let (hfn, modifiers) = handle_fn(&salvo, &oapi, sig)?;
             let meta = metadata(&salvo, &oapi, attr, name, modifiers)?;
             Ok(quote! {
                 #sdef
@@ -127,15 +126,13 @@ pub(crate) fn generate(mut attr: EndpointAttr, input: Item) -> syn::Result<Token
                 #meta
             })
         }
-        Item::Impl(item_impl) => {
+        Item::Impl(mut item_impl) => {
             let attrs = &item_impl.attrs;
 
             attr.doc_comments = Some(CommentAttributes::from_attributes(attrs).0);
-            attr.deprecated = if attrs.iter().any(|attr| attr.path().is_ident("deprecated")) {
-                Some(true)
-            } else {
-                None
-            };
+            if attrs.iter().any(|attr| attr.path().is_ident("deprecated")) {
+                attr.deprecated.get_or_insert(true);
+            }
 
             let mut hmtd = None;
             for item in &item_impl.items {
@@ -152,6 +149,11 @@ pub(crate) fn generate(mut attr: EndpointAttr, input: Item) -> syn::Result<Token
                 ));
             };
             let (hfn, modifiers) = handle_fn(&salvo, &oapi, &hmtd.sig)?;
+            for item in &mut item_impl.items {
+                if let ImplItem::Fn(method) = item {
+                    remove_arg_docs(&mut method.sig);
+                }
+            }
             let ty = &item_impl.self_ty;
             let (impl_generics, _, where_clause) = &item_impl.generics.split_for_impl();
             let name = Ident::new(&ty.to_token_stream().to_string(), Span::call_site());
@@ -230,9 +232,27 @@ fn handle_fn(
                             }
                         };
                     });
-                    modifiers.push(quote! {
-                         <#ty as #oapi::oapi::EndpointArgRegister>::register(components, operation, #idv);
-                    });
+                    let description =
+                        CommentAttributes::from_attributes(&pat.attrs).as_formatted_string();
+                    if description.is_empty() {
+                        modifiers.push(quote! {
+                             <#ty as #oapi::oapi::EndpointArgRegister>::register(components, operation, #idv);
+                        });
+                    } else {
+                        // Doc comments of the argument describe the parameter named after it, or the request body
+                        // registered by it.
+                        modifiers.push(quote! {{
+                            let has_request_body = operation.request_body.is_some();
+                            <#ty as #oapi::oapi::EndpointArgRegister>::register(components, operation, #idv);
+                            if let Some(parameter) = operation.parameters.0.iter_mut().find(|parameter| parameter.name == #idv) {
+                                parameter.description = Some(#description.into());
+                            } else if !has_request_body {
+                                if let Some(request_body) = operation.request_body.as_mut() {
+                                    request_body.description = Some(#description.into());
+                                }
+                            }
+                        }});
+                    }
                 } else {
                     return Err(syn::Error::new_spanned(pat, "invalid param definition"));
                 }
@@ -284,3 +304,13 @@ fn handle_fn(
     };
     Ok((hfn, modifiers))
 }
+
+/// Removes doc comments of the handler arguments, which are read by [`handle_fn`] but are not
+/// allowed on function parameters by the compiler.
+fn remove_arg_docs(sig: &mut Signature) {
+    for input in &mut sig.inputs {
+        if let FnArg::Typed(pat) = input {
+            pat.attrs.retain(|attr| !attr.path().is_ident("doc"));
+        }
+    }
+}
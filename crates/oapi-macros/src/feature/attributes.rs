@@ -181,6 +181,27 @@ impl From<XmlAttr> for Feature {
 }
 impl_get_name!(XmlAttr = "xml");
 
+#[derive(Clone, Debug)]
+pub(crate) struct ExternalDocs(pub(crate) crate::ExternalDocs);
+impl Parse for ExternalDocs {
+    fn parse(input: syn::parse::ParseStream, _: Ident) -> syn::Result<Self> {
+        let external_docs;
+        parenthesized!(external_docs in input);
+        external_docs.parse::<crate::ExternalDocs>().map(Self)
+    }
+}
+impl ToTokens for ExternalDocs {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.0.to_tokens(tokens)
+    }
+}
+impl From<ExternalDocs> for Feature {
+    fn from(value: ExternalDocs) -> Self {
+        Feature::ExternalDocs(value)
+    }
+}
+impl_get_name!(ExternalDocs = "external_docs");
+
 #[derive(Clone, Debug)]
 pub(crate) struct Format(pub(crate) SchemaFormat<'static>);
 impl Parse for Format {
@@ -202,16 +223,33 @@ impl From<Format> for Feature {
 impl_get_name!(Format = "format");
 
 #[derive(Clone, Debug)]
-pub(crate) struct ValueType(pub(crate) syn::Type);
+pub(crate) enum ValueType {
+    Type(syn::Type),
+    /// Literal value the field is pinned to, rendered as `const`.
+    Const(syn::Lit),
+}
 impl ValueType {
     /// Create [`TypeTree`] from current [`syn::Type`].
     pub(crate) fn as_type_tree(&self) -> DiagResult<TypeTree> {
-        TypeTree::from_type(&self.0)
+        match self {
+            Self::Type(ty) => TypeTree::from_type(ty),
+            Self::Const(lit) => Err(Diagnostic::spanned(
+                lit.span(),
+                DiagLevel::Error,
+                "literal `value_type` is only supported on named struct fields",
+            )),
+        }
     }
 }
 impl Parse for ValueType {
     fn parse(input: syn::parse::ParseStream, _: Ident) -> syn::Result<Self> {
-        parse_utils::parse_next(input, || input.parse::<syn::Type>()).map(Self)
+        parse_utils::parse_next(input, || {
+            if input.peek(syn::Lit) {
+                input.parse::<syn::Lit>().map(Self::Const)
+            } else {
+                input.parse::<syn::Type>().map(Self::Type)
+            }
+        })
     }
 }
 impl From<ValueType> for Feature {
@@ -676,24 +714,54 @@ impl From<Skip> for Feature {
 impl_get_name!(Skip = "skip");
 
 #[derive(Clone, Debug)]
-pub(crate) struct AdditionalProperties(pub(crate) bool);
+pub(crate) enum AdditionalProperties {
+    FreeForm(bool),
+    /// Type of the additional properties, rendered as its schema.
+    Type(Box<Type>),
+}
 impl Parse for AdditionalProperties {
     fn parse(input: ParseStream, _: Ident) -> syn::Result<Self>
     where
         Self: std::marker::Sized,
     {
-        parse_utils::parse_bool_or_true(input).map(Self)
+        if input.peek(Token![=]) && !input.peek2(syn::LitBool) {
+            parse_utils::parse_next(input, || {
+                input.parse::<Type>().map(|ty| Self::Type(Box::new(ty)))
+            })
+        } else {
+            parse_utils::parse_bool_or_true(input).map(Self::FreeForm)
+        }
     }
 }
-impl ToTokens for AdditionalProperties {
-    fn to_tokens(&self, tokens: &mut TokenStream) {
+impl TryToTokens for AdditionalProperties {
+    fn try_to_tokens(&self, tokens: &mut TokenStream) -> DiagResult<()> {
         let oapi = crate::oapi_crate();
-        let additional_properties = &self.0;
-        tokens.extend(quote!(
-            #oapi::oapi::schema::AdditionalProperties::FreeForm(
-                #additional_properties
-            )
-        ))
+        match self {
+            Self::FreeForm(additional_properties) => tokens.extend(quote!(
+                #oapi::oapi::schema::AdditionalProperties::FreeForm(
+                    #additional_properties
+                )
+            )),
+            Self::Type(ty) => {
+                let type_tree = TypeTree::from_type(ty)?;
+                let schema = crate::component::ComponentSchema::new(
+                    crate::component::ComponentSchemaProps {
+                        type_tree: &type_tree,
+                        features: None,
+                        description: None,
+                        deprecated: None,
+                        object_name: "",
+                    },
+                )?
+                .to_token_stream();
+                tokens.extend(quote!(
+                    #oapi::oapi::schema::AdditionalProperties::RefOr(
+                        #oapi::oapi::RefOr::<#oapi::oapi::Schema>::from(#schema)
+                    )
+                ))
+            }
+        }
+        Ok(())
     }
 }
 impl_get_name!(AdditionalProperties = "additional_properties");
@@ -39,6 +39,7 @@ is_validatable! {
     Example => false,
     Examples => false,
     XmlAttr => false,
+    ExternalDocs => false,
     Format => false,
     WriteOnly => false,
     ReadOnly => false,
@@ -67,6 +68,7 @@ is_validatable! {
     Pattern => true,
     MaxItems => true,
     MinItems => true,
+    UniqueItems => true,
     MaxProperties => false,
     MinProperties => false,
     SchemaWith => false,
@@ -195,6 +197,7 @@ impl_feature_into_inner! {
     Pattern,
     MaxItems,
     MinItems,
+    UniqueItems,
     MaxProperties,
     MinProperties,
     SchemaWith,
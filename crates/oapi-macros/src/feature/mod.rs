@@ -77,6 +77,7 @@ pub(crate) enum Feature {
     Default(Default),
     Inline(Inline),
     XmlAttr(XmlAttr),
+    ExternalDocs(ExternalDocs),
     Format(Format),
     ValueType(ValueType),
     WriteOnly(WriteOnly),
@@ -104,6 +105,7 @@ pub(crate) enum Feature {
     Pattern(Pattern),
     MaxItems(MaxItems),
     MinItems(MinItems),
+    UniqueItems(UniqueItems),
     MaxProperties(MaxProperties),
     MinProperties(MinProperties),
     SchemaWith(SchemaWith),
@@ -149,8 +151,9 @@ impl Feature {
             Feature::MinItems(min_items) => min_items.validate(
                 ValidatorChain::new(&AboveZeroUsize(min_items.0)).next(&IsVec(type_tree)),
             ),
+            Feature::UniqueItems(unique_items) => unique_items.validate(IsVec(type_tree)),
             _unsupported_variant => {
-                const SUPPORTED_VARIANTS: [&str; 10] = [
+                const SUPPORTED_VARIANTS: [&str; 11] = [
                     "multiple_of",
                     "maximum",
                     "minimum",
@@ -161,6 +164,7 @@ impl Feature {
                     "pattern",
                     "max_items",
                     "min_items",
+                    "unique_items",
                 ];
                 panic!(
                     "Unsupported variant: `{variant}` for Validate::validate, expected one of: {variants}",
@@ -185,6 +189,7 @@ impl TryToTokens for Feature {
             Feature::Example(example) => quote! { .example(#example) },
             Feature::Examples(examples) => quote! { .examples(#examples) },
             Feature::XmlAttr(xml) => quote! { .xml(#xml) },
+            Feature::ExternalDocs(external_docs) => quote! { .external_docs(#external_docs) },
             Feature::Format(format) => {
                 let format = format.try_to_token_stream()?;
                 quote! { .format(#format) }
@@ -231,6 +236,7 @@ impl TryToTokens for Feature {
             Feature::Pattern(pattern) => quote! { .pattern(#pattern) },
             Feature::MaxItems(max_items) => quote! { .max_items(#max_items) },
             Feature::MinItems(min_items) => quote! { .min_items(#min_items) },
+            Feature::UniqueItems(unique_items) => quote! { .unique_items(#unique_items) },
             Feature::MaxProperties(max_properties) => {
                 quote! { .max_properties(#max_properties) }
             }
@@ -242,6 +248,7 @@ impl TryToTokens for Feature {
             Feature::Deprecated(deprecated) => quote! { .deprecated(#deprecated) },
             Feature::Skip(_) => TokenStream::new(),
             Feature::AdditionalProperties(additional_properties) => {
+                let additional_properties = additional_properties.try_to_token_stream()?;
                 quote! { .additional_properties(#additional_properties) }
             }
             Feature::ContentEncoding(content_encoding) => {
@@ -294,6 +301,7 @@ impl Display for Feature {
             Feature::Example(example) => example.fmt(f),
             Feature::Examples(examples) => examples.fmt(f),
             Feature::XmlAttr(xml) => xml.fmt(f),
+            Feature::ExternalDocs(external_docs) => external_docs.fmt(f),
             Feature::Format(format) => format.fmt(f),
             Feature::WriteOnly(write_only) => write_only.fmt(f),
             Feature::ReadOnly(read_only) => read_only.fmt(f),
@@ -322,6 +330,7 @@ impl Display for Feature {
             Feature::Pattern(pattern) => pattern.fmt(f),
             Feature::MaxItems(max_items) => max_items.fmt(f),
             Feature::MinItems(min_items) => min_items.fmt(f),
+            Feature::UniqueItems(unique_items) => unique_items.fmt(f),
             Feature::MaxProperties(max_properties) => max_properties.fmt(f),
             Feature::MinProperties(min_properties) => min_properties.fmt(f),
             Feature::SchemaWith(with_schema) => with_schema.fmt(f),
@@ -345,6 +354,7 @@ impl Validatable for Feature {
             Feature::Example(example) => example.is_validatable(),
             Feature::Examples(examples) => examples.is_validatable(),
             Feature::XmlAttr(xml) => xml.is_validatable(),
+            Feature::ExternalDocs(external_docs) => external_docs.is_validatable(),
             Feature::Format(format) => format.is_validatable(),
             Feature::WriteOnly(write_only) => write_only.is_validatable(),
             Feature::ReadOnly(read_only) => read_only.is_validatable(),
@@ -373,6 +383,7 @@ impl Validatable for Feature {
             Feature::Pattern(pattern) => pattern.is_validatable(),
             Feature::MaxItems(max_items) => max_items.is_validatable(),
             Feature::MinItems(min_items) => min_items.is_validatable(),
+            Feature::UniqueItems(unique_items) => unique_items.is_validatable(),
             Feature::MaxProperties(max_properties) => max_properties.is_validatable(),
             Feature::MinProperties(min_properties) => min_properties.is_validatable(),
             Feature::SchemaWith(with_schema) => with_schema.is_validatable(),
@@ -364,6 +364,41 @@ impl From<MinItems> for Feature {
 }
 impl_get_name!(MinItems = "min_items");
 
+#[derive(Clone, Debug)]
+pub(crate) struct UniqueItems(pub(crate) bool, pub(crate) Ident);
+impl Validate for UniqueItems {
+    fn validate(&self, validator: impl Validator) -> DiagResult<()> {
+        if let Err(error) = validator.is_valid() {
+            Err(
+                Diagnostic::spanned(self.1.span(), DiagLevel::Error, format!("`unique_items` error: {}", error)).help(
+                    "See more details: `http://json-schema.org/draft/2020-12/json-schema-validation.html#name-uniqueitems",
+                ),
+            )
+        } else {
+            Ok(())
+        }
+    }
+}
+impl Parse for UniqueItems {
+    fn parse(input: ParseStream, ident: Ident) -> syn::Result<Self>
+    where
+        Self: Sized,
+    {
+        parse_utils::parse_bool_or_true(input).map(|unique_items| Self(unique_items, ident))
+    }
+}
+impl ToTokens for UniqueItems {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        self.0.to_tokens(tokens);
+    }
+}
+impl From<UniqueItems> for Feature {
+    fn from(value: UniqueItems) -> Self {
+        Feature::UniqueItems(value)
+    }
+}
+impl_get_name!(UniqueItems = "unique_items");
+
 #[derive(Clone, Debug)]
 #[allow(dead_code)]
 pub(crate) struct MaxProperties(pub(crate) usize, pub(crate) Ident);
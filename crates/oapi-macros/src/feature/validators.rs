@@ -45,10 +45,13 @@ pub(crate) struct IsVec<'a>(pub(crate) &'a TypeTree<'a>);
 
 impl Validator for IsVec<'_> {
     fn is_valid(&self) -> Result<(), &'static str> {
-        if self.0.generic_type == Some(GenericType::Vec) {
+        if matches!(
+            self.0.generic_type,
+            Some(GenericType::Vec | GenericType::LinkedList | GenericType::Set)
+        ) {
             Ok(())
         } else {
-            Err("can only be used with `Vec`, `array` or `slice` types")
+            Err("can only be used with `Vec`, `LinkedList`, `HashSet`, `BTreeSet`, array or slice types")
         }
     }
 }
@@ -25,6 +25,7 @@ pub(crate) mod feature;
 mod operation;
 mod parameter;
 pub(crate) mod parse_utils;
+mod request_body;
 mod response;
 mod schema;
 mod schema_type;
@@ -83,6 +84,17 @@ pub fn derive_to_parameters(input: TokenStream) -> TokenStream {
     }
 }
 
+/// Generate request body with content examples from struct or enum, [Read more][more].
+///
+/// [more]: ../salvo_oapi/derive.ToRequestBody.html
+#[proc_macro_derive(ToRequestBody, attributes(salvo))] //attributes(request_body)
+pub fn derive_to_request_body(input: TokenStream) -> TokenStream {
+    match request_body::to_request_body(syn::parse_macro_input!(input)) {
+        Ok(stream) => stream.into(),
+        Err(e) => e.emit_as_item_tokens().into(),
+    }
+}
+
 /// Generate reusable [OpenApi][openapi] response, [Read more][more].
 ///
 /// [openapi]: ../salvo_oapi/struct.OpenApi.html
@@ -254,7 +266,7 @@ mod tests {
                 impl salvo::oapi::ToSchema for User {
                     fn to_schema(components: &mut salvo::oapi::Components) -> salvo::oapi::RefOr<salvo::oapi::schema::Schema> {
                         let name = salvo::oapi::naming::assign_name::<User>(salvo::oapi::naming::NameRule::Auto);
-                        let ref_or = salvo::oapi::RefOr::Ref(salvo::oapi::Ref::new(format!("#/components/schemas/{}", name)));
+                        let ref_or = salvo::oapi::RefOr::Ref(salvo::oapi::Ref::from_schema_name(&name));
                         if !components.schemas.contains_key(&name) {
                             components.schemas.insert(name.clone(), ref_or.clone());
                             let schema = salvo::oapi::Object::new()
@@ -284,6 +296,7 @@ mod tests {
                                     salvo::oapi::Object::new()
                                         .schema_type(salvo::oapi::schema::SchemaType::basic(salvo::oapi::schema::BasicType::Integer))
                                         .format(salvo::oapi::SchemaFormat::KnownFormat(salvo::oapi::KnownFormat::UInt32))
+                                        .description("Deprecated: There is deprecated")
                                         .deprecated(salvo::oapi::Deprecated::True)
                                         .minimum(0f64)
                                 )
@@ -329,7 +342,7 @@ mod tests {
                         }
                         let name = name
                             .unwrap_or_else(|| salvo::oapi::naming::assign_name::<MyObject<T>>(salvo::oapi::naming::NameRule::Auto));
-                        let ref_or = salvo::oapi::RefOr::Ref(salvo::oapi::Ref::new(format!("#/components/schemas/{}", name)));
+                        let ref_or = salvo::oapi::RefOr::Ref(salvo::oapi::Ref::from_schema_name(&name));
                         if !components.schemas.contains_key(&name) {
                             components.schemas.insert(name.clone(), ref_or.clone());
                             let schema = salvo::oapi::Object::new()
@@ -364,7 +377,7 @@ mod tests {
                 impl salvo::oapi::ToSchema for People {
                     fn to_schema(components: &mut salvo::oapi::Components) -> salvo::oapi::RefOr<salvo::oapi::schema::Schema> {
                         let name = salvo::oapi::naming::assign_name::<People>(salvo::oapi::naming::NameRule::Auto);
-                        let ref_or = salvo::oapi::RefOr::Ref(salvo::oapi::Ref::new(format!("#/components/schemas/{}", name)));
+                        let ref_or = salvo::oapi::RefOr::Ref(salvo::oapi::Ref::from_schema_name(&name));
                         if !components.schemas.contains_key(&name) {
                             components.schemas.insert(name.clone(), ref_or.clone());
                             let schema = salvo::oapi::Object::new()
@@ -418,7 +431,7 @@ mod tests {
                             )
                         );
                         components.responses.insert("User", response);
-                        salvo::oapi::RefOr::Ref(salvo::oapi::Ref::new(format!("#/components/responses/{}", "User")))
+                        salvo::oapi::RefOr::Ref(salvo::oapi::Ref::from_response_name("User"))
                     }
                 }
                 impl salvo::oapi::EndpointOutRegister for User {
@@ -546,6 +559,7 @@ mod tests {
                         salvo::oapi::Parameters(
                             [
                                 salvo::oapi::parameter::Parameter::new("name")
+                                    .parameter_in(salvo::oapi::parameter::ParameterIn::Query)
                                     .description("Name of pet")
                                     .required(salvo::oapi::Required::False)
                                     .schema(
@@ -553,6 +567,7 @@ mod tests {
                                             .schema_type(salvo::oapi::schema::SchemaType::from_iter([salvo::oapi::schema::BasicType::String, salvo::oapi::schema::BasicType::Null]))
                                     ),
                                 salvo::oapi::parameter::Parameter::new("age")
+                                    .parameter_in(salvo::oapi::parameter::ParameterIn::Query)
                                     .description("Age of pet")
                                     .required(salvo::oapi::Required::False)
                                     .schema(
@@ -563,9 +578,10 @@ mod tests {
                                             ))
                                     ),
                                 salvo::oapi::parameter::Parameter::new("kind")
+                                    .parameter_in(salvo::oapi::parameter::ParameterIn::Query)
                                     .description("Kind of pet")
                                     .required(salvo::oapi::Required::True)
-                                    .schema(<PetKind as salvo::oapi::ToSchema>::to_schema(components)),
+                                    .schema(components.inline_schema::<PetKind>()),
                             ]
                             .to_vec()
                         )
@@ -10,7 +10,7 @@ use crate::parse_utils::LitStrOrExpr;
 use crate::schema_type::SchemaType;
 use crate::security_requirement::SecurityRequirementsAttr;
 use crate::type_tree::{GenericType, TypeTree};
-use crate::{Array, DiagResult, TryToTokens};
+use crate::{Array, DiagResult, ExternalDocs, TryToTokens};
 
 pub(crate) mod example;
 pub(crate) mod request_body;
@@ -29,6 +29,7 @@ pub(crate) struct Operation<'a> {
     security: Option<&'a Array<'a, SecurityRequirementsAttr>>,
     summary: Option<Summary<'a>>,
     description: Option<Description<'a>>,
+    external_docs: Option<&'a ExternalDocs>,
 }
 
 impl<'a> Operation<'a> {
@@ -80,6 +81,7 @@ impl<'a> Operation<'a> {
             security: attr.security.as_ref(),
             summary,
             description,
+            external_docs: attr.external_docs.as_ref(),
         }
     }
     pub(crate) fn modifiers(&self) -> DiagResult<Vec<TokenStream>> {
@@ -146,6 +148,12 @@ impl<'a> Operation<'a> {
             }
         }
 
+        if let Some(external_docs) = self.external_docs {
+            modifiers.push(quote! {
+                operation.external_docs = Some(#external_docs);
+            })
+        }
+
         self.parameters
             .iter()
             .map(TryToTokens::try_to_token_stream)
@@ -59,7 +59,7 @@ pub(crate) struct RequestBodyAttr<'r> {
 impl Parse for RequestBodyAttr<'_> {
     fn parse(input: syn::parse::ParseStream) -> syn::Result<Self> {
         const EXPECTED_ATTRIBUTE_MESSAGE: &str =
-            "unexpected attribute, expected any of: content, content_type, description, examples";
+            "unexpected attribute, expected any of: content, content_type, description, example, examples";
         let lookahead = input.lookahead1();
 
         if lookahead.peek(Paren) {
@@ -17,7 +17,7 @@ use crate::feature::attributes::{
 };
 use crate::feature::validation::{
     ExclusiveMaximum, ExclusiveMinimum, MaxItems, MaxLength, Maximum, MinItems, MinLength, Minimum,
-    MultipleOf, Pattern,
+    MultipleOf, Pattern, UniqueItems,
 };
 use crate::feature::{
     impl_into_inner, impl_merge, parse_features, pop_feature, Feature, FeaturesExt, Merge,
@@ -386,6 +386,7 @@ impl Parse for FieldFeatures {
             Pattern,
             MaxItems,
             MinItems,
+            UniqueItems,
             AdditionalProperties
         )))
     }
@@ -454,6 +455,7 @@ impl Parameter<'_> {
                     | Feature::Pattern(_)
                     | Feature::MaxItems(_)
                     | Feature::MinItems(_)
+                    | Feature::UniqueItems(_)
                     | Feature::AdditionalProperties(_) => {
                         schema_features.push(feature);
                     }
@@ -568,6 +570,9 @@ impl TryToTokens for Parameter<'_> {
             tokens.extend(quote! { .parameter_in(#parameter_in) });
         } else if let Some(parameter_in) = &self.container_attributes.default_parameter_in {
             tokens.extend(parameter_in.try_to_token_stream()?);
+        } else {
+            // Keep documentation consistent with extraction, which reads from query by default.
+            tokens.extend(quote! { .parameter_in(#oapi::oapi::parameter::ParameterIn::Query) });
         }
 
         if let Some(style) = param_features.pop_style_feature() {
@@ -16,7 +16,7 @@ use crate::feature::attributes::{
 };
 use crate::feature::validation::{
     ExclusiveMaximum, ExclusiveMinimum, MaxItems, MaxLength, Maximum, MinItems, MinLength, Minimum,
-    MultipleOf, Pattern,
+    MultipleOf, Pattern, UniqueItems,
 };
 use crate::feature::{parse_features, Feature, TryToTokensExt};
 use crate::{operation::InlineType, parse_utils, Required};
@@ -228,7 +228,8 @@ impl Parse for ParameterFeatures {
             MinLength,
             Pattern,
             MaxItems,
-            MinItems
+            MinItems,
+            UniqueItems
         )))
     }
 }
@@ -257,7 +258,8 @@ impl ParameterFeatures {
                     | Feature::MinLength(_)
                     | Feature::Pattern(_)
                     | Feature::MaxItems(_)
-                    | Feature::MinItems(_) => {
+                    | Feature::MinItems(_)
+                    | Feature::UniqueItems(_) => {
                         schema_features.push(feature);
                     }
                     _ => {
@@ -0,0 +1,60 @@
+use std::borrow::Cow;
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{parse_quote, DeriveInput};
+
+use crate::doc_comment::CommentAttributes;
+use crate::operation::request_body::RequestBodyAttr;
+use crate::operation::{InlineType, PathType};
+use crate::{attribute, parse_utils, DiagResult, Diagnostic, TryToTokens};
+
+pub(crate) fn to_request_body(input: DeriveInput) -> DiagResult<TokenStream> {
+    let oapi = crate::oapi_crate();
+    let DeriveInput {
+        attrs,
+        ident,
+        generics,
+        ..
+    } = input;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let mut request_body = RequestBodyAttr::default();
+    for attr in attrs.iter().filter(|attr| attr.path().is_ident("salvo")) {
+        if let Some(list) = attribute::find_nested_list(attr, "request_body")? {
+            let tokens = list.tokens;
+            request_body =
+                syn::parse2::<RequestBodyAttr>(quote! { (#tokens) }).map_err(Diagnostic::from)?;
+        }
+    }
+    if request_body.content.is_none() {
+        request_body.content = Some(PathType::MediaType(InlineType {
+            ty: Cow::Owned(parse_quote!(#ident #ty_generics)),
+            is_inline: false,
+        }));
+    }
+    if request_body.description.is_none() {
+        let comments = CommentAttributes::from_attributes(&attrs);
+        if !comments.is_empty() {
+            let description = comments.as_formatted_string();
+            request_body.description = Some(parse_utils::LitStrOrExpr::LitStr(parse_quote!(
+                #description
+            )));
+        }
+    }
+    let request_body = request_body.try_to_token_stream()?;
+
+    Ok(quote! {
+        impl #impl_generics #oapi::oapi::ToRequestBody for #ident #ty_generics #where_clause {
+            fn to_request_body(components: &mut #oapi::oapi::Components) -> #oapi::oapi::RequestBody {
+                #request_body
+            }
+        }
+        impl #impl_generics #oapi::oapi::EndpointArgRegister for #ident #ty_generics #where_clause {
+            fn register(components: &mut #oapi::oapi::Components, operation: &mut #oapi::oapi::Operation, _arg: &str) {
+                let request_body = <Self as #oapi::oapi::ToRequestBody>::to_request_body(components);
+                operation.request_body = Some(request_body);
+            }
+        }
+    })
+}
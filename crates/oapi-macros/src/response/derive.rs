@@ -82,7 +82,7 @@ impl TryToTokens for ToResponse<'_> {
                 fn to_response(components: &mut #oapi::oapi::Components) -> #oapi::oapi::RefOr<#oapi::oapi::Response> {
                     let response = #response;
                     components.responses.insert(#name, response);
-                    #oapi::oapi::RefOr::Ref(#oapi::oapi::Ref::new(format!("#/components/responses/{}", #name)))
+                    #oapi::oapi::RefOr::Ref(#oapi::oapi::Ref::from_response_name(#name))
                 }
             }
             impl #impl_generics #oapi::oapi::EndpointOutRegister for #ident #ty_generics #where_clause {
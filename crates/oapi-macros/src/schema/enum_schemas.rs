@@ -421,6 +421,24 @@ fn regular_enum_to_tokens<T: self::enum_variant::Variant>(
 ) -> DiagResult<()> {
     let enum_values = get_variants_tokens_vec();
 
+    // Tagged enums are rendered as `OneOf` which can not carry external docs.
+    let is_tagged = container_rules.as_ref().is_some_and(|rules| {
+        matches!(
+            rules.enum_repr,
+            SerdeEnumRepr::InternallyTagged { .. } | SerdeEnumRepr::AdjacentlyTagged { .. }
+        )
+    });
+    if is_tagged
+        && enum_variant_features
+            .iter()
+            .any(|feature| matches!(feature, Feature::ExternalDocs(_)))
+    {
+        return Err(Diagnostic::new(
+            DiagLevel::Error,
+            "external_docs is not supported on internally or adjacently tagged enums",
+        ));
+    }
+
     tokens.extend(match container_rules {
         Some(serde_container) => match &serde_container.enum_repr {
             SerdeEnumRepr::ExternallyTagged => Enum::new(enum_values).to_token_stream(),
@@ -147,13 +147,20 @@ where
         let enum_type = &self.enum_type;
         let description = &self.description;
 
+        // A single possible value is better described as `const` than a one-element `enum`.
+        let values = if let [item] = &**items {
+            quote! { .const_value::<#enum_type>(#item) }
+        } else {
+            quote! { .enum_values::<[#enum_type; #len], #enum_type>(#items) }
+        };
+
         tokens.extend(quote! {
             #oapi::oapi::Object::new()
                 #title
                 #description
                 #example
                 .schema_type(#schema_type)
-                .enum_values::<[#enum_type; #len], #enum_type>(#items)
+                #values
         })
     }
 }
@@ -237,7 +244,7 @@ impl<'t, V: Variant> FromIterator<(Cow<'t, str>, V)> for TaggedEnum<V> {
                                 #tag,
                                 #oapi::oapi::schema::Object::new()
                                     .schema_type(#schema_type)
-                                    .enum_values::<[#enum_type; 1], #enum_type>([#item])
+                                    .const_value::<#enum_type>(#item)
                             )
                             .required(#tag)
                     )
@@ -336,14 +343,14 @@ impl<'t, V: Variant> FromIterator<(Cow<'t, str>, Cow<'t, str>, V)> for Adjacentl
                                 #tag,
                                 #oapi::oapi::schema::Object::new()
                                     .schema_type(#oapi::oapi::schema::BasicType::String)
-                                    .enum_values::<[#enum_type; 1], #enum_type>([#content])
+                                    .const_value::<#enum_type>(#content)
                             )
                             .required(#tag)
                             .property(
                                 #content,
                                 #oapi::oapi::schema::Object::new()
                                     .schema_type(#schema_type)
-                                    .enum_values::<[#enum_type; 1], #enum_type>([#item])
+                                    .const_value::<#enum_type>(#item)
                             )
                             .required(#content)
                     )
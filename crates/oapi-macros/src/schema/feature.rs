@@ -3,12 +3,12 @@ use syn::Attribute;
 
 use crate::feature::attributes::{
     AdditionalProperties, Aliases, Bound, ContentEncoding, ContentMediaType, Default, Deprecated,
-    Description, Example, Examples, Format, Inline, Name, Nullable, ReadOnly, Rename, RenameAll,
-    Required, SchemaWith, Skip, SkipBound, Title, ValueType, WriteOnly, XmlAttr,
+    Description, Example, Examples, ExternalDocs, Format, Inline, Name, Nullable, ReadOnly, Rename,
+    RenameAll, Required, SchemaWith, Skip, SkipBound, Title, ValueType, WriteOnly, XmlAttr,
 };
 use crate::feature::validation::{
     ExclusiveMaximum, ExclusiveMinimum, MaxItems, MaxLength, MaxProperties, Maximum, MinItems,
-    MinLength, MinProperties, Minimum, MultipleOf, Pattern,
+    MinLength, MinProperties, Minimum, MultipleOf, Pattern, UniqueItems,
 };
 use crate::feature::{impl_into_inner, impl_merge, parse_features, Feature, Merge};
 use crate::{attribute, DiagResult, Diagnostic, IntoInner};
@@ -32,9 +32,11 @@ impl Parse for NamedFieldStructFeatures {
             Default,
             Deprecated,
             Description,
+            ExternalDocs,
             Skip,
             Bound,
-            SkipBound
+            SkipBound,
+            AdditionalProperties
         )))
     }
 }
@@ -54,6 +56,8 @@ impl Parse for UnnamedFieldStructFeatures {
             Title,
             Aliases,
             Format,
+            ContentEncoding,
+            ContentMediaType,
             ValueType,
             Inline,
             Deprecated,
@@ -74,6 +78,7 @@ impl Parse for EnumFeatures {
         Ok(EnumFeatures(parse_features!(
             input as Example,
             Examples,
+            XmlAttr,
             Default,
             Name,
             Title,
@@ -82,6 +87,7 @@ impl Parse for EnumFeatures {
             Inline,
             Deprecated,
             Description,
+            ExternalDocs,
             Bound,
             SkipBound
         )))
@@ -139,6 +145,7 @@ impl Parse for NamedFieldFeatures {
             Pattern,
             MaxItems,
             MinItems,
+            UniqueItems,
             SchemaWith,
             AdditionalProperties,
             Required,
@@ -156,7 +156,7 @@ impl TryToTokens for ToSchema<'_> {
                 };
                 quote! {
                     #name_tokens
-                    let ref_or = #oapi::oapi::RefOr::Ref(#oapi::oapi::Ref::new(format!("#/components/schemas/{}", name)));
+                    let ref_or = #oapi::oapi::RefOr::Ref(#oapi::oapi::Ref::from_schema_name(&name));
                     if !components.schemas.contains_key(&name) {
                         components.schemas.insert(name.clone(), ref_or.clone());
                         let schema = #variant;
@@ -356,6 +356,7 @@ enum Property {
     Schema(ComponentSchema),
     SchemaWith(Feature),
     FlattenedMap(FlattenedMapSchema),
+    Const(TokenStream),
 }
 
 impl TryToTokens for Property {
@@ -370,6 +371,10 @@ impl TryToTokens for Property {
                 Ok(())
             }
             Self::SchemaWith(with_schema) => with_schema.try_to_tokens(tokens),
+            Self::Const(schema) => {
+                schema.to_tokens(tokens);
+                Ok(())
+            }
         }
     }
 }
@@ -12,7 +12,6 @@ use crate::feature::attributes::{
 };
 use crate::feature::{
     parse_features, pop_feature, pop_feature_as_inner, Feature, FeaturesExt, IsSkipped,
-    TryToTokensExt,
 };
 use crate::schema::{Description, Inline};
 use crate::type_tree::TypeTree;
@@ -111,15 +110,37 @@ impl NamedStructSchema<'_> {
             })
         });
 
+        let comments = CommentAttributes::from_attributes(&field.attrs);
+        let mut notes = Vec::new();
+        if let Some(note) = crate::get_deprecated_note(&field.attrs) {
+            notes.push(format!("Deprecated: {note}"));
+        }
+        if let Some(rule) =
+            serde_util::parse_value(&field.attrs).filter(|rule| !rule.aliases.is_empty())
+        {
+            notes.push(aliases_note(&rule.aliases));
+        }
+        let notes_description = (!notes.is_empty()).then(|| with_notes(&comments, &notes));
+        let description = &notes_description
+            .as_ref()
+            .map(ComponentDescription::Description)
+            .unwrap_or(ComponentDescription::CommentAttributes(&comments));
+
         let value_type = field_features
             .as_mut()
             .and_then(|features| features.pop_value_type_feature());
+        if let Some(attributes::ValueType::Const(lit)) = &value_type {
+            return Ok(NamedStructFieldOptions {
+                property: Property::Const(const_schema(lit, description, deprecated.as_ref())?),
+                rename_field_value: rename_field,
+                required: pop_feature_as_inner!(field_features => Feature::Required(_v)),
+                is_option: type_tree.is_option(),
+            });
+        }
         let override_type_tree = value_type
             .as_ref()
             .map(|value_type| value_type.as_type_tree())
             .transpose()?;
-        let comments = CommentAttributes::from_attributes(&field.attrs);
-        let description = &ComponentDescription::CommentAttributes(&comments);
         let with_schema = pop_feature!(field_features => Feature::SchemaWith(_));
         let required = pop_feature_as_inner!(field_features => Feature::Required(_v));
         let type_tree = override_type_tree.as_ref().unwrap_or(type_tree);
@@ -149,6 +170,57 @@ impl NamedStructSchema<'_> {
     }
 }
 
+/// Note listing the names accepted through `#[serde(alias = "...")]`, so the spec documents every
+/// name the deserializer accepts.
+fn aliases_note(aliases: &[String]) -> String {
+    let aliases = aliases
+        .iter()
+        .map(|alias| format!("`{alias}`"))
+        .collect::<Vec<_>>()
+        .join(", ");
+    format!("Also accepted as: {aliases}.")
+}
+
+/// Appends notes, e.g. the deprecation message or serde aliases, to the field description.
+fn with_notes(comments: &CommentAttributes, notes: &[String]) -> Description {
+    let notes = notes.join("\n\n");
+    if comments.is_empty() {
+        notes.into()
+    } else {
+        format!("{}\n\n{notes}", comments.as_formatted_string()).into()
+    }
+}
+
+/// Schema of a field pinned to a literal with `value_type = <literal>`.
+fn const_schema(
+    lit: &syn::Lit,
+    description: &ComponentDescription,
+    deprecated: Option<&Deprecated>,
+) -> DiagResult<TokenStream> {
+    let oapi = crate::oapi_crate();
+    let schema_type = match lit {
+        syn::Lit::Str(_) | syn::Lit::Char(_) => quote! { String },
+        syn::Lit::Int(_) => quote! { Integer },
+        syn::Lit::Float(_) => quote! { Number },
+        syn::Lit::Bool(_) => quote! { Boolean },
+        _ => {
+            return Err(Diagnostic::spanned(
+                lit.span(),
+                DiagLevel::Error,
+                "unsupported literal for `value_type`, expected string, char, number or bool",
+            ))
+        }
+    };
+    let deprecated = deprecated.map(|deprecated| quote! { .deprecated(#deprecated) });
+    Ok(quote! {
+        #oapi::oapi::Object::new()
+            .schema_type(#oapi::oapi::schema::BasicType::#schema_type)
+            .const_value(#lit)
+            #description
+            #deprecated
+    })
+}
+
 impl TryToTokens for NamedStructSchema<'_> {
     fn try_to_tokens(&self, tokens: &mut TokenStream) -> DiagResult<()> {
         let oapi = crate::oapi_crate();
@@ -235,6 +307,17 @@ impl TryToTokens for NamedStructSchema<'_> {
             }
         }
 
+        // The struct level `additional_properties` belongs to the object of the struct's own
+        // fields, which is wrapped in `AllOf` when there are flattened fields.
+        let additional_properties = self.features.as_ref().and_then(|features| {
+            features
+                .iter()
+                .find(|feature| matches!(feature, Feature::AdditionalProperties(_)))
+        });
+        if let Some(additional_properties) = additional_properties {
+            object_tokens.extend(additional_properties.try_to_token_stream()?);
+        }
+
         let flatten_fields: Vec<&Field> = self
             .fields
             .iter()
@@ -253,15 +336,25 @@ impl TryToTokens for NamedStructSchema<'_> {
                     self.field_as_schema_property(field, true, &container_rules)?;
 
                 match property {
-                    Property::Schema(_) | Property::SchemaWith(_) => {
+                    Property::Schema(_) | Property::SchemaWith(_) | Property::Const(_) => {
                         let property = property.try_to_token_stream()?;
                         flattened_tokens.extend(quote! { .item(#property) })
                     }
+                    Property::FlattenedMap(_) if additional_properties.is_some() => {
+                        return Err(Diagnostic::spanned(
+                            field.span(),
+                            DiagLevel::Error,
+                            format!(
+                                "The structure `{}` sets `additional_properties` and contains a flattened map field.",
+                                self.struct_name
+                            ),
+                        )
+                        .help("Remove `additional_properties`, the flattened map already describes the additional properties"));
+                    }
                     Property::FlattenedMap(_) => match flattened_map_field {
                         None => {
                             let property = property.try_to_token_stream()?;
-                            object_tokens
-                                .extend(quote! { .additional_properties(Some(#property)) });
+                            object_tokens.extend(quote! { .additional_properties(#property) });
                             flattened_map_field = Some(field);
                         }
                         Some(flattened_map_field) => {
@@ -318,7 +411,11 @@ impl TryToTokens for NamedStructSchema<'_> {
         }
 
         if let Some(struct_features) = self.features.as_ref() {
-            tokens.extend(struct_features.try_to_token_stream()?)
+            for feature in struct_features {
+                if !matches!(feature, Feature::AdditionalProperties(_)) {
+                    feature.try_to_tokens(tokens)?;
+                }
+            }
         }
 
         let comments = CommentAttributes::from_attributes(self.attributes);
@@ -142,6 +142,37 @@ pub(crate) fn get_deprecated(attributs: &[Attribute]) -> Option<crate::Deprecate
     }
 }
 
+/// Get the message of Rust's `#[deprecated = "..."]` or `#[deprecated(note = "...")]` attribute.
+pub(crate) fn get_deprecated_note(attributs: &[Attribute]) -> Option<String> {
+    let attr = attributs
+        .iter()
+        .find(|attr| attr.path().is_ident("deprecated"))?;
+    match &attr.meta {
+        syn::Meta::NameValue(syn::MetaNameValue {
+            value:
+                syn::Expr::Lit(syn::ExprLit {
+                    lit: Lit::Str(note),
+                    ..
+                }),
+            ..
+        }) => Some(note.value()),
+        syn::Meta::List(_) => {
+            let mut note = None;
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("note") {
+                    note = Some(meta.value()?.parse::<LitStr>()?.value());
+                } else {
+                    meta.value()?.parse::<Lit>()?;
+                }
+                Ok(())
+            })
+            .ok()?;
+            note
+        }
+        _ => None,
+    }
+}
+
 /// Check whether field is required based on following rules.
 ///
 /// * If field has not serde's `skip_serializing_if`
@@ -277,8 +308,7 @@ impl ToTokens for Required {
     }
 }
 
-#[allow(dead_code)]
-#[derive(Default, Debug)]
+#[derive(Default, Clone, Debug)]
 pub(crate) struct ExternalDocs {
     url: String,
     description: Option<String>,
@@ -311,6 +341,10 @@ impl Parse for ExternalDocs {
             }
         }
 
+        if external_docs.url.is_empty() {
+            return Err(input.error("missing required attribute: url"));
+        }
+
         Ok(external_docs)
     }
 }
@@ -320,8 +354,7 @@ impl ToTokens for ExternalDocs {
         let oapi = crate::oapi_crate();
         let url = &self.url;
         tokens.extend(quote! {
-            #oapi::oapi::external_docs::ExternalDocsBuilder::new()
-                .url(#url)
+            #oapi::oapi::ExternalDocs::new(#url)
         });
 
         if let Some(ref description) = self.description {
@@ -0,0 +1,61 @@
+#![allow(missing_docs)]
+use assert_json_diff::assert_json_eq;
+use salvo::oapi::{Components, ToRequestBody, ToSchema};
+use salvo::prelude::*;
+use serde::Deserialize;
+use serde_json::json;
+
+#[test]
+fn test_derive_to_request_body_examples() {
+    /// Pet to add to the store.
+    #[derive(Deserialize, ToSchema, ToRequestBody, Extractible)]
+    #[salvo(schema(name = PetBody))]
+    #[salvo(extract(default_source(from = "body")))]
+    #[salvo(request_body(
+        example = json!({"name": "Tom"}),
+        examples(
+            ("Cat" = (summary = "A cat", value = json!({"name": "Tom"}))),
+            ("Dog" = (summary = "A dog", value = json!({"name": "Spike"})))
+        )
+    ))]
+    #[allow(dead_code)]
+    struct Pet {
+        name: String,
+    }
+
+    let request_body = Pet::to_request_body(&mut Components::new());
+    assert_json_eq!(
+        request_body,
+        json!({
+            "description": "Pet to add to the store.",
+            "required": true,
+            "content": {
+                "application/json": {
+                    "schema": {"$ref": "#/components/schemas/PetBody"},
+                    "example": {"name": "Tom"},
+                    "examples": {
+                        "Cat": {"summary": "A cat", "value": {"name": "Tom"}},
+                        "Dog": {"summary": "A dog", "value": {"name": "Spike"}}
+                    }
+                }
+            }
+        })
+    );
+
+    #[endpoint]
+    async fn create_pet(pet: Pet) -> String {
+        pet.name
+    }
+
+    let router = Router::with_path("pets").post(create_pet);
+    let doc = OpenApi::new("test api", "0.0.1").merge_router(&router);
+    let operation = doc
+        .paths
+        .get("/pets")
+        .unwrap()
+        .operations
+        .get(&salvo::oapi::PathItemType::Post)
+        .unwrap();
+    assert_json_eq!(operation.request_body.as_ref().unwrap(), &request_body);
+    assert!(doc.components.schemas.get("PetBody").is_some());
+}
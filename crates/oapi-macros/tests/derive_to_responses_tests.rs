@@ -0,0 +1,58 @@
+#![allow(missing_docs)]
+use assert_json_diff::assert_json_eq;
+use salvo::oapi::{Components, ToResponses};
+use serde_json::json;
+
+#[test]
+fn test_derive_to_responses_variant_description_and_headers() {
+    #[derive(ToResponses)]
+    #[allow(dead_code)]
+    enum UserResponses {
+        /// Doc comment description.
+        #[salvo(response(
+            status_code = 200,
+            description = "User found",
+            headers(
+                ("X-RateLimit-Remaining" = i32, description = "Requests left in the window"),
+                ("X-Request-Id")
+            )
+        ))]
+        Success { value: String },
+
+        /// User not found.
+        #[salvo(response(status_code = 404))]
+        NotFound,
+    }
+
+    let responses = UserResponses::to_responses(&mut Components::new());
+    assert_json_eq!(
+        responses,
+        json!({
+            "200": {
+                "description": "User found",
+                "headers": {
+                    "X-RateLimit-Remaining": {
+                        "schema": {"type": "integer", "format": "int32"},
+                        "description": "Requests left in the window"
+                    },
+                    "X-Request-Id": {
+                        "schema": {"type": "string"}
+                    }
+                },
+                "content": {
+                    "application/json": {
+                        "schema": {
+                            "type": "object",
+                            "description": "Doc comment description.",
+                            "required": ["value"],
+                            "properties": {"value": {"type": "string"}}
+                        }
+                    }
+                }
+            },
+            "404": {
+                "description": "User not found."
+            }
+        })
+    );
+}
@@ -250,3 +250,482 @@ fn test_derive_to_schema_enum() {
         })
     );
 }
+
+#[test]
+fn test_derive_to_schema_const() {
+    fn schema_of<T: ToSchema>() -> serde_json::Value {
+        let mut components = salvo::oapi::Components::new();
+        T::to_schema(&mut components);
+        serde_json::to_value(components.schemas.into_iter().next().unwrap().1).unwrap()
+    }
+
+    #[derive(Serialize, ToSchema)]
+    #[allow(dead_code)]
+    enum Single {
+        Only,
+    }
+    assert_json_eq!(
+        schema_of::<Single>(),
+        json!({"type": "string", "const": "Only"})
+    );
+
+    #[derive(Serialize, ToSchema)]
+    #[serde(tag = "kind")]
+    #[allow(dead_code)]
+    enum Shape {
+        Circle { radius: f64 },
+    }
+    assert_json_eq!(
+        schema_of::<Shape>(),
+        json!({
+            "discriminator": {"propertyName": "kind"},
+            "oneOf": [{
+                "type": "object",
+                "required": ["radius", "kind"],
+                "properties": {
+                    "radius": {"type": "number", "format": "double"},
+                    "kind": {"type": "string", "const": "Circle"}
+                }
+            }]
+        })
+    );
+
+    #[derive(Serialize, ToSchema)]
+    struct Circle {
+        /// Always a circle.
+        #[salvo(schema(value_type = "circle"))]
+        kind: String,
+        #[salvo(schema(value_type = 2))]
+        version: u8,
+    }
+    assert_json_eq!(
+        schema_of::<Circle>(),
+        json!({
+            "type": "object",
+            "required": ["kind", "version"],
+            "properties": {
+                "kind": {"type": "string", "const": "circle", "description": "Always a circle."},
+                "version": {"type": "integer", "const": 2}
+            }
+        })
+    );
+}
+
+#[test]
+fn test_derive_to_schema_xml() {
+    fn schema_of<T: ToSchema>() -> serde_json::Value {
+        let mut components = salvo::oapi::Components::new();
+        T::to_schema(&mut components);
+        serde_json::to_value(components.schemas.into_iter().next().unwrap().1).unwrap()
+    }
+
+    #[derive(Serialize, ToSchema)]
+    #[salvo(schema(xml(name = "pet", namespace = "https://example.com/pet", prefix = "p")))]
+    struct Pet {
+        #[salvo(schema(xml(attribute)))]
+        id: u64,
+        #[salvo(schema(xml(name = "tag", wrapped(name = "tags"))))]
+        tags: Vec<String>,
+    }
+    assert_json_eq!(
+        schema_of::<Pet>(),
+        json!({
+            "type": "object",
+            "required": ["id", "tags"],
+            "xml": {"name": "pet", "namespace": "https://example.com/pet", "prefix": "p"},
+            "properties": {
+                "id": {
+                    "type": "integer",
+                    "format": "uint64",
+                    "minimum": 0.0,
+                    "xml": {"attribute": true}
+                },
+                "tags": {
+                    "type": "array",
+                    "items": {"type": "string", "xml": {"name": "tag"}},
+                    "xml": {"name": "tags", "wrapped": true}
+                }
+            }
+        })
+    );
+
+    #[derive(Serialize, ToSchema)]
+    #[salvo(schema(xml(name = "color")))]
+    #[allow(dead_code)]
+    enum Color {
+        Red,
+        Green,
+    }
+    assert_json_eq!(
+        schema_of::<Color>(),
+        json!({"type": "string", "enum": ["Red", "Green"], "xml": {"name": "color"}})
+    );
+}
+
+#[test]
+fn test_derive_to_schema_serde_alias() {
+    #[derive(Deserialize, ToSchema)]
+    #[allow(dead_code)]
+    struct User {
+        /// Display name.
+        #[serde(alias = "full_name", alias = "fullName")]
+        name: String,
+        #[serde(alias = "mail")]
+        email: String,
+    }
+
+    let mut components = salvo::oapi::Components::new();
+    User::to_schema(&mut components);
+    let schema = serde_json::to_value(components.schemas.into_iter().next().unwrap().1).unwrap();
+    assert_json_eq!(
+        schema,
+        json!({
+            "type": "object",
+            "required": ["name", "email"],
+            "properties": {
+                "name": {
+                    "type": "string",
+                    "description": "Display name.\n\nAlso accepted as: `full_name`, `fullName`."
+                },
+                "email": {
+                    "type": "string",
+                    "description": "Also accepted as: `mail`."
+                }
+            }
+        })
+    );
+}
+
+#[test]
+fn test_derive_to_schema_flatten_map() {
+    #[derive(Serialize, ToSchema)]
+    struct Metadata {
+        name: String,
+        #[serde(flatten)]
+        extra: std::collections::HashMap<String, i32>,
+    }
+
+    let mut components = salvo::oapi::Components::new();
+    Metadata::to_schema(&mut components);
+    let schema = serde_json::to_value(components.schemas.into_iter().next().unwrap().1).unwrap();
+    assert_json_eq!(
+        schema,
+        json!({
+            "type": "object",
+            "required": ["name"],
+            "properties": {
+                "name": {"type": "string"}
+            },
+            "additionalProperties": {"type": "integer", "format": "int32"}
+        })
+    );
+}
+
+#[test]
+fn test_derive_to_schema_field_inline() {
+    #[derive(Serialize, ToSchema)]
+    struct Address {
+        city: String,
+    }
+    #[derive(Serialize, ToSchema)]
+    struct Tag {
+        label: String,
+    }
+    #[derive(Serialize, ToSchema)]
+    struct Customer {
+        #[salvo(schema(inline))]
+        address: Address,
+        #[salvo(schema(inline))]
+        previous: Option<Address>,
+        tag: Tag,
+    }
+
+    let mut components = salvo::oapi::Components::new();
+    Customer::to_schema(&mut components);
+    let names = components.schemas.keys().cloned().collect::<Vec<_>>();
+    assert_eq!(names.len(), 2);
+    assert!(names.iter().all(|name| !name.ends_with("Address")));
+    let schema = components
+        .schemas
+        .into_iter()
+        .find(|(name, _)| name.ends_with("Customer"))
+        .unwrap()
+        .1;
+    let address = json!({
+        "type": "object",
+        "required": ["city"],
+        "properties": {
+            "city": {"type": "string"}
+        }
+    });
+    let schema = serde_json::to_value(schema).unwrap();
+    assert_json_eq!(schema["properties"]["address"], address);
+    assert_json_eq!(
+        schema["properties"]["previous"],
+        json!({"allOf": [{"type": "null"}, address]})
+    );
+    assert!(schema["properties"]["tag"]["$ref"]
+        .as_str()
+        .unwrap()
+        .ends_with("Tag"));
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_derive_to_schema_deprecated_note() {
+    #[derive(Serialize, ToSchema)]
+    struct Forecast {
+        /// Highest temperature.
+        #[deprecated = "Use `max` instead."]
+        high: i32,
+        #[deprecated(since = "0.2.0", note = "Use `min` instead.")]
+        low: i32,
+        #[deprecated]
+        average: i32,
+    }
+
+    let mut components = salvo::oapi::Components::new();
+    Forecast::to_schema(&mut components);
+    let schema = serde_json::to_value(components.schemas.into_iter().next().unwrap().1).unwrap();
+    assert_json_eq!(
+        schema,
+        json!({
+            "type": "object",
+            "required": ["high", "low", "average"],
+            "properties": {
+                "high": {
+                    "type": "integer",
+                    "format": "int32",
+                    "deprecated": true,
+                    "description": "Highest temperature.\n\nDeprecated: Use `max` instead."
+                },
+                "low": {
+                    "type": "integer",
+                    "format": "int32",
+                    "deprecated": true,
+                    "description": "Deprecated: Use `min` instead."
+                },
+                "average": {
+                    "type": "integer",
+                    "format": "int32",
+                    "deprecated": true
+                }
+            }
+        })
+    );
+}
+
+#[test]
+fn test_derive_to_schema_array_items() {
+    #[derive(Serialize, ToSchema)]
+    struct Batch {
+        #[salvo(schema(min_items = 1, max_items = 50, unique_items))]
+        ids: Vec<i64>,
+        tags: std::collections::HashSet<String>,
+        #[salvo(schema(unique_items = false))]
+        labels: std::collections::BTreeSet<String>,
+    }
+
+    let mut components = salvo::oapi::Components::new();
+    Batch::to_schema(&mut components);
+    let schema = serde_json::to_value(components.schemas.into_iter().next().unwrap().1).unwrap();
+    assert_json_eq!(
+        schema["properties"],
+        json!({
+            "ids": {
+                "type": "array",
+                "items": {"type": "integer", "format": "int64"},
+                "minItems": 1,
+                "maxItems": 50,
+                "uniqueItems": true
+            },
+            "tags": {
+                "type": "array",
+                "items": {"type": "string"},
+                "uniqueItems": true
+            },
+            "labels": {
+                "type": "array",
+                "items": {"type": "string"}
+            }
+        })
+    );
+}
+
+#[test]
+fn test_derive_to_schema_binary_fields() {
+    #[derive(ToSchema)]
+    #[salvo(schema(name = Upload))]
+    #[allow(dead_code)]
+    struct Upload {
+        file: FormFile,
+        #[salvo(schema(format = Binary))]
+        data: Vec<u8>,
+        #[salvo(schema(format = Binary))]
+        thumbnail: Option<Vec<u8>>,
+    }
+
+    let mut components = salvo::oapi::Components::new();
+    Upload::to_schema(&mut components);
+    assert_json_eq!(
+        components.schemas.get("Upload").unwrap(),
+        json!({
+            "type": "object",
+            "required": ["file", "data"],
+            "properties": {
+                "file": {"type": "string", "format": "binary"},
+                "data": {"type": "string", "format": "binary"},
+                "thumbnail": {"type": ["string", "null"], "format": "binary"}
+            }
+        })
+    );
+}
+
+#[test]
+fn test_derive_to_schema_content_encoding() {
+    #[derive(ToSchema)]
+    #[salvo(schema(name = Png, content_encoding = "base64", content_media_type = "image/png"))]
+    #[allow(dead_code)]
+    struct Png(String);
+
+    #[derive(ToSchema)]
+    #[salvo(schema(name = Avatar))]
+    #[allow(dead_code)]
+    struct Avatar {
+        #[salvo(schema(content_encoding = "base64", content_media_type = "image/png"))]
+        data: String,
+        png: Png,
+    }
+
+    let mut components = salvo::oapi::Components::new();
+    Avatar::to_schema(&mut components);
+    assert_json_eq!(
+        components.schemas.get("Avatar").unwrap(),
+        json!({
+            "type": "object",
+            "required": ["data", "png"],
+            "properties": {
+                "data": {
+                    "type": "string",
+                    "contentEncoding": "base64",
+                    "contentMediaType": "image/png"
+                },
+                "png": {"$ref": "#/components/schemas/Png"}
+            }
+        })
+    );
+    assert_json_eq!(
+        components.schemas.get("Png").unwrap(),
+        json!({
+            "type": "string",
+            "contentEncoding": "base64",
+            "contentMediaType": "image/png"
+        })
+    );
+}
+
+#[test]
+fn test_derive_to_schema_deny_unknown_fields() {
+    #[derive(Deserialize, ToSchema)]
+    #[serde(deny_unknown_fields)]
+    #[salvo(schema(name = StrictUser))]
+    #[allow(dead_code)]
+    struct StrictUser {
+        name: String,
+    }
+
+    let mut components = salvo::oapi::Components::new();
+    StrictUser::to_schema(&mut components);
+    assert_json_eq!(
+        components.schemas.get("StrictUser").unwrap(),
+        json!({
+            "type": "object",
+            "required": ["name"],
+            "properties": {
+                "name": {"type": "string"}
+            },
+            "additionalProperties": false
+        })
+    );
+}
+
+#[test]
+fn test_derive_to_schema_additional_properties_type() {
+    #[derive(ToSchema)]
+    #[salvo(schema(name = Label))]
+    #[allow(dead_code)]
+    struct Label {
+        color: String,
+    }
+
+    #[derive(ToSchema)]
+    #[salvo(schema(name = Labels, additional_properties = Label))]
+    #[allow(dead_code)]
+    struct Labels {
+        default: Label,
+    }
+
+    #[derive(ToSchema)]
+    #[salvo(schema(name = Counters, additional_properties = i64))]
+    #[allow(dead_code)]
+    struct Counters {
+        total: i64,
+    }
+
+    #[derive(ToSchema, Serialize)]
+    #[salvo(schema(name = Audit))]
+    struct Audit {
+        created_by: String,
+    }
+
+    #[derive(ToSchema, Serialize)]
+    #[salvo(schema(name = Tagged, additional_properties = String))]
+    struct Tagged {
+        name: String,
+        #[serde(flatten)]
+        audit: Audit,
+    }
+
+    let mut components = salvo::oapi::Components::new();
+    Labels::to_schema(&mut components);
+    Counters::to_schema(&mut components);
+    Tagged::to_schema(&mut components);
+    assert_json_eq!(
+        components.schemas.get("Labels").unwrap(),
+        json!({
+            "type": "object",
+            "required": ["default"],
+            "properties": {
+                "default": {"$ref": "#/components/schemas/Label"}
+            },
+            "additionalProperties": {"$ref": "#/components/schemas/Label"}
+        })
+    );
+    assert_json_eq!(
+        components.schemas.get("Counters").unwrap(),
+        json!({
+            "type": "object",
+            "required": ["total"],
+            "properties": {
+                "total": {"type": "integer", "format": "int64"}
+            },
+            "additionalProperties": {"type": "integer", "format": "int64"}
+        })
+    );
+    assert_json_eq!(
+        components.schemas.get("Tagged").unwrap(),
+        json!({
+            "allOf": [
+                {"$ref": "#/components/schemas/Audit"},
+                {
+                    "type": "object",
+                    "required": ["name"],
+                    "properties": {
+                        "name": {"type": "string"}
+                    },
+                    "additionalProperties": {"type": "string"}
+                }
+            ]
+        })
+    );
+}
@@ -44,3 +44,213 @@ fn test_endpoint_hello() {
         })
     );
 }
+
+#[tokio::test]
+async fn test_endpoint_header_parameters() {
+    #[derive(serde::Deserialize, ToParameters, Debug)]
+    struct TenantQuery {
+        /// Tenant id.
+        #[salvo(parameter(parameter_in = Header, rename = "x-tenant-id"))]
+        tenant_id: String,
+        /// Page number.
+        page: Option<u32>,
+    }
+
+    #[endpoint]
+    async fn tenant(query: TenantQuery) -> String {
+        format!("{}:{}", query.tenant_id, query.page.unwrap_or_default())
+    }
+
+    let router = Router::new().push(Router::with_path("tenant").get(tenant));
+
+    let doc = OpenApi::new("test api", "0.0.1").merge_router(&router);
+    assert_json_eq!(
+        doc.paths
+            .get("/tenant")
+            .unwrap()
+            .operations
+            .get(&salvo::oapi::PathItemType::Get)
+            .unwrap()
+            .parameters,
+        json!([
+            {
+                "name": "x-tenant-id",
+                "in": "header",
+                "description": "Tenant id.",
+                "required": true,
+                "schema": {"type": "string"}
+            },
+            {
+                "name": "page",
+                "in": "query",
+                "description": "Page number.",
+                "required": false,
+                "schema": {"type": ["integer", "null"], "format": "uint32", "minimum": 0.0}
+            }
+        ])
+    );
+
+    use salvo::test::{ResponseExt, TestClient};
+    let content = TestClient::get("http://127.0.0.1:5801/tenant?page=2")
+        .add_header("X-Tenant-Id", "acme", true)
+        .send(router)
+        .await
+        .take_string()
+        .await
+        .unwrap();
+    assert_eq!(content, "acme:2");
+}
+
+#[test]
+fn test_endpoint_external_docs() {
+    #[derive(serde::Serialize, ToSchema, Debug)]
+    #[salvo(schema(external_docs(url = "https://example.com/pet", description = "Pet design")))]
+    struct Pet {
+        name: String,
+    }
+
+    #[endpoint(external_docs(url = "https://example.com/pets"))]
+    async fn pet() -> Json<Pet> {
+        Json(Pet {
+            name: "kitty".into(),
+        })
+    }
+
+    let router = Router::new().push(Router::with_path("pet").get(pet));
+
+    let doc = OpenApi::new("test api", "0.0.1").merge_router(&router);
+    let operation = doc
+        .paths
+        .get("/pet")
+        .unwrap()
+        .operations
+        .get(&salvo::oapi::PathItemType::Get)
+        .unwrap();
+    assert_json_eq!(
+        operation.external_docs,
+        json!({"url": "https://example.com/pets"})
+    );
+    assert_json_eq!(
+        doc.components
+            .schemas
+            .get("endpoint_tests.test_endpoint_external_docs.Pet")
+            .unwrap(),
+        json!({
+            "type": "object",
+            "required": ["name"],
+            "properties": {"name": {"type": "string"}},
+            "externalDocs": {
+                "url": "https://example.com/pet",
+                "description": "Pet design"
+            }
+        })
+    );
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_endpoint_deprecated() {
+    #[endpoint(deprecated)]
+    async fn old_pets() -> &'static str {
+        "pets"
+    }
+
+    #[endpoint]
+    #[deprecated = "Use `/pets` instead."]
+    async fn legacy_pets() -> &'static str {
+        "pets"
+    }
+
+    #[endpoint]
+    async fn pets() -> &'static str {
+        "pets"
+    }
+
+    let router = Router::new()
+        .push(Router::with_path("old_pets").get(old_pets))
+        .push(Router::with_path("legacy_pets").get(legacy_pets))
+        .push(Router::with_path("pets").get(pets));
+
+    let doc = OpenApi::new("test api", "0.0.1").merge_router(&router);
+    let deprecated = |path: &str| {
+        doc.paths
+            .get(path)
+            .unwrap()
+            .operations
+            .get(&salvo::oapi::PathItemType::Get)
+            .unwrap()
+            .deprecated
+            .clone()
+    };
+    assert_eq!(deprecated("/old_pets"), Some(salvo::oapi::Deprecated::True));
+    assert_eq!(
+        deprecated("/legacy_pets"),
+        Some(salvo::oapi::Deprecated::True)
+    );
+    assert_eq!(deprecated("/pets"), None);
+}
+
+#[test]
+fn test_endpoint_arg_descriptions() {
+    #[derive(serde::Deserialize, ToSchema, Debug)]
+    #[allow(dead_code)]
+    struct PetInput {
+        name: String,
+    }
+
+    #[endpoint]
+    async fn update_pet(
+        /// Id of the pet to update.
+        id: PathParam<u64>,
+        /// Notify the owner
+        /// about the change.
+        notify: QueryParam<bool, false>,
+        /// New values of the pet.
+        pet: JsonBody<PetInput>,
+        dry_run: QueryParam<bool, false>,
+    ) -> String {
+        format!("{} {:?} {:?} {:?}", *id, notify, pet, dry_run)
+    }
+
+    let router = Router::new().push(Router::with_path("pets/{id}").patch(update_pet));
+
+    let doc = OpenApi::new("test api", "0.0.1").merge_router(&router);
+    let operation = doc
+        .paths
+        .get("/pets/{id}")
+        .unwrap()
+        .operations
+        .get(&salvo::oapi::PathItemType::Patch)
+        .unwrap();
+    let description = |name: &str| {
+        operation
+            .parameters
+            .0
+            .iter()
+            .find(|parameter| parameter.name == name)
+            .unwrap()
+            .description
+            .clone()
+    };
+    assert_eq!(
+        description("id").as_deref(),
+        Some("Id of the pet to update.")
+    );
+    assert_eq!(
+        description("notify").as_deref(),
+        Some("Notify the owner\nabout the change.")
+    );
+    assert_eq!(
+        description("dry_run").as_deref(),
+        Some("Get parameter `dry_run` from request url query.")
+    );
+    assert_eq!(
+        operation
+            .request_body
+            .as_ref()
+            .unwrap()
+            .description
+            .as_deref(),
+        Some("New values of the pet.")
+    );
+}
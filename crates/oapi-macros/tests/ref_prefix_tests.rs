@@ -0,0 +1,74 @@
+#![allow(missing_docs)]
+// The ref prefix is global, so this test lives in its own test binary.
+use assert_json_diff::assert_json_eq;
+use salvo::oapi::{ToResponse, ToSchema};
+use salvo::prelude::*;
+use serde::Serialize;
+use serde_json::json;
+
+#[test]
+fn test_custom_ref_prefix() {
+    salvo::oapi::set_schema_ref_prefix("bundle.json#/definitions/");
+    salvo::oapi::set_response_ref_prefix("bundle.json#/responses/");
+
+    #[derive(Serialize, ToSchema)]
+    #[salvo(schema(name = Pet))]
+    struct Pet {
+        name: String,
+    }
+
+    #[derive(Serialize, ToSchema)]
+    #[salvo(schema(name = Owner))]
+    struct Owner {
+        pets: Vec<Pet>,
+    }
+
+    #[derive(ToSchema, ToResponse)]
+    #[salvo(response(description = "Not found"))]
+    #[allow(dead_code)]
+    struct NotFound {
+        message: String,
+    }
+
+    #[endpoint(responses((status_code = 404, response = NotFound)))]
+    async fn owner() -> Json<Owner> {
+        Json(Owner { pets: vec![] })
+    }
+
+    let router = Router::with_path("owner").get(owner);
+    let doc = OpenApi::new("test api", "0.0.1").merge_router(&router);
+    let operation = doc
+        .paths
+        .get("/owner")
+        .unwrap()
+        .operations
+        .get(&salvo::oapi::PathItemType::Get)
+        .unwrap();
+    assert_json_eq!(
+        operation.responses,
+        json!({
+            "200": {
+                "description": "Response with json format data",
+                "content": {
+                    "application/json": {
+                        "schema": {"$ref": "bundle.json#/definitions/Owner"}
+                    }
+                }
+            },
+            "404": {"$ref": "bundle.json#/responses/NotFound"}
+        })
+    );
+    assert_json_eq!(
+        doc.components.schemas.get("Owner").unwrap(),
+        json!({
+            "type": "object",
+            "required": ["pets"],
+            "properties": {
+                "pets": {
+                    "type": "array",
+                    "items": {"$ref": "bundle.json#/definitions/Pet"}
+                }
+            }
+        })
+    );
+}
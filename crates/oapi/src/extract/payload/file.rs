@@ -9,8 +9,8 @@ use salvo_core::{async_trait, Request};
 
 use crate::endpoint::EndpointArgRegister;
 use crate::{
-    Array, BasicType, Components, Content, KnownFormat, Object, Operation, RequestBody, Schema,
-    SchemaFormat,
+    Array, BasicType, Components, Content, KnownFormat, Object, Operation, RefOr, RequestBody,
+    Schema, SchemaFormat, ToSchema,
 };
 
 /// Represents the upload file.
@@ -93,27 +93,22 @@ impl<'ex> Extractible<'ex> for FormFile {
     }
 }
 
+impl ToSchema for FilePart {
+    fn to_schema(_components: &mut Components) -> RefOr<Schema> {
+        binary_schema().into()
+    }
+}
+
+impl ToSchema for FormFile {
+    fn to_schema(_components: &mut Components) -> RefOr<Schema> {
+        binary_schema().into()
+    }
+}
+
 #[async_trait]
 impl EndpointArgRegister for FormFile {
     fn register(_components: &mut Components, operation: &mut Operation, arg: &str) {
-        let schema = Schema::from(
-            Object::new().property(
-                arg,
-                Object::with_type(BasicType::String)
-                    .format(SchemaFormat::KnownFormat(KnownFormat::Binary)),
-            ),
-        );
-
-        if let Some(request_body) = &mut operation.request_body {
-            request_body
-                .contents
-                .insert("multipart/form-data".into(), Content::new(schema));
-        } else {
-            let request_body = RequestBody::new()
-                .description("Upload a file.")
-                .add_content("multipart/form-data", Content::new(schema));
-            operation.request_body = Some(request_body);
-        }
+        register_multipart_part(operation, arg, binary_schema(), "Upload a file.");
     }
 }
 
@@ -167,27 +162,84 @@ impl<'ex> Extractible<'ex> for FormFiles {
     }
 }
 
+impl ToSchema for FormFiles {
+    fn to_schema(_components: &mut Components) -> RefOr<Schema> {
+        Array::new().items(binary_schema()).into()
+    }
+}
+
 #[async_trait]
 impl EndpointArgRegister for FormFiles {
     fn register(_components: &mut Components, operation: &mut Operation, arg: &str) {
-        let schema = Schema::from(
-            Object::new().property(
-                arg,
-                Array::new().items(Schema::from(
-                    Object::with_type(BasicType::String)
-                        .format(SchemaFormat::KnownFormat(KnownFormat::Binary)),
-                )),
-            ),
+        register_multipart_part(
+            operation,
+            arg,
+            Array::new().items(binary_schema()),
+            "Upload files.",
+        );
+    }
+}
+
+fn binary_schema() -> Schema {
+    Object::with_type(BasicType::String)
+        .format(SchemaFormat::KnownFormat(KnownFormat::Binary))
+        .into()
+}
+
+/// Add the part to the `multipart/form-data` content of the request body, so every file argument
+/// of the endpoint is documented as its own part.
+fn register_multipart_part(
+    operation: &mut Operation,
+    arg: &str,
+    schema: impl Into<RefOr<Schema>>,
+    description: &str,
+) {
+    let request_body = operation
+        .request_body
+        .get_or_insert_with(|| RequestBody::new().description(description));
+    let content = request_body
+        .contents
+        .entry("multipart/form-data".into())
+        .or_insert_with(|| Content::new(Object::new()));
+    if let RefOr::Type(Schema::Object(object)) = &mut content.schema {
+        object.properties.insert(arg.into(), schema.into());
+    } else {
+        content.schema = Object::new().property(arg, schema).into();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use assert_json_diff::assert_json_eq;
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn test_form_file_register_parts() {
+        let mut components = Components::new();
+        let mut operation = Operation::new();
+        FormFile::register(&mut components, &mut operation, "avatar");
+        FormFiles::register(&mut components, &mut operation, "photos");
+        assert_json_eq!(
+            operation.request_body,
+            json!({
+                "description": "Upload a file.",
+                "content": {
+                    "multipart/form-data": {
+                        "schema": {
+                            "type": "object",
+                            "properties": {
+                                "avatar": {"type": "string", "format": "binary"},
+                                "photos": {
+                                    "type": "array",
+                                    "items": {"type": "string", "format": "binary"}
+                                }
+                            }
+                        }
+                    }
+                }
+            })
         );
-        if let Some(request_body) = &mut operation.request_body {
-            request_body
-                .contents
-                .insert("multipart/form-data".into(), Content::new(schema));
-        } else {
-            let request_body = RequestBody::new()
-                .description("Upload files.")
-                .add_content("multipart/form-data", Content::new(schema));
-            operation.request_body = Some(request_body);
-        }
     }
 }
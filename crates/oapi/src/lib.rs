@@ -40,6 +40,8 @@ pub use salvo_oapi_macros::endpoint;
 pub(crate) use salvo_oapi_macros::schema;
 #[doc = include_str!("../docs/derive_to_parameters.md")]
 pub use salvo_oapi_macros::ToParameters;
+#[doc = include_str!("../docs/derive_to_request_body.md")]
+pub use salvo_oapi_macros::ToRequestBody;
 #[doc = include_str!("../docs/derive_to_response.md")]
 pub use salvo_oapi_macros::ToResponse;
 #[doc = include_str!("../docs/derive_to_responses.md")]
@@ -310,7 +312,7 @@ impl<K: ToSchema, V: ToSchema> ToSchema for HashMap<K, V> {
 impl ToSchema for StatusError {
     fn to_schema(components: &mut Components) -> RefOr<schema::Schema> {
         let name = crate::naming::assign_name::<StatusError>(Default::default());
-        let ref_or = crate::RefOr::Ref(crate::Ref::new(format!("#/components/schemas/{}", name)));
+        let ref_or = crate::RefOr::Ref(crate::Ref::from_schema_name(&name));
         if !components.schemas.contains_key(&name) {
             components.schemas.insert(name.clone(), ref_or.clone());
             let schema = Schema::from(
@@ -343,7 +345,7 @@ where
 {
     fn to_schema(components: &mut Components) -> RefOr<schema::Schema> {
         let name = crate::naming::assign_name::<StatusError>(Default::default());
-        let ref_or = crate::RefOr::Ref(crate::Ref::new(format!("#/components/schemas/{}", name)));
+        let ref_or = crate::RefOr::Ref(crate::Ref::from_schema_name(&name));
         if !components.schemas.contains_key(&name) {
             components.schemas.insert(name.clone(), ref_or.clone());
             let schema = OneOf::new()
@@ -104,6 +104,7 @@ pub trait Namer: Sync + Send + 'static {
 #[derive(Default, Clone, Debug)]
 pub struct FlexNamer {
     short_mode: bool,
+    full_path_on_collision: bool,
     generic_delimiter: Option<(String, String)>,
 }
 impl FlexNamer {
@@ -118,6 +119,15 @@ impl FlexNamer {
         self
     }
 
+    /// Use the full path name when a short name collides with another type's name.
+    ///
+    /// This only takes effect in short mode. By default a numeric suffix is appended instead,
+    /// e.g. `User2`.
+    pub fn full_path_on_collision(mut self, full_path_on_collision: bool) -> Self {
+        self.full_path_on_collision = full_path_on_collision;
+        self
+    }
+
     /// Set the delimiter for generic types.
     pub fn generic_delimiter(mut self, open: impl Into<String>, close: impl Into<String>) -> Self {
         self.generic_delimiter = Some((open.into(), close.into()));
@@ -137,11 +147,35 @@ impl Namer for FlexNamer {
                 if let Some((open, close)) = &self.generic_delimiter {
                     base = base.replace('<', open).replace('>', close);
                 }
+                if self.short_mode && self.full_path_on_collision {
+                    if let Some((exist_id, exist_name)) = type_info_by_name(&base) {
+                        if exist_id != type_id {
+                            tracing::warn!(
+                                "Duplicate name `{}` for types: {}, {}, using full path name instead",
+                                base,
+                                exist_name,
+                                type_name
+                            );
+                            base = type_name.replace("::", ".");
+                            if let Some((open, close)) = &self.generic_delimiter {
+                                base = base.replace('<', open).replace('>', close);
+                            }
+                        }
+                    }
+                }
                 let mut name = base.to_string();
                 let mut count = 1;
-                while let Some(exist_id) = type_info_by_name(&name).map(|t| t.0) {
+                while let Some((exist_id, exist_name)) = type_info_by_name(&name) {
                     if exist_id != type_id {
                         count += 1;
+                        tracing::warn!(
+                            "Duplicate name `{}` for types: {}, {}, using `{}{}` instead",
+                            name,
+                            exist_name,
+                            type_name,
+                            base,
+                            count
+                        );
                         name = format!("{}{}", base, count);
                     } else {
                         break;
@@ -227,4 +261,35 @@ mod tests {
         // let name = assign_name::<nest::MyString>(NameRule::Auto);
         // assert_eq!(name, "MyString2");
     }
+
+    #[test]
+    fn test_short_name_collision() {
+        use std::any::{type_name, TypeId};
+
+        use super::*;
+
+        mod foo {
+            pub(crate) struct CollidingUser;
+        }
+        mod bar {
+            pub(crate) struct CollidingUser;
+        }
+        mod baz {
+            pub(crate) struct CollidingUser;
+        }
+        fn assign<T: 'static>(namer: &FlexNamer) -> String {
+            namer.assign_name(TypeId::of::<T>(), type_name::<T>(), NameRule::Auto)
+        }
+
+        let namer = FlexNamer::new().short_mode(true);
+        assert_eq!(assign::<foo::CollidingUser>(&namer), "CollidingUser");
+        assert_eq!(assign::<bar::CollidingUser>(&namer), "CollidingUser2");
+
+        let namer = namer.full_path_on_collision(true);
+        assert_eq!(assign::<foo::CollidingUser>(&namer), "CollidingUser");
+        assert_eq!(
+            assign::<baz::CollidingUser>(&namer),
+            "salvo_oapi.naming.tests.test_short_name_collision.baz.CollidingUser"
+        );
+    }
 }
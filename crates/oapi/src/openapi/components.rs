@@ -2,9 +2,45 @@
 //! used to define field properties, enum values, array or object types.
 //!
 //! [schema]: https://spec.openapis.org/oas/latest.html#schema-object
+use std::sync::LazyLock;
+
+use parking_lot::RwLock;
 use serde::{Deserialize, Serialize};
 
-use crate::{PropMap, RefOr, Response, Responses, Schema, Schemas, SecurityScheme};
+use crate::{PropMap, RefOr, Response, Responses, Schema, Schemas, SecurityScheme, ToSchema};
+
+const DEFAULT_SCHEMA_REF_PREFIX: &str = "#/components/schemas/";
+const DEFAULT_RESPONSE_REF_PREFIX: &str = "#/components/responses/";
+
+static SCHEMA_REF_PREFIX: LazyLock<RwLock<String>> =
+    LazyLock::new(|| RwLock::new(DEFAULT_SCHEMA_REF_PREFIX.to_owned()));
+static RESPONSE_REF_PREFIX: LazyLock<RwLock<String>> =
+    LazyLock::new(|| RwLock::new(DEFAULT_RESPONSE_REF_PREFIX.to_owned()));
+
+/// Set the global prefix used to build `$ref` of reusable schemas, default is `#/components/schemas/`.
+///
+/// This affects all refs generated by [`ToSchema`] and [`Ref::from_schema_name`][crate::Ref::from_schema_name]
+/// afterwards, so it should be called before the [`OpenApi`][crate::OpenApi] is generated.
+pub fn set_schema_ref_prefix(prefix: impl Into<String>) {
+    *SCHEMA_REF_PREFIX.write() = prefix.into();
+}
+/// Get the global prefix used to build `$ref` of reusable schemas.
+pub fn schema_ref_prefix() -> String {
+    SCHEMA_REF_PREFIX.read().clone()
+}
+
+/// Set the global prefix used to build `$ref` of reusable responses, default is `#/components/responses/`.
+///
+/// This affects all refs generated by [`ToResponse`][crate::ToResponse] and
+/// [`Ref::from_response_name`][crate::Ref::from_response_name] afterwards, so it should be called
+/// before the [`OpenApi`][crate::OpenApi] is generated.
+pub fn set_response_ref_prefix(prefix: impl Into<String>) {
+    *RESPONSE_REF_PREFIX.write() = prefix.into();
+}
+/// Get the global prefix used to build `$ref` of reusable responses.
+pub fn response_ref_prefix() -> String {
+    RESPONSE_REF_PREFIX.read().clone()
+}
 
 /// Implements [OpenAPI Components Object][components] which holds supported
 /// reusable objects.
@@ -174,6 +210,30 @@ impl Components {
         self.security_schemes.append(&mut other.security_schemes);
     }
 
+    /// Returns the schema of `T` inlined, instead of a reference to `#/components/schemas`.
+    ///
+    /// Schemas `T` depends on are still added to [`Components`], only the schema of `T` itself is
+    /// inlined. **warning:** Don't use this for recursive data types!
+    pub fn inline_schema<T: ToSchema + ?Sized>(&mut self) -> RefOr<Schema> {
+        let mut components = Components::new();
+        let schema = match T::to_schema(&mut components) {
+            RefOr::Ref(reference) => {
+                let name = reference
+                    .ref_location
+                    .rsplit('/')
+                    .next()
+                    .unwrap_or_default();
+                components
+                    .schemas
+                    .shift_remove(name)
+                    .unwrap_or(RefOr::Ref(reference))
+            }
+            schema => schema,
+        };
+        self.append(&mut components);
+        schema
+    }
+
     /// Returns `true` if instance contains no elements.
     pub fn is_empty(&self) -> bool {
         self.schemas.is_empty() && self.responses.is_empty() && self.security_schemes.is_empty()
@@ -29,7 +29,10 @@ use serde::de::{Error, Expected, Visitor};
 use serde::{Deserialize, Deserializer, Serialize, Serializer};
 
 pub use self::{
-    components::Components,
+    components::{
+        response_ref_prefix, schema_ref_prefix, set_response_ref_prefix, set_schema_ref_prefix,
+        Components,
+    },
     content::Content,
     example::Example,
     external_docs::ExternalDocs,
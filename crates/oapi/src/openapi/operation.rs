@@ -253,6 +253,12 @@ impl Operation {
         self
     }
 
+    /// Add or change external documentation of the [`Operation`].
+    pub fn external_docs(mut self, external_docs: ExternalDocs) -> Self {
+        self.external_docs = Some(external_docs);
+        self
+    }
+
     /// Add or change list of [`SecurityRequirement`]s that are available for [`Operation`].
     pub fn securities<I: IntoIterator<Item = SecurityRequirement>>(
         mut self,
@@ -294,8 +300,8 @@ mod tests {
 
     use super::{Operation, Operations};
     use crate::{
-        security::SecurityRequirement, server::Server, Deprecated, Parameter, PathItemType,
-        RequestBody, Responses,
+        security::SecurityRequirement, server::Server, Deprecated, ExternalDocs, Parameter,
+        PathItemType, RequestBody, Responses,
     };
 
     #[test]
@@ -329,6 +335,7 @@ mod tests {
             .request_body(RequestBody::new())
             .responses(Responses::new())
             .deprecated(Deprecated::False)
+            .external_docs(ExternalDocs::new("https://example.com/docs"))
             .securities([SecurityRequirement::new("api_key", ["read:items"])])
             .servers([Server::new("/api")]);
 
@@ -350,6 +357,9 @@ mod tests {
                 ],
                 "operationId": "operation_id",
                 "deprecated": false,
+                "externalDocs": {
+                    "url": "https://example.com/docs"
+                },
                 "security": [
                     {
                         "api_key": ["read:items"]
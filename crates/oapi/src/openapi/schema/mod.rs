@@ -285,15 +285,23 @@ impl Ref {
     }
 
     /// Construct a new [`Ref`] from provided schema name. This will create a [`Ref`] that
-    /// references the reusable schemas.
+    /// references the reusable schemas, see [`set_schema_ref_prefix`][crate::set_schema_ref_prefix].
     pub fn from_schema_name<I: Into<String>>(schema_name: I) -> Self {
-        Self::new(format!("#/components/schemas/{}", schema_name.into()))
+        Self::new(format!(
+            "{}{}",
+            crate::schema_ref_prefix(),
+            schema_name.into()
+        ))
     }
 
     /// Construct a new [`Ref`] from provided response name. This will create a [`Ref`] that
-    /// references the reusable response.
+    /// references the reusable response, see [`set_response_ref_prefix`][crate::set_response_ref_prefix].
     pub fn from_response_name<I: Into<String>>(response_name: I) -> Self {
-        Self::new(format!("#/components/responses/{}", response_name.into()))
+        Self::new(format!(
+            "{}{}",
+            crate::response_ref_prefix(),
+            response_name.into()
+        ))
     }
 
     /// Add or change reference location of the actual component.
@@ -305,7 +313,7 @@ impl Ref {
     /// Add or change reference location of the actual component automatically formatting the $ref
     /// to `#/components/schemas/...` format.
     pub fn ref_location_from_schema_name<S: Into<String>>(mut self, schema_name: S) -> Self {
-        self.ref_location = format!("#/components/schemas/{}", schema_name.into());
+        self.ref_location = format!("{}{}", crate::schema_ref_prefix(), schema_name.into());
         self
     }
 
@@ -3,7 +3,9 @@ use serde::{Deserialize, Serialize};
 use serde_json::Value;
 
 use super::AdditionalProperties;
-use crate::{Deprecated, PropMap, RefOr, Schema, SchemaFormat, SchemaType, ToArray, Xml};
+use crate::{
+    Deprecated, ExternalDocs, PropMap, RefOr, Schema, SchemaFormat, SchemaType, ToArray, Xml,
+};
 
 /// Implements subset of [OpenAPI Schema Object][schema] which allows
 /// adding other [`Schema`]s as **properties** to this [`Schema`].
@@ -40,6 +42,10 @@ pub struct Object {
     #[serde(default, rename = "enum", skip_serializing_if = "Vec::is_empty")]
     pub enum_values: Vec<Value>,
 
+    /// Single fixed value the [`Object`] must be equal to.
+    #[serde(rename = "const", skip_serializing_if = "Option::is_none")]
+    pub const_value: Option<Value>,
+
     /// Vector of required field names.
     #[serde(default, skip_serializing_if = "IndexSet::is_empty")]
     pub required: IndexSet<String>,
@@ -78,6 +84,10 @@ pub struct Object {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub xml: Option<Xml>,
 
+    /// Additional external documentation for the [`Object`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub external_docs: Option<ExternalDocs>,
+
     /// Must be a number strictly greater than `0`. Numeric value is considered valid if value
     /// divided by the _`multiple_of`_ value results an integer.
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -251,6 +261,12 @@ impl Object {
         self
     }
 
+    /// Add or change the single fixed value of the [`Object`].
+    pub fn const_value<V: Into<Value>>(mut self, const_value: V) -> Self {
+        self.const_value = Some(const_value.into());
+        self
+    }
+
     /// Add or change example shown in UI of the value for richer documentation.
     pub fn example<V: Into<Value>>(mut self, example: V) -> Self {
         self.examples.push(example.into());
@@ -281,6 +297,12 @@ impl Object {
         self
     }
 
+    /// Add or change external documentation of the [`Object`].
+    pub fn external_docs(mut self, external_docs: ExternalDocs) -> Self {
+        self.external_docs = Some(external_docs);
+        self
+    }
+
     /// Set or change _`multiple_of`_ validation flag for `number` and `integer` type values.
     pub fn multiple_of(mut self, multiple_of: f64) -> Self {
         self.multiple_of = Some(multiple_of);
@@ -20,7 +20,7 @@ const INDEX_TMPL: &str = r#"
     <script type="module" src="{{lib_url}}"></script>
   </head>
   <body>
-    <rapi-doc spec-url="{{spec_url}}"></rapi-doc>
+    <rapi-doc spec-url="{{spec_url}}"{{attributes}}></rapi-doc>
   </body>
 </html>
 "#;
@@ -39,6 +39,8 @@ pub struct RapiDoc {
     pub lib_url: Cow<'static, str>,
     /// The spec url path.
     pub spec_url: Cow<'static, str>,
+    /// Extra attributes of the `rapi-doc` element.
+    pub attributes: Vec<(Cow<'static, str>, Cow<'static, str>)>,
 }
 impl RapiDoc {
     /// Create a new [`RapiDoc`] for given path.
@@ -59,6 +61,7 @@ impl RapiDoc {
             description: None,
             lib_url: "https://unpkg.com/rapidoc/dist/rapidoc-min.js".into(),
             spec_url: spec_url.into(),
+            attributes: vec![],
         }
     }
 
@@ -86,6 +89,23 @@ impl RapiDoc {
         self
     }
 
+    /// Add an attribute to the `rapi-doc` element, such as `theme`, `render-style` or
+    /// `allow-try`. See the [RapiDoc API](https://rapidocweb.com/api.html) for all attributes.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use salvo_oapi::rapidoc::RapiDoc;
+    /// let doc = RapiDoc::new("/openapi.json")
+    ///     .title("My API")
+    ///     .attribute("theme", "dark")
+    ///     .attribute("render-style", "focused");
+    /// ```
+    pub fn attribute(mut self, name: impl Into<Cow<'static, str>>, value: impl Into<Cow<'static, str>>) -> Self {
+        self.attributes.push((name.into(), value.into()));
+        self
+    }
+
     /// Consusmes the [`RapiDoc`] and returns [`Router`] with the [`RapiDoc`] as handler.
     pub fn into_router(self, path: impl Into<String>) -> Router {
         Router::with_path(path.into()).goal(self)
@@ -110,8 +130,14 @@ impl Handler for RapiDoc {
             .as_ref()
             .map(|s| format!("<meta name=\"description\" content=\"{}\">", s))
             .unwrap_or_default();
+        let attributes = self
+            .attributes
+            .iter()
+            .map(|(name, value)| format!(" {}=\"{}\"", name, escape_attribute(value)))
+            .collect::<String>();
         let html = INDEX_TMPL
             .replacen("{{spec_url}}", &self.spec_url, 1)
+            .replacen("{{attributes}}", &attributes, 1)
             .replacen("{{lib_url}}", &self.lib_url, 1)
             .replacen("{{description}}", &description, 1)
             .replacen("{{keywords}}", &keywords, 1)
@@ -119,3 +145,36 @@ impl Handler for RapiDoc {
         res.render(Text::Html(html));
     }
 }
+
+fn escape_attribute(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('"', "&quot;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+#[cfg(test)]
+mod tests {
+    use salvo_core::prelude::*;
+    use salvo_core::test::{ResponseExt, TestClient};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_rapidoc_options() {
+        let doc = RapiDoc::new("/openapi.json")
+            .title("My API")
+            .attribute("theme", "dark")
+            .attribute("nav-item-spacing", "\"relaxed\"");
+        let service = Service::new(doc.into_router("rapidoc"));
+        let html = TestClient::get("http://127.0.0.1:5801/rapidoc")
+            .send(&service)
+            .await
+            .take_string()
+            .await
+            .unwrap();
+        assert!(html.contains("<title>My API</title>"));
+        assert!(html.contains(r#"<rapi-doc spec-url="/openapi.json" theme="dark" nav-item-spacing="&quot;relaxed&quot;">"#));
+    }
+}
@@ -31,7 +31,7 @@ const INDEX_TMPL: &str = r#"
     <script>
       Redoc.init(
         "{{spec_url}}",
-        {},
+        {{options}},
         document.getElementById("redoc-container")
       );
     </script>
@@ -53,6 +53,8 @@ pub struct ReDoc {
     pub lib_url: Cow<'static, str>,
     /// The spec url path.
     pub spec_url: Cow<'static, str>,
+    /// The options passed to `Redoc.init`.
+    pub options: Option<serde_json::Value>,
 }
 
 impl ReDoc {
@@ -74,6 +76,7 @@ impl ReDoc {
             description: None,
             lib_url: "https://cdn.redoc.ly/redoc/latest/bundles/redoc.standalone.js".into(),
             spec_url: spec_url.into(),
+            options: None,
         }
     }
 
@@ -101,6 +104,22 @@ impl ReDoc {
         self
     }
 
+    /// Set the options passed to `Redoc.init`, such as `expandResponses` or `hideDownloadButton`.
+    /// See the [Redoc configuration](https://redocly.com/docs/redoc/config) for all options.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use salvo_oapi::redoc::ReDoc;
+    /// let doc = ReDoc::new("/openapi.json")
+    ///     .title("My API")
+    ///     .options(serde_json::json!({ "expandResponses": "200", "hideDownloadButton": true }));
+    /// ```
+    pub fn options(mut self, options: serde_json::Value) -> Self {
+        self.options = Some(options);
+        self
+    }
+
     /// Consusmes the [`ReDoc`] and returns [`Router`] with the [`ReDoc`] as handler.
     pub fn into_router(self, path: impl Into<String>) -> Router {
         Router::with_path(path.into()).goal(self)
@@ -125,8 +144,15 @@ impl Handler for ReDoc {
             .as_ref()
             .map(|s| format!("<meta name=\"description\" content=\"{}\">", s))
             .unwrap_or_default();
+        // `</` is escaped so the options can not close the script element.
+        let options = self
+            .options
+            .as_ref()
+            .map(|options| options.to_string().replace("</", "<\\/"))
+            .unwrap_or_else(|| "{}".into());
         let html = INDEX_TMPL
             .replacen("{{spec_url}}", &self.spec_url, 1)
+            .replacen("{{options}}", &options, 1)
             .replacen("{{lib_url}}", &self.lib_url, 1)
             .replacen("{{description}}", &description, 1)
             .replacen("{{keywords}}", &keywords, 1)
@@ -134,3 +160,27 @@ impl Handler for ReDoc {
         res.render(Text::Html(html));
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use salvo_core::prelude::*;
+    use salvo_core::test::{ResponseExt, TestClient};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_redoc_options() {
+        let doc = ReDoc::new("/openapi.json")
+            .title("My API")
+            .options(serde_json::json!({ "hideDownloadButton": true }));
+        let service = Service::new(doc.into_router("redoc"));
+        let html = TestClient::get("http://127.0.0.1:5801/redoc")
+            .send(&service)
+            .await
+            .take_string()
+            .await
+            .unwrap();
+        assert!(html.contains("<title>My API</title>"));
+        assert!(html.contains(r#"{"hideDownloadButton":true},"#));
+    }
+}
@@ -24,7 +24,7 @@ const INDEX_TMPL: &str = r#"
   </head>
 
   <body>{{header}}
-    <script id="api-reference" data-url="{{spec_url}}"></script>
+    <script id="api-reference" data-url="{{spec_url}}"></script>{{configuration}}
     <script src="{{lib_url}}"></script>
   </body>
 </html>
@@ -48,6 +48,8 @@ pub struct Scalar {
     pub lib_url: Cow<'static, str>,
     /// The spec url path.
     pub spec_url: Cow<'static, str>,
+    /// The configuration of the api reference.
+    pub configuration: Option<serde_json::Value>,
 }
 impl Scalar {
     /// Create a new [`Scalar`] for given path.
@@ -70,6 +72,7 @@ impl Scalar {
             header: None,
             lib_url: "https://cdn.jsdelivr.net/npm/@scalar/api-reference".into(),
             spec_url: spec_url.into(),
+            configuration: None,
         }
     }
 
@@ -97,6 +100,23 @@ impl Scalar {
         self
     }
 
+    /// Set the configuration of the api reference, such as `theme`, `layout` or `hideModels`.
+    /// See the [Scalar configuration](https://github.com/scalar/scalar/blob/main/documentation/configuration.md)
+    /// for all options.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use salvo_oapi::scalar::Scalar;
+    /// let doc = Scalar::new("/openapi.json")
+    ///     .title("My API")
+    ///     .configuration(serde_json::json!({ "theme": "purple", "layout": "classic" }));
+    /// ```
+    pub fn configuration(mut self, configuration: serde_json::Value) -> Self {
+        self.configuration = Some(configuration);
+        self
+    }
+
     /// Consusmes the [`Scalar`] and returns [`Router`] with the [`Scalar`] as handler.
     pub fn into_router(self, path: impl Into<String>) -> Router {
         Router::with_path(path.into()).goal(self)
@@ -125,8 +145,20 @@ impl Handler for Scalar {
             .as_ref()
             .map(|s| format!("<style>{}</style>", s))
             .unwrap_or_default();
+        // `</` is escaped so the configuration can not close the script element.
+        let configuration = self
+            .configuration
+            .as_ref()
+            .map(|configuration| {
+                format!(
+                    "\n    <script>document.getElementById(\"api-reference\").dataset.configuration = JSON.stringify({});</script>",
+                    configuration.to_string().replace("</", "<\\/")
+                )
+            })
+            .unwrap_or_default();
         let html = INDEX_TMPL
             .replacen("{{lib_url}}", &self.lib_url, 1)
+            .replacen("{{configuration}}", &configuration, 1)
             .replacen("{{spec_url}}", &self.spec_url, 1)
             .replacen("{{header}}", self.header.as_deref().unwrap_or_default(), 1)
             .replacen("{{style}}", &style, 1)
@@ -248,3 +280,27 @@ const DEFAULT_STYLE: &str = r#":root {
     height: 32px;
   }
 "#;
+
+#[cfg(test)]
+mod tests {
+    use salvo_core::prelude::*;
+    use salvo_core::test::{ResponseExt, TestClient};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_scalar_options() {
+        let doc = Scalar::new("/openapi.json")
+            .title("My API")
+            .configuration(serde_json::json!({ "theme": "purple" }));
+        let service = Service::new(doc.into_router("scalar"));
+        let html = TestClient::get("http://127.0.0.1:5801/scalar")
+            .send(&service)
+            .await
+            .take_string()
+            .await
+            .unwrap();
+        assert!(html.contains("<title>My API</title>"));
+        assert!(html.contains(r#"dataset.configuration = JSON.stringify({"theme":"purple"});"#));
+    }
+}
@@ -193,6 +193,34 @@ impl SwaggerUi {
         self
     }
 
+    /// Set whether deep linking to tags and operations is enabled, see [`Config::deep_linking`].
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// # use salvo_oapi::swagger_ui::SwaggerUi;
+    /// let swagger = SwaggerUi::new("/api-docs/openapi.json")
+    ///     .title("My API")
+    ///     .deep_linking(true)
+    ///     .doc_expansion("none");
+    /// ```
+    pub fn deep_linking(mut self, deep_linking: bool) -> Self {
+        self.config = self.config.deep_linking(deep_linking);
+        self
+    }
+
+    /// Set default expansion of operations and tags, see [`Config::doc_expansion`].
+    pub fn doc_expansion(mut self, doc_expansion: impl Into<String>) -> Self {
+        self.config = self.config.doc_expansion(doc_expansion);
+        self
+    }
+
+    /// Set whether the filter bar of tags is shown, see [`Config::filter`].
+    pub fn filter(mut self, filter: bool) -> Self {
+        self.config = self.config.filter(filter);
+        self
+    }
+
     /// Consusmes the [`SwaggerUi`] and returns [`Router`] with the [`SwaggerUi`] as handler.
     pub fn into_router(self, path: impl Into<String>) -> Router {
         Router::with_path(format!("{}/{{**}}", path.into())).goal(self)
@@ -419,3 +447,29 @@ pub fn serve<'a>(
 
     Ok(file)
 }
+
+#[cfg(test)]
+mod tests {
+    use salvo_core::prelude::*;
+    use salvo_core::test::{ResponseExt, TestClient};
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_swagger_ui_options() {
+        let swagger = SwaggerUi::new("/api-doc/openapi.json")
+            .title("My API")
+            .deep_linking(true)
+            .doc_expansion("none");
+        let service = Service::new(swagger.into_router("swagger-ui"));
+        let html = TestClient::get("http://127.0.0.1:5801/swagger-ui/")
+            .send(&service)
+            .await
+            .take_string()
+            .await
+            .unwrap();
+        assert!(html.contains("<title>My API</title>"));
+        assert!(html.contains(r#""deepLinking":true"#));
+        assert!(html.contains(r#""docExpansion":"none""#));
+    }
+}
@@ -1,11 +1,11 @@
+use std::time::Duration;
+
 use hyper::upgrade::OnUpgrade;
 use hyper_rustls::{HttpsConnector, HttpsConnectorBuilder};
 use hyper_util::client::legacy::{connect::HttpConnector, Client as HyperUtilClient};
 use hyper_util::rt::TokioExecutor;
-use salvo_core::http::{ReqBody, ResBody, StatusCode};
-use salvo_core::rt::tokio::TokioIo;
+use salvo_core::http::{ReqBody, ResBody};
 use salvo_core::Error;
-use tokio::io::copy_bidirectional;
 
 use crate::{Client, HyperRequest, Proxy, BoxedError, Upstreams, HyperResponse};
 
@@ -17,15 +17,89 @@ pub struct HyperClient {
 
 impl Default for HyperClient {
     fn default() -> Self {
+        HyperClientBuilder::new().build()
+    }
+}
+
+/// Builder for [`HyperClient`] to tune the connection pool and protocol of the underlying client.
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct HyperClientBuilder {
+    /// Timeout for idle sockets being kept alive, `None` uses the `hyper_util` default.
+    pub pool_idle_timeout: Option<Duration>,
+    /// Maximum idle connections kept per host, `None` means no limit.
+    pub pool_max_idle_per_host: Option<usize>,
+    /// Whether only HTTP/2 is used to connect upstreams.
+    pub http2_only: bool,
+    /// Whether plaintext `http` upstreams are allowed, by default only `https` ones are.
+    pub allow_http: bool,
+}
+
+impl HyperClientBuilder {
+    /// Create new `HyperClientBuilder`.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets timeout for idle sockets being kept alive.
+    #[inline]
+    pub fn pool_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.pool_idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Sets maximum idle connections kept per host.
+    #[inline]
+    pub fn pool_max_idle_per_host(mut self, max_idle: usize) -> Self {
+        self.pool_max_idle_per_host = Some(max_idle);
+        self
+    }
+
+    /// Sets whether only HTTP/2 is used to connect upstreams.
+    ///
+    /// HTTP/2 is negotiated with ALPN for `https` upstreams, `http` upstreams must support HTTP/2
+    /// with prior knowledge.
+    #[inline]
+    pub fn http2_only(mut self, http2_only: bool) -> Self {
+        self.http2_only = http2_only;
+        self
+    }
+
+    /// Sets whether plaintext `http` upstreams are allowed besides `https` ones.
+    ///
+    /// It is off by default, so requests are never sent unencrypted by mistake. Enable it to
+    /// proxy services on a trusted network, e.g. a local WebSocket or development server.
+    #[inline]
+    pub fn allow_http(mut self, allow_http: bool) -> Self {
+        self.allow_http = allow_http;
+        self
+    }
+
+    /// Build the [`HyperClient`].
+    pub fn build(self) -> HyperClient {
         let https = HttpsConnectorBuilder::new()
             .with_native_roots()
-            .expect("no native root CA certificates found")
-            .https_only()
-            .enable_http1()
-            .build();
-        Self {
-            inner: HyperUtilClient::builder(TokioExecutor::new()).build(https),
+            .expect("no native root CA certificates found");
+        let https = if self.allow_http {
+            https.https_or_http()
+        } else {
+            https.https_only()
+        };
+        let https = if self.http2_only {
+            https.enable_http2().build()
+        } else {
+            https.enable_http1().build()
+        };
+        let mut builder = HyperUtilClient::builder(TokioExecutor::new());
+        if let Some(timeout) = self.pool_idle_timeout {
+            builder.pool_idle_timeout(timeout);
+        }
+        if let Some(max_idle) = self.pool_max_idle_per_host {
+            builder.pool_max_idle_per_host(max_idle);
         }
+        builder.http2_only(self.http2_only);
+        HyperClient::new(builder.build(https))
     }
 }
 
@@ -45,6 +119,12 @@ impl HyperClient {
     pub fn new(inner: HyperUtilClient<HttpsConnector<HttpConnector>, ReqBody>) -> Self {
         Self { inner }
     }
+
+    /// Create a [`HyperClientBuilder`] to configure the connection pool and protocol.
+    #[inline]
+    pub fn builder() -> HyperClientBuilder {
+        HyperClientBuilder::new()
+    }
 }
 
 impl Client for HyperClient {
@@ -59,33 +139,7 @@ impl Client for HyperClient {
 
         let mut response = self.inner.request(proxied_request).await.map_err(Error::other)?;
 
-        if response.status() == StatusCode::SWITCHING_PROTOCOLS {
-            let response_upgrade_type = crate::get_upgrade_type(response.headers());
-            if request_upgrade_type == response_upgrade_type.map(|s| s.to_lowercase()) {
-                let response_upgraded = hyper::upgrade::on(&mut response).await?;
-                if let Some(request_upgraded) = request_upgraded {
-                    tokio::spawn(async move {
-                        match request_upgraded.await {
-                            Ok(request_upgraded) => {
-                                let mut request_upgraded = TokioIo::new(request_upgraded);
-                                let mut response_upgraded = TokioIo::new(response_upgraded);
-                                if let Err(e) = copy_bidirectional(&mut response_upgraded, &mut request_upgraded).await
-                                {
-                                    tracing::error!(error = ?e, "coping between upgraded connections failed.");
-                                }
-                            }
-                            Err(e) => {
-                                tracing::error!(error = ?e, "upgrade request failed.");
-                            }
-                        }
-                    });
-                } else {
-                    return Err(Error::other("request does not have an upgrade extension."));
-                }
-            } else {
-                return Err(Error::other("upgrade type mismatch"));
-            }
-        }
+        crate::upgrade::upgrade_response(request_upgrade_type.as_deref(), request_upgraded, &mut response).await?;
         Ok(response.map(ResBody::Hyper))
     }
 }
@@ -94,6 +148,7 @@ impl Client for HyperClient {
 // Unit tests for Proxy
 #[cfg(test)]
 mod tests {
+    use salvo_core::conn::Acceptor;
     use salvo_core::prelude::*;
     use salvo_core::test::*;
 
@@ -104,7 +159,7 @@ mod tests {
     async fn test_upstreams_elect() {
         let upstreams = vec!["https://www.example.com", "https://www.example2.com"];
         let proxy = Proxy::new(upstreams.clone(), HyperClient::default());
-        let elected_upstream = proxy.upstreams().elect().await.unwrap();
+        let elected_upstream = proxy.upstreams().elect(&Request::new(), &Depot::new()).await.unwrap();
         assert!(upstreams.contains(&elected_upstream));
     }
 
@@ -123,6 +178,134 @@ mod tests {
         assert!(content.contains("Install Rust"));
     }
 
+    #[tokio::test]
+    async fn test_merge_early_hints() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let upstream = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = [0; 1024];
+            let _ = stream.read(&mut buf).await.unwrap();
+            stream
+                .write_all(
+                    b"HTTP/1.1 103 Early Hints\r\nLink: </style.css>; rel=preload; as=style\r\n\r\n\
+                    HTTP/1.1 200 OK\r\ncontent-length: 5\r\n\r\nhello",
+                )
+                .await
+                .unwrap();
+        });
+
+        let https = HttpsConnectorBuilder::new()
+            .with_native_roots()
+            .unwrap()
+            .https_or_http()
+            .enable_http1()
+            .build();
+        let client = HyperClient::new(HyperUtilClient::builder(TokioExecutor::new()).build(https));
+        let router = Router::with_path("{**rest}")
+            .goal(Proxy::new(upstream, client).informational(crate::InformationalPolicy::MergeEarlyHints));
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/").send(router).await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        assert_eq!(res.headers().get("link").unwrap(), "</style.css>; rel=preload; as=style");
+        assert_eq!(res.take_string().await.unwrap(), "hello");
+    }
+
+    #[tokio::test]
+    async fn test_websocket_passthrough() {
+        use hyper::header::{CONNECTION, SEC_WEBSOCKET_ACCEPT, SEC_WEBSOCKET_KEY, SEC_WEBSOCKET_VERSION, UPGRADE};
+        use salvo_core::rt::tokio::TokioIo;
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // A minimal upstream which accepts the upgrade and echoes raw bytes back.
+        let upstream_listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let upstream = format!("http://{}", upstream_listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let (mut stream, _) = upstream_listener.accept().await.unwrap();
+            let mut buf = [0; 1024];
+            let n = stream.read(&mut buf).await.unwrap();
+            let head = String::from_utf8_lossy(&buf[..n]).to_lowercase();
+            assert!(head.contains("upgrade: websocket"));
+            assert!(head.contains("connection: upgrade"));
+            stream
+                .write_all(
+                    b"HTTP/1.1 101 Switching Protocols\r\nupgrade: websocket\r\nconnection: Upgrade\r\n\
+                    sec-websocket-accept: s3pPLMBiTxaQ9kYGzzhZRbK+xOo=\r\n\r\n",
+                )
+                .await
+                .unwrap();
+            loop {
+                let n = stream.read(&mut buf).await.unwrap();
+                if n == 0 {
+                    break;
+                }
+                stream.write_all(&buf[..n]).await.unwrap();
+            }
+        });
+
+        let client = HyperClient::builder().allow_http(true).build();
+        let router = Router::with_path("{**rest}").goal(Proxy::new(upstream, client));
+        let acceptor = TcpListener::new("127.0.0.1:0").bind().await;
+        let addr = acceptor.holdings()[0].local_addr.clone().into_std().unwrap();
+        tokio::spawn(async move {
+            Server::new(acceptor).serve(router).await;
+        });
+
+        let stream = tokio::net::TcpStream::connect(addr).await.unwrap();
+        let (mut sender, conn) = hyper::client::conn::http1::handshake(TokioIo::new(stream)).await.unwrap();
+        tokio::spawn(conn.with_upgrades());
+        let req = hyper::Request::builder()
+            .uri(format!("http://{addr}/chat"))
+            .header(UPGRADE, "websocket")
+            .header(CONNECTION, "Upgrade")
+            .header(SEC_WEBSOCKET_KEY, "dGhlIHNhbXBsZSBub25jZQ==")
+            .header(SEC_WEBSOCKET_VERSION, "13")
+            .body(http_body_util::Empty::<hyper::body::Bytes>::new())
+            .unwrap();
+        let mut res = sender.send_request(req).await.unwrap();
+        assert_eq!(res.status(), StatusCode::SWITCHING_PROTOCOLS);
+        assert_eq!(res.headers().get(SEC_WEBSOCKET_ACCEPT).unwrap(), "s3pPLMBiTxaQ9kYGzzhZRbK+xOo=");
+
+        let mut upgraded = TokioIo::new(hyper::upgrade::on(&mut res).await.unwrap());
+        upgraded.write_all(b"hello").await.unwrap();
+        let mut buf = [0; 5];
+        upgraded.read_exact(&mut buf).await.unwrap();
+        assert_eq!(&buf, b"hello");
+    }
+
+    #[tokio::test]
+    async fn test_hyper_client_builder() {
+        #[handler]
+        async fn hello(req: &mut Request) -> String {
+            format!("{:?}", req.version())
+        }
+
+        let upstream_router = Router::with_path("hello").get(hello);
+        let acceptor = TcpListener::new("127.0.0.1:0").bind().await;
+        let upstream = format!("http://{}", acceptor.holdings()[0].local_addr.clone().into_std().unwrap());
+        tokio::spawn(async move {
+            Server::new(acceptor).serve(upstream_router).await;
+        });
+
+        let client = HyperClient::builder()
+            .pool_idle_timeout(Duration::from_secs(30))
+            .pool_max_idle_per_host(4)
+            .allow_http(true)
+            .build();
+        let service = Service::new(Router::with_path("{**rest}").goal(Proxy::new(upstream, client)));
+        for _ in 0..2 {
+            let content = TestClient::get("http://127.0.0.1:5801/hello")
+                .send(&service)
+                .await
+                .take_string()
+                .await
+                .unwrap();
+            assert_eq!(content, "HTTP/1.1");
+        }
+    }
+
     #[test]
     fn test_others() {
         let mut handler = Proxy::new(["https://www.bing.com"], HyperClient::default());
@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use bytes::Bytes;
+use salvo_core::async_trait;
+use salvo_core::http::header::{HeaderMap, HeaderName};
+use salvo_core::http::StatusCode;
+
+/// Default header name which carries the idempotency key.
+pub const IDEMPOTENCY_KEY: HeaderName = HeaderName::from_static("idempotency-key");
+
+/// A buffered upstream response which is replayed for duplicated idempotency keys.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct IdempotentResponse {
+    /// Response status code.
+    pub status: StatusCode,
+    /// Response headers.
+    pub headers: HeaderMap,
+    /// Response body.
+    pub body: Bytes,
+}
+impl IdempotentResponse {
+    /// Create a new `IdempotentResponse`.
+    pub fn new(status: StatusCode, headers: HeaderMap, body: Bytes) -> Self {
+        Self {
+            status,
+            headers,
+            body,
+        }
+    }
+}
+
+/// Store for responses of requests bearing an idempotency key.
+#[async_trait]
+pub trait IdempotencyStore: Send + Sync + 'static {
+    /// Load the cached response for the key, if it is still within its window.
+    async fn load(&self, key: &str) -> Option<IdempotentResponse>;
+    /// Save the response for the key, it should be kept for `window`.
+    async fn save(&self, key: &str, response: IdempotentResponse, window: Duration);
+}
+
+/// An in-memory [`IdempotencyStore`].
+///
+/// Expired entries are evicted lazily when new responses are saved.
+#[derive(Default, Debug)]
+pub struct MemoryIdempotencyStore {
+    entries: Mutex<HashMap<String, (Instant, IdempotentResponse)>>,
+}
+impl MemoryIdempotencyStore {
+    /// Create a new `MemoryIdempotencyStore`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl IdempotencyStore for MemoryIdempotencyStore {
+    async fn load(&self, key: &str) -> Option<IdempotentResponse> {
+        let entries = self.entries.lock().ok()?;
+        entries
+            .get(key)
+            .filter(|(expires_at, _)| *expires_at > Instant::now())
+            .map(|(_, response)| response.clone())
+    }
+    async fn save(&self, key: &str, response: IdempotentResponse, window: Duration) {
+        if let Ok(mut entries) = self.entries.lock() {
+            let now = Instant::now();
+            entries.retain(|_, (expires_at, _)| *expires_at > now);
+            entries.insert(key.to_owned(), (now + window, response));
+        }
+    }
+}
+
+pub(crate) struct Idempotency {
+    pub(crate) store: Box<dyn IdempotencyStore>,
+    pub(crate) window: Duration,
+    in_flight: Mutex<HashMap<String, Arc<tokio::sync::Mutex<()>>>>,
+}
+impl Idempotency {
+    pub(crate) fn new(store: impl IdempotencyStore, window: Duration) -> Self {
+        Self {
+            store: Box::new(store),
+            window,
+            in_flight: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Join the in-flight requests with the same key, they are serialized by [`InFlight::lock`].
+    ///
+    /// The key is removed from the in-flight map when the last [`InFlight`] of it is dropped, even
+    /// if the request is cancelled.
+    pub(crate) fn acquire(&self, key: &str) -> InFlight<'_> {
+        let mut in_flight = self.in_flight.lock().unwrap_or_else(|e| e.into_inner());
+        let lock = in_flight.entry(key.to_owned()).or_default().clone();
+        InFlight {
+            idempotency: self,
+            key: key.to_owned(),
+            lock,
+        }
+    }
+
+    #[cfg(test)]
+    pub(crate) fn in_flight_len(&self) -> usize {
+        self.in_flight
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .len()
+    }
+}
+
+/// A request taking part in the in-flight requests of an idempotency key.
+pub(crate) struct InFlight<'a> {
+    idempotency: &'a Idempotency,
+    key: String,
+    lock: Arc<tokio::sync::Mutex<()>>,
+}
+impl InFlight<'_> {
+    /// Wait until the other requests with the same key are done.
+    pub(crate) async fn lock(&self) -> tokio::sync::MutexGuard<'_, ()> {
+        self.lock.lock().await
+    }
+}
+impl Drop for InFlight<'_> {
+    fn drop(&mut self) {
+        let mut in_flight = self
+            .idempotency
+            .in_flight
+            .lock()
+            .unwrap_or_else(|e| e.into_inner());
+        // One reference is held by the map and one by this request.
+        if Arc::strong_count(&self.lock) <= 2 {
+            in_flight.remove(&self.key);
+        }
+    }
+}
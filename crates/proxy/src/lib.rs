@@ -30,6 +30,27 @@
 //!     Server::new(acceptor).serve(router).await;
 //! }
 //! ```
+//!
+//! # WebSocket
+//!
+//! Protocol upgrade requests, such as WebSocket handshakes, are forwarded with their `Upgrade` and
+//! `Connection` headers. When the upstream answers `101 Switching Protocols`, the client connection
+//! and the upstream connection are bridged bidirectionally until either side closes, so a WebSocket
+//! server can be proxied without any extra configuration. Plaintext `http` upstreams must be
+//! allowed explicitly with [`HyperClientBuilder::allow_http`]:
+//!
+//! ```no_run
+//! use salvo_core::prelude::*;
+//! use salvo_proxy::{HyperClient, Proxy};
+//!
+//! #[tokio::main]
+//! async fn main() {
+//!     let client = HyperClient::builder().allow_http(true).build();
+//!     let router = Router::with_path("ws/{**rest}").goal(Proxy::new("http://localhost:5800", client));
+//!     let acceptor = TcpListener::new("0.0.0.0:8888").bind().await;
+//!     Server::new(acceptor).serve(router).await;
+//! }
+//! ```
 #![doc(html_favicon_url = "https://salvo.rs/favicon-32x32.png")]
 #![doc(html_logo_url = "https://salvo.rs/images/logo.svg")]
 #![cfg_attr(docsrs, feature(doc_cfg))]
@@ -37,22 +58,62 @@
 use std::convert::Infallible;
 use std::error::Error as StdError;
 use std::future::Future;
+use std::hash::Hasher;
+use std::net::IpAddr;
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+use bytes::Bytes;
+use http_body_util::{BodyExt, LengthLimitError, Limited};
 
+use hyper::body::Body;
 use hyper::upgrade::OnUpgrade;
-use percent_encoding::{utf8_percent_encode, CONTROLS};
-use salvo_core::http::header::{HeaderMap, HeaderName, HeaderValue, CONNECTION, HOST, UPGRADE};
+use percent_encoding::{utf8_percent_encode, AsciiSet, CONTROLS};
+use salvo_core::http::header::{
+    HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, CONNECTION, CONTENT_LENGTH, FORWARDED, HOST,
+    LINK, PROXY_AUTHENTICATE, PROXY_AUTHORIZATION, TE, TRAILER, TRANSFER_ENCODING, UPGRADE,
+};
 use salvo_core::http::uri::Uri;
-use salvo_core::http::{ReqBody, ResBody, StatusCode};
+use salvo_core::http::{Method, ReqBody, ResBody, StatusCode};
 use salvo_core::{async_trait, BoxedError, Depot, Error, FlowCtrl, Handler, Request, Response};
 
 #[macro_use]
 mod cfg;
 
+mod idempotency;
+use idempotency::Idempotency;
+pub use idempotency::{
+    IdempotencyStore, IdempotentResponse, MemoryIdempotencyStore, IDEMPOTENCY_KEY,
+};
+mod upstreams;
+pub use upstreams::{HealthCheckedUpstreams, RoundRobin, RuleUpstreams, StickyUpstreams, Weighted};
+
+cfg_feature! {
+    #![any(
+        feature = "hyper-client",
+        feature = "reqwest-client",
+        all(unix, feature = "unix-sock-client"),
+        feature = "pooling-client"
+    )]
+    mod upgrade;
+}
 cfg_feature! {
     #![feature = "hyper-client"]
     mod hyper_client;
     pub use hyper_client::*;
 }
+cfg_feature! {
+    #![all(unix, feature = "unix-sock-client")]
+    mod unix_sock_client;
+    pub use unix_sock_client::*;
+}
+cfg_feature! {
+    #![feature = "pooling-client"]
+    mod pooling_client;
+    pub use pooling_client::*;
+}
 cfg_feature! {
     #![feature = "reqwest-client"]
     mod reqwest_client;
@@ -62,16 +123,36 @@ cfg_feature! {
 type HyperRequest = hyper::Request<ReqBody>;
 type HyperResponse = hyper::Response<ResBody>;
 
+// Characters which can not appear literally in a path segment, `?` and `#` would otherwise end the
+// path and turn the rest of it into the query or fragment of the forwarded url.
+const PATH_SEGMENT: &AsciiSet = &CONTROLS
+    .add(b' ')
+    .add(b'"')
+    .add(b'#')
+    .add(b'%')
+    .add(b'<')
+    .add(b'>')
+    .add(b'?')
+    .add(b'`')
+    .add(b'{')
+    .add(b'}');
+
 /// Encode url path. This can be used when build your custom url path getter.
+///
+/// The path must be decoded, `%` is encoded too, so `100%off` becomes `100%25off`.
 #[inline]
-pub(crate) fn encode_url_path(path: &str) -> String {
+pub fn encode_url_path(path: &str) -> String {
     path.split('/')
-        .map(|s| utf8_percent_encode(s, CONTROLS).to_string())
+        .map(|s| utf8_percent_encode(s, PATH_SEGMENT).to_string())
         .collect::<Vec<_>>()
         .join("/")
 }
 
 /// Client trait.
+///
+/// The body of the returned response is relayed to the client without buffering, it is dropped as
+/// soon as the client disconnects. Implementations should stop reading from the upstream when the
+/// body is dropped, so resources are not held until the upstream reaches EOF.
 pub trait Client: Send + Sync + 'static {
     /// Error type.
     type Error: StdError + Send + Sync + 'static;
@@ -83,30 +164,103 @@ pub trait Client: Send + Sync + 'static {
     ) -> impl Future<Output = Result<HyperResponse, Self::Error>> + Send;
 }
 
+/// Key used to insert the elected upstream into depot.
+pub const PROXY_UPSTREAM_KEY: &str = "::salvo::proxy::upstream";
+/// Key used to insert the upstream response time into depot.
+pub const PROXY_ELAPSED_KEY: &str = "::salvo::proxy::elapsed";
+
+/// Extension for Depot.
+pub trait ProxyDepotExt {
+    /// Get the upstream elected by [`Proxy`] for the current request.
+    ///
+    /// If the request was retried, it is the upstream of the last attempt.
+    fn proxy_upstream(&self) -> Option<&str>;
+    /// Get the time [`Proxy`] waited for the upstream response headers, including retries.
+    ///
+    /// The response body is relayed after it, so streaming the body is not included.
+    fn proxy_elapsed(&self) -> Option<Duration>;
+}
+
+impl ProxyDepotExt for Depot {
+    #[inline]
+    fn proxy_upstream(&self) -> Option<&str> {
+        self.get::<String>(PROXY_UPSTREAM_KEY)
+            .ok()
+            .map(|upstream| upstream.as_str())
+    }
+    #[inline]
+    fn proxy_elapsed(&self) -> Option<Duration> {
+        self.get::<Duration>(PROXY_ELAPSED_KEY).ok().copied()
+    }
+}
+
+const X_FORWARDED_FOR: HeaderName = HeaderName::from_static("x-forwarded-for");
+const X_FORWARDED_PROTO: HeaderName = HeaderName::from_static("x-forwarded-proto");
+const X_FORWARDED_HOST: HeaderName = HeaderName::from_static("x-forwarded-host");
+
+/// Path of the unix socket the request is proxied to.
+///
+/// It is inserted into the extensions of proxied requests whose upstream has the form
+/// `unix:/path/to/app.sock`, the uri of these requests only has the path and query. [`Client`]
+/// implementations supporting unix sockets, such as `UnixSockClient`, connect to it.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub struct UnixSocketPath(pub PathBuf);
+
 /// Upstreams trait.
+///
+/// # Migration
+///
+/// `elect` used to take only `&self`. It now also receives the current request and depot, so
+/// existing implementations need to add the two parameters, and can ignore them if they do not
+/// need them:
+///
+/// ```
+/// use std::convert::Infallible;
+///
+/// use salvo_core::{Depot, Request};
+/// use salvo_proxy::Upstreams;
+///
+/// struct Backend(String);
+///
+/// impl Upstreams for Backend {
+///     type Error = Infallible;
+///
+///     // Before: `async fn elect(&self) -> Result<&str, Self::Error>`
+///     async fn elect(&self, _req: &Request, _depot: &Depot) -> Result<&str, Self::Error> {
+///         Ok(&self.0)
+///     }
+/// }
+/// ```
 pub trait Upstreams: Send + Sync + 'static {
     /// Error type.
     type Error: StdError + Send + Sync + 'static;
     /// Elect a upstream to process current request.
-    fn elect(&self) -> impl Future<Output = Result<&str, Self::Error>> + Send;
+    ///
+    /// The request and depot can be used to route requests to different upstreams, e.g. by path
+    /// or by a route stored in depot by a previous handler.
+    fn elect(
+        &self,
+        req: &Request,
+        depot: &Depot,
+    ) -> impl Future<Output = Result<&str, Self::Error>> + Send;
 }
 impl Upstreams for &'static str {
     type Error = Infallible;
 
-    async fn elect(&self) -> Result<&str, Self::Error> {
+    async fn elect(&self, _req: &Request, _depot: &Depot) -> Result<&str, Self::Error> {
         Ok(*self)
     }
 }
 impl Upstreams for String {
     type Error = Infallible;
-    async fn elect(&self) -> Result<&str, Self::Error> {
+    async fn elect(&self, _req: &Request, _depot: &Depot) -> Result<&str, Self::Error> {
         Ok(self.as_str())
     }
 }
 
 impl<const N: usize> Upstreams for [&'static str; N] {
     type Error = Error;
-    async fn elect(&self) -> Result<&str, Self::Error> {
+    async fn elect(&self, _req: &Request, _depot: &Depot) -> Result<&str, Self::Error> {
         if self.is_empty() {
             return Err(Error::other("upstreams is empty"));
         }
@@ -120,7 +274,7 @@ where
     T: AsRef<str> + Send + Sync + 'static,
 {
     type Error = Error;
-    async fn elect(&self) -> Result<&str, Self::Error> {
+    async fn elect(&self, _req: &Request, _depot: &Depot) -> Result<&str, Self::Error> {
         if self.is_empty() {
             return Err(Error::other("upstreams is empty"));
         }
@@ -129,9 +283,69 @@ where
     }
 }
 
+/// Idempotent methods which are retried by default, see [`Proxy::retries`].
+pub const DEFAULT_RETRY_METHODS: [Method; 4] =
+    [Method::GET, Method::HEAD, Method::PUT, Method::DELETE];
+
 /// Url part getter. You can use this to get the proxied url path or query.
 pub type UrlPartGetter = Box<dyn Fn(&Request, &Depot) -> Option<String> + Send + Sync + 'static>;
 
+/// Upstream response headers mapper, see [`Proxy::map_response_headers`].
+pub type ResponseHeadersMapper = Box<dyn Fn(&mut HeaderMap, &Request) + Send + Sync + 'static>;
+
+/// Forward header value getter, see [`Proxy::add_forward_header_with`].
+pub type ForwardHeaderGetter =
+    Box<dyn Fn(&Request, &Depot) -> Option<HeaderValue> + Send + Sync + 'static>;
+
+/// Proxy error handler, see [`Proxy::on_error`].
+pub type ErrorHandler = Box<dyn Fn(&Error, &mut Response) + Send + Sync + 'static>;
+
+/// Idempotency key scope getter, see [`Proxy::idempotency_scope`].
+pub type IdempotencyScope = Box<dyn Fn(&Request, &Depot) -> Option<String> + Send + Sync + 'static>;
+
+/// Default max size of buffered upstream response bodies, 10 MiB.
+pub const DEFAULT_MAX_RESPONSE_SIZE: usize = 10 * 1024 * 1024;
+
+/// Default max size of upstream response bodies saved for idempotency keys, 1 MiB.
+pub const DEFAULT_MAX_IDEMPOTENT_RESPONSE_SIZE: usize = 1024 * 1024;
+
+/// Default idempotency key scope, the client address and its `Authorization` header.
+///
+/// The scope is hashed so credentials are not written into the idempotency store.
+pub fn default_idempotency_scope(req: &Request, _depot: &Depot) -> Option<String> {
+    let mut hasher = FnvHasher::default();
+    if let Some(ip) = req.remote_addr().clone().into_std().map(|addr| addr.ip()) {
+        hasher.write(ip.to_string().as_bytes());
+    }
+    hasher.write_u8(0xff);
+    if let Some(authorization) = req.headers().get(AUTHORIZATION) {
+        hasher.write(authorization.as_bytes());
+    }
+    Some(format!("{:016x}", hasher.finish()))
+}
+
+/// 64-bit FNV-1a hasher, unlike `DefaultHasher` its output is specified and stable.
+pub(crate) struct FnvHasher(u64);
+
+impl Default for FnvHasher {
+    fn default() -> Self {
+        Self(0xcbf2_9ce4_8422_2325)
+    }
+}
+
+impl Hasher for FnvHasher {
+    fn write(&mut self, bytes: &[u8]) {
+        for byte in bytes {
+            self.0 ^= u64::from(*byte);
+            self.0 = self.0.wrapping_mul(0x0100_0000_01b3);
+        }
+    }
+
+    fn finish(&self) -> u64 {
+        self.0
+    }
+}
+
 /// Default url path getter.
 ///
 /// This getter will get the last param as the rest url path from request.
@@ -144,6 +358,26 @@ pub fn default_url_query_getter(req: &Request, _depot: &Depot) -> Option<String>
     req.uri().query().map(Into::into)
 }
 
+/// Policy for handling 1xx informational responses sent by upstream before the final response.
+///
+/// Informational responses are only reported for HTTP/1 upstreams and only by clients built on
+/// hyper, such as the default hyper client. The server side can not send interim responses to the
+/// downstream client, so `103 Early Hints` are relayed by merging their `Link` headers into the
+/// final response, which browsers also honor for preloading.
+///
+/// `100 Continue` never needs relaying: the proxy forwards the client's `Expect` header, the
+/// client connection consumes the upstream's `100 Continue`, and the server answers the downstream
+/// client itself as soon as the request body is read.
+#[derive(Clone, Copy, Debug, Default, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum InformationalPolicy {
+    /// Discard all informational responses.
+    #[default]
+    Discard,
+    /// Merge the `Link` headers of `103 Early Hints` into the final response.
+    MergeEarlyHints,
+}
+
 /// Handler that can proxy request to other server.
 #[non_exhaustive]
 pub struct Proxy<U, C>
@@ -159,6 +393,38 @@ where
     pub url_path_getter: UrlPartGetter,
     /// Url query getter.
     pub url_query_getter: UrlPartGetter,
+    /// Policy for 1xx informational responses.
+    pub informational: InformationalPolicy,
+    /// Upstream response headers mapper.
+    pub response_headers_mapper: Option<ResponseHeadersMapper>,
+    /// Whether `X-Forwarded-For`, `X-Forwarded-Proto` and `X-Forwarded-Host` are added.
+    pub forwarded_headers: bool,
+    /// Whether the standard `Forwarded` header is added.
+    pub standard_forwarded: bool,
+    /// Whether hop-by-hop headers are removed from forwarded requests.
+    pub strip_hop_by_hop_headers: bool,
+    /// Prefix stripped from the original request path before forwarding.
+    pub strip_prefix: Option<String>,
+    /// Max size of forwarded request bodies.
+    pub max_body_size: Option<usize>,
+    /// Timeout for receiving the upstream response headers.
+    pub timeout: Option<Duration>,
+    /// Max number of retries for failed upstream requests.
+    pub retries: usize,
+    /// Delay before the first retry, it is doubled for each following retry.
+    pub retry_backoff: Duration,
+    /// Methods whose requests can be retried.
+    pub retry_methods: Vec<Method>,
+    /// Whether upstream response bodies are buffered in memory before they are written.
+    pub buffer_response: bool,
+    /// Max size of buffered upstream response bodies.
+    pub max_response_size: usize,
+    /// Max size of upstream response bodies saved for idempotency keys.
+    pub max_idempotent_response_size: usize,
+    forward_headers: Vec<(HeaderName, ForwardHeaderGetter)>,
+    on_error: Option<ErrorHandler>,
+    idempotency: Option<Idempotency>,
+    idempotency_scope: IdempotencyScope,
 }
 
 impl<U, C> Proxy<U, C>
@@ -174,10 +440,31 @@ where
             client,
             url_path_getter: Box::new(default_url_path_getter),
             url_query_getter: Box::new(default_url_query_getter),
+            informational: InformationalPolicy::default(),
+            response_headers_mapper: None,
+            forwarded_headers: false,
+            standard_forwarded: false,
+            strip_hop_by_hop_headers: true,
+            strip_prefix: None,
+            max_body_size: None,
+            timeout: None,
+            retries: 0,
+            retry_backoff: Duration::ZERO,
+            retry_methods: DEFAULT_RETRY_METHODS.to_vec(),
+            buffer_response: false,
+            max_response_size: DEFAULT_MAX_RESPONSE_SIZE,
+            max_idempotent_response_size: DEFAULT_MAX_IDEMPOTENT_RESPONSE_SIZE,
+            forward_headers: vec![],
+            on_error: None,
+            idempotency: None,
+            idempotency_scope: Box::new(default_idempotency_scope),
         }
     }
 
     /// Set url path getter.
+    ///
+    /// The returned path is forwarded as is, so it must be percent-encoded, see
+    /// [`encode_url_path`].
     #[inline]
     pub fn url_path_getter<G>(mut self, url_path_getter: G) -> Self
     where
@@ -197,6 +484,233 @@ where
         self
     }
 
+    /// Add a header to forwarded requests.
+    ///
+    /// It is inserted after the client headers are copied, so it overrides a client header with
+    /// the same name. It can be called multiple times to add several headers.
+    #[inline]
+    pub fn add_forward_header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.forward_headers
+            .push((name, Box::new(move |_, _| Some(value.clone()))));
+        self
+    }
+
+    /// Add a header to forwarded requests whose value is computed for each request.
+    ///
+    /// The header is not added if `getter` returns `None`, see
+    /// [`add_forward_header`](Self::add_forward_header).
+    #[inline]
+    pub fn add_forward_header_with<G>(mut self, name: HeaderName, getter: G) -> Self
+    where
+        G: Fn(&Request, &Depot) -> Option<HeaderValue> + Send + Sync + 'static,
+    {
+        self.forward_headers.push((name, Box::new(getter)));
+        self
+    }
+
+    /// Set a function to render the response when proxying fails.
+    ///
+    /// The status code is already set when it is called: `502 Bad Gateway` if the proxied request
+    /// can not be built or the upstream can not be reached, `504 Gateway Timeout` if the
+    /// [`timeout`](Self::timeout) elapses and `400 Bad Request` if the request body can not be
+    /// read. It can change the status code and render a body.
+    #[inline]
+    pub fn on_error<F>(mut self, on_error: F) -> Self
+    where
+        F: Fn(&Error, &mut Response) + Send + Sync + 'static,
+    {
+        self.on_error = Some(Box::new(on_error));
+        self
+    }
+
+    /// Set a function to rewrite the upstream response headers.
+    ///
+    /// It is called with the headers and the original request after the upstream response headers
+    /// are received, before they are copied into the response and the body is relayed. It can be
+    /// used to rewrite `Location` redirects or `Set-Cookie` domains so they do not leak upstream
+    /// host names to clients.
+    #[inline]
+    pub fn map_response_headers<F>(mut self, mapper: F) -> Self
+    where
+        F: Fn(&mut HeaderMap, &Request) + Send + Sync + 'static,
+    {
+        self.response_headers_mapper = Some(Box::new(mapper));
+        self
+    }
+
+    /// Set whether `X-Forwarded-For`, `X-Forwarded-Proto` and `X-Forwarded-Host` are added to
+    /// forwarded requests, default is `false`.
+    ///
+    /// The client ip is appended to an existing `X-Forwarded-For`, the proto and host are taken
+    /// from the scheme and `Host` header of the incoming request.
+    #[inline]
+    pub fn forwarded_headers(mut self, enabled: bool) -> Self {
+        self.forwarded_headers = enabled;
+        self
+    }
+
+    /// Set whether the standard `Forwarded` header ([RFC 7239]) is added to forwarded requests,
+    /// default is `false`.
+    ///
+    /// An element with `for`, `proto` and `host` of the incoming request is appended to an
+    /// existing `Forwarded` header.
+    ///
+    /// [RFC 7239]: https://www.rfc-editor.org/rfc/rfc7239
+    #[inline]
+    pub fn standard_forwarded(mut self, enabled: bool) -> Self {
+        self.standard_forwarded = enabled;
+        self
+    }
+
+    /// Set whether hop-by-hop headers are removed from forwarded requests, default is `true`.
+    ///
+    /// Hop-by-hop headers, such as `Connection`, `Keep-Alive`, `Transfer-Encoding`, `TE`,
+    /// `Trailer`, `Upgrade` and `Proxy-Authorization`, and the headers listed in `Connection` only
+    /// apply to the client connection. `TE: trailers` is kept, and for protocol upgrade requests
+    /// `Connection: upgrade` and `Upgrade` are sent to the upstream again.
+    #[inline]
+    pub fn strip_hop_by_hop_headers(mut self, strip: bool) -> Self {
+        self.strip_hop_by_hop_headers = strip;
+        self
+    }
+
+    /// Forward the original request path with `prefix` stripped.
+    ///
+    /// For example, with prefix `/api` a request to `/api/users/1` is forwarded to `/users/1`. The
+    /// prefix is only stripped at a segment boundary, paths which do not start with it are
+    /// forwarded unchanged. When it is set, the path is taken from the request uri and the
+    /// [`url_path_getter`](Self::url_path_getter) is not used, the query is still taken from the
+    /// [`url_query_getter`](Self::url_query_getter).
+    #[inline]
+    pub fn strip_prefix(mut self, prefix: impl Into<String>) -> Self {
+        let prefix = prefix.into();
+        self.strip_prefix = Some(format!("/{}", prefix.trim_matches('/')));
+        self
+    }
+
+    /// Set policy for 1xx informational responses.
+    #[inline]
+    pub fn informational(mut self, policy: InformationalPolicy) -> Self {
+        self.informational = policy;
+        self
+    }
+
+    /// Set max size of forwarded request bodies.
+    ///
+    /// Requests whose `Content-Length` exceeds it are rejected with `413 Payload Too Large`
+    /// without reaching the upstream. Bodies without `Content-Length` are counted while they are
+    /// streamed, the upstream request is aborted as soon as the limit is exceeded and
+    /// `413 Payload Too Large` is returned, unless the upstream already responded.
+    #[inline]
+    pub fn max_body_size(mut self, size: usize) -> Self {
+        self.max_body_size = Some(size);
+        self
+    }
+
+    /// Set timeout for upstream requests.
+    ///
+    /// If the upstream does not send response headers within `timeout`, `504 Gateway Timeout` is
+    /// returned. The timeout does not cover streaming the response body, which is relayed to the
+    /// client as it arrives.
+    #[inline]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = Some(timeout);
+        self
+    }
+
+    /// Set max number of retries for failed upstream requests.
+    ///
+    /// A request is retried when the client returns an error, the upstream responds with a server
+    /// error status or the [`timeout`](Self::timeout) elapses. An upstream is elected again for
+    /// each retry. Only requests whose method is in [`retry_methods`](Self::retry_methods) are
+    /// retried, the body of these requests is buffered in memory so it can be sent again.
+    /// Protocol upgrade requests are never retried.
+    #[inline]
+    pub fn retries(mut self, retries: usize) -> Self {
+        self.retries = retries;
+        self
+    }
+
+    /// Set delay before the first retry, it is doubled for each following retry.
+    #[inline]
+    pub fn retry_backoff(mut self, backoff: Duration) -> Self {
+        self.retry_backoff = backoff;
+        self
+    }
+
+    /// Set methods whose requests can be retried, it replaces [`DEFAULT_RETRY_METHODS`].
+    #[inline]
+    pub fn retry_methods(mut self, methods: Vec<Method>) -> Self {
+        self.retry_methods = methods;
+        self
+    }
+
+    /// Set whether upstream response bodies are buffered in memory.
+    ///
+    /// By default the upstream body is streamed to the client as it arrives, so a slow client
+    /// keeps the upstream connection busy. When enabled, the whole body is read before the
+    /// response is written, which frees the upstream connection as soon as possible. Protocol
+    /// upgrade responses are never buffered. The memory used by every response is limited by
+    /// [`max_response_size`](Self::max_response_size).
+    #[inline]
+    pub fn buffer_response(mut self, enabled: bool) -> Self {
+        self.buffer_response = enabled;
+        self
+    }
+
+    /// Set max size of buffered upstream response bodies, [`DEFAULT_MAX_RESPONSE_SIZE`] by
+    /// default.
+    ///
+    /// If a buffered upstream body exceeds it, `502 Bad Gateway` is returned instead of a
+    /// truncated body. It has no effect unless [`buffer_response`](Self::buffer_response) is
+    /// enabled.
+    #[inline]
+    pub fn max_response_size(mut self, size: usize) -> Self {
+        self.max_response_size = size;
+        self
+    }
+
+    /// Deduplicate `POST` and `PATCH` requests bearing an [`IDEMPOTENCY_KEY`] header.
+    ///
+    /// The first response for a key is buffered and saved in `store`, requests with the same key
+    /// within `window` are answered with the saved response without reaching the upstream.
+    /// Duplicates arriving while the first request is still in flight wait for its response.
+    /// Keys are scoped to the client, see [`idempotency_scope`](Self::idempotency_scope).
+    ///
+    /// Server error responses are not saved, so clients can retry them. Neither are upgrade
+    /// responses and bodies whose size is unknown or larger than
+    /// [`max_idempotent_response_size`](Self::max_idempotent_response_size), e.g. streams.
+    #[inline]
+    pub fn idempotency(mut self, store: impl IdempotencyStore, window: Duration) -> Self {
+        self.idempotency = Some(Idempotency::new(store, window));
+        self
+    }
+
+    /// Set the scope of idempotency keys, only requests with the same scope share responses.
+    ///
+    /// The default is [`default_idempotency_scope`], which scopes keys to the client address
+    /// and `Authorization` header. Behind another proxy, or with cookie based sessions, scope
+    /// them to the authenticated user instead. Requests are not deduplicated when `scope`
+    /// returns `None`.
+    #[inline]
+    pub fn idempotency_scope<G>(mut self, scope: G) -> Self
+    where
+        G: Fn(&Request, &Depot) -> Option<String> + Send + Sync + 'static,
+    {
+        self.idempotency_scope = Box::new(scope);
+        self
+    }
+
+    /// Set max size of upstream response bodies saved for idempotency keys.
+    ///
+    /// Larger responses are passed through but not saved. The default is
+    /// [`DEFAULT_MAX_IDEMPOTENT_RESPONSE_SIZE`].
+    #[inline]
+    pub fn max_idempotent_response_size(mut self, size: usize) -> Self {
+        self.max_idempotent_response_size = size;
+        self
+    }
+
     /// Get upstreams list.
     #[inline]
     pub fn upstreams(&self) -> &U {
@@ -219,18 +733,90 @@ where
         &mut self.client
     }
 
+    fn add_forwarded_headers(&self, req: &Request, headers: &mut HeaderMap) {
+        if !self.forwarded_headers && !self.standard_forwarded {
+            return;
+        }
+        let client_ip = req.remote_addr().clone().into_std().map(|addr| addr.ip());
+        let proto = req.scheme().as_str();
+        let host = req
+            .headers()
+            .get(HOST)
+            .and_then(|host| host.to_str().ok())
+            .map(ToOwned::to_owned)
+            .or_else(|| req.uri().authority().map(|authority| authority.to_string()));
+        if self.forwarded_headers {
+            if let Some(client_ip) = client_ip {
+                let value = match req
+                    .headers()
+                    .get(X_FORWARDED_FOR)
+                    .and_then(|v| v.to_str().ok())
+                {
+                    Some(forwarded_for) => format!("{forwarded_for}, {client_ip}"),
+                    None => client_ip.to_string(),
+                };
+                if let Ok(value) = HeaderValue::from_str(&value) {
+                    headers.insert(X_FORWARDED_FOR, value);
+                }
+            }
+            if let Ok(value) = HeaderValue::from_str(proto) {
+                headers.insert(X_FORWARDED_PROTO, value);
+            }
+            if let Some(value) = host.as_deref().and_then(|h| HeaderValue::from_str(h).ok()) {
+                headers.insert(X_FORWARDED_HOST, value);
+            }
+        }
+        if self.standard_forwarded {
+            let node = match client_ip {
+                Some(IpAddr::V4(ip)) => ip.to_string(),
+                Some(IpAddr::V6(ip)) => format!("\"[{ip}]\""),
+                None => "unknown".into(),
+            };
+            let mut element = format!("for={node};proto={proto}");
+            if let Some(host) = &host {
+                if host.contains(':') {
+                    element.push_str(&format!(";host=\"{host}\""));
+                } else {
+                    element.push_str(&format!(";host={host}"));
+                }
+            }
+            let value = match req.headers().get(FORWARDED).and_then(|v| v.to_str().ok()) {
+                Some(forwarded) => format!("{forwarded}, {element}"),
+                None => element,
+            };
+            if let Ok(value) = HeaderValue::from_str(&value) {
+                headers.insert(FORWARDED, value);
+            }
+        }
+    }
+
     async fn build_proxied_request(
         &self,
         req: &mut Request,
-        depot: &Depot,
+        depot: &mut Depot,
+        body: ReqBody,
     ) -> Result<HyperRequest, Error> {
-        let upstream = self.upstreams.elect().await.map_err(Error::other)?;
+        let upstream = self
+            .upstreams
+            .elect(req, depot)
+            .await
+            .map_err(Error::other)?;
         if upstream.is_empty() {
             tracing::error!("upstreams is empty");
             return Err(Error::other("upstreams is empty"));
         }
+        depot.insert(PROXY_UPSTREAM_KEY, upstream.to_owned());
 
-        let path = encode_url_path(&(self.url_path_getter)(req, depot).unwrap_or_default());
+        // The request path is already encoded, the getters return encoded paths too.
+        let path = if let Some(prefix) = &self.strip_prefix {
+            let path = req.uri().path();
+            match path.strip_prefix(prefix.as_str()) {
+                Some(rest) if rest.is_empty() || rest.starts_with('/') => rest.to_owned(),
+                _ => path.to_owned(),
+            }
+        } else {
+            (self.url_path_getter)(req, depot).unwrap_or_default()
+        };
         let query = (self.url_query_getter)(req, depot);
         let rest = if let Some(query) = query {
             if query.starts_with('?') {
@@ -241,7 +827,17 @@ where
         } else {
             path
         };
-        let forward_url = if upstream.ends_with('/') && rest.starts_with('/') {
+        let unix_socket = upstream.strip_prefix("unix:").map(|socket| {
+            let socket = socket.strip_prefix("//").unwrap_or(socket);
+            UnixSocketPath(PathBuf::from(socket))
+        });
+        let forward_url = if unix_socket.is_some() {
+            if rest.starts_with('/') {
+                rest
+            } else {
+                format!("/{rest}")
+            }
+        } else if upstream.ends_with('/') && rest.starts_with('/') {
             format!("{}{}", upstream.trim_end_matches('/'), rest)
         } else if upstream.ends_with('/') || rest.starts_with('/') {
             format!("{}{}", upstream, rest)
@@ -254,9 +850,27 @@ where
         let mut build = hyper::Request::builder()
             .method(req.method())
             .uri(&forward_url);
+        let connection_headers = if self.strip_hop_by_hop_headers {
+            connection_header_names(req.headers())
+        } else {
+            vec![]
+        };
         for (key, value) in req.headers() {
-            if key != HOST {
-                build = build.header(key, value);
+            if key == HOST {
+                continue;
+            }
+            if self.strip_hop_by_hop_headers
+                && (is_hop_by_hop_header(key, value) || connection_headers.contains(key))
+            {
+                continue;
+            }
+            build = build.header(key, value);
+        }
+        if self.strip_hop_by_hop_headers {
+            if let Some(upgrade) = get_upgrade_type(req.headers()) {
+                build = build
+                    .header(CONNECTION, HeaderValue::from_static("upgrade"))
+                    .header(UPGRADE, upgrade);
             }
         }
         if let Some(host) = forward_url
@@ -265,25 +879,20 @@ where
         {
             build = build.header(HeaderName::from_static("host"), host);
         }
-        // let x_forwarded_for_header_name = "x-forwarded-for";
-        // // Add forwarding information in the headers
-        // match request.headers_mut().entry(x_forwarded_for_header_name) {
-        //     Ok(header_entry) => {
-        //         match header_entry {
-        //             hyper::header::Entry::Vacant(entry) => {
-        //                 let addr = format!("{}", client_ip);
-        //                 entry.insert(addr.parse().unwrap());
-        //             },
-        //             hyper::header::Entry::Occupied(mut entry) => {
-        //                 let addr = format!("{}, {}", entry.get().to_str().unwrap(), client_ip);
-        //                 entry.insert(addr.parse().unwrap());
-        //             }
-        //         }
-        //     }
-        //     // shouldn't happen...
-        //     Err(_) => panic!("Invalid header name: {}", x_forwarded_for_header_name),
-        // }
-        build.body(req.take_body()).map_err(Error::other)
+        if let Some(unix_socket) = unix_socket {
+            build = build
+                .header(HOST, HeaderValue::from_static("localhost"))
+                .extension(unix_socket);
+        }
+        if let Some(headers) = build.headers_mut() {
+            self.add_forwarded_headers(req, headers);
+            for (name, getter) in &self.forward_headers {
+                if let Some(value) = getter(req, depot) {
+                    headers.insert(name.clone(), value);
+                }
+            }
+        }
+        build.body(body).map_err(Error::other)
     }
 }
 
@@ -301,46 +910,327 @@ where
         res: &mut Response,
         _ctrl: &mut FlowCtrl,
     ) {
-        match self.build_proxied_request(req, depot).await {
-            Ok(proxied_request) => {
-                match self
-                    .client
-                    .execute(proxied_request, req.extensions_mut().remove())
-                    .await
-                {
-                    Ok(response) => {
-                        let (
-                            salvo_core::http::response::Parts {
-                                status,
-                                // version,
-                                headers,
-                                // extensions,
-                                ..
-                            },
-                            body,
-                        ) = response.into_parts();
-                        res.status_code(status);
-                        for (name, value) in headers {
-                            if let Some(name) = name {
-                                res.headers.insert(name, value);
-                            }
-                        }
+        let Some(idempotency) = &self.idempotency else {
+            self.proxy(req, depot, res).await;
+            return;
+        };
+        let key = req
+            .headers()
+            .get(IDEMPOTENCY_KEY)
+            .and_then(|v| v.to_str().ok())
+            .filter(|_| {
+                matches!(*req.method(), Method::POST | Method::PATCH)
+                    && get_upgrade_type(req.headers()).is_none()
+            })
+            .and_then(|key| {
+                let scope = (self.idempotency_scope)(req, depot)?;
+                Some(format!(
+                    "{scope} {} {} {key}",
+                    req.method(),
+                    req.uri().path()
+                ))
+            });
+        let Some(key) = key else {
+            self.proxy(req, depot, res).await;
+            return;
+        };
+        let in_flight = idempotency.acquire(&key);
+        let _guard = in_flight.lock().await;
+        if let Some(cached) = idempotency.store.load(&key).await {
+            res.status_code(cached.status);
+            for name in cached.headers.keys() {
+                res.headers.remove(name);
+            }
+            res.headers.extend(cached.headers);
+            res.body(cached.body);
+        } else {
+            self.proxy(req, depot, res).await;
+            let status = res.status_code.unwrap_or(StatusCode::OK);
+            let body = res.take_body();
+            let savable = !status.is_server_error()
+                && status != StatusCode::SWITCHING_PROTOCOLS
+                && body
+                    .size_hint()
+                    .upper()
+                    .is_some_and(|len| len <= self.max_idempotent_response_size as u64);
+            if !savable {
+                res.body(body);
+            } else {
+                match body.collect().await {
+                    Ok(body) => {
+                        let body = body.to_bytes();
+                        let cached =
+                            IdempotentResponse::new(status, res.headers.clone(), body.clone());
+                        idempotency
+                            .store
+                            .save(&key, cached, idempotency.window)
+                            .await;
                         res.body(body);
                     }
                     Err(e) => {
-                        tracing::error!( error = ?e, uri = ?req.uri(), "get response data failed: {}", e);
-                        res.status_code(StatusCode::INTERNAL_SERVER_ERROR);
+                        tracing::error!(error = ?e, "read upstream response body failed");
+                        res.status_code(StatusCode::BAD_GATEWAY);
                     }
                 }
             }
-            Err(e) => {
-                tracing::error!(error = ?e, "build proxied request failed");
+        }
+    }
+}
+
+impl<U, C> Proxy<U, C>
+where
+    U: Upstreams,
+    U::Error: Into<BoxedError>,
+    C: Client,
+{
+    async fn proxy(&self, req: &mut Request, depot: &mut Depot, res: &mut Response) {
+        let retries = if self.retry_methods.contains(req.method())
+            && get_upgrade_type(req.headers()).is_none()
+        {
+            self.retries
+        } else {
+            0
+        };
+        let body_too_large = if let Some(max_body_size) = self.max_body_size {
+            let content_length = req
+                .headers()
+                .get(CONTENT_LENGTH)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse::<u64>().ok());
+            if content_length.is_some_and(|len| len > max_body_size as u64) {
+                self.fail(
+                    res,
+                    StatusCode::PAYLOAD_TOO_LARGE,
+                    Error::other("request body too large"),
+                );
+                return;
+            }
+            let exceeded = Arc::new(AtomicBool::new(false));
+            let flag = exceeded.clone();
+            let body = Limited::new(req.take_body(), max_body_size).map_err(move |e| {
+                if e.is::<LengthLimitError>() {
+                    flag.store(true, Ordering::Relaxed);
+                }
+                e
+            });
+            req.replace_body(ReqBody::Boxed {
+                inner: Box::pin(body),
+                fusewire: None,
+            });
+            Some(exceeded)
+        } else {
+            None
+        };
+        let is_body_too_large = || {
+            body_too_large
+                .as_ref()
+                .is_some_and(|flag| flag.load(Ordering::Relaxed))
+        };
+        let buffered_body = if retries > 0 {
+            match req.take_body().collect().await {
+                Ok(body) => Some(body.to_bytes()),
+                Err(e) => {
+                    tracing::error!(error = ?e, "read request body failed");
+                    let status_code = if is_body_too_large() {
+                        StatusCode::PAYLOAD_TOO_LARGE
+                    } else {
+                        StatusCode::BAD_REQUEST
+                    };
+                    self.fail(res, status_code, Error::other(e));
+                    return;
+                }
+            }
+        } else {
+            None
+        };
+        let started = Instant::now();
+        let mut attempt = 0;
+        let (result, early_hints) = loop {
+            let body = match &buffered_body {
+                Some(body) => ReqBody::Once(body.clone()),
+                None => req.take_body(),
+            };
+            let mut proxied_request = match self.build_proxied_request(req, depot, body).await {
+                Ok(proxied_request) => proxied_request,
+                Err(e) => {
+                    tracing::error!(error = ?e, "build proxied request failed");
+                    self.fail(res, StatusCode::BAD_GATEWAY, e);
+                    return;
+                }
+            };
+            let early_hints = if self.informational == InformationalPolicy::MergeEarlyHints {
+                let hints = Arc::new(Mutex::new(Vec::new()));
+                let collector = hints.clone();
+                hyper::ext::on_informational(&mut proxied_request, move |info| {
+                    if info.status() == StatusCode::EARLY_HINTS {
+                        if let Ok(mut hints) = collector.lock() {
+                            hints.extend(info.headers().get_all(LINK).iter().cloned());
+                        }
+                    }
+                });
+                Some(hints)
+            } else {
+                None
+            };
+            let upgraded = if attempt == 0 {
+                req.extensions_mut().remove()
+            } else {
+                None
+            };
+            let result = self.execute(req, proxied_request, upgraded).await;
+            let failed = match &result {
+                Ok(response) => response.status().is_server_error(),
+                Err(_) => true,
+            };
+            if !failed || attempt >= retries {
+                depot.insert(PROXY_ELAPSED_KEY, started.elapsed());
+                break (result, early_hints);
+            }
+            attempt += 1;
+            tracing::debug!(uri = ?req.uri(), attempt, "retry upstream request");
+            if !self.retry_backoff.is_zero() {
+                tokio::time::sleep(self.retry_backoff * 2u32.saturating_pow(attempt as u32 - 1))
+                    .await;
+            }
+        };
+        match result {
+            Ok(response) => {
+                let (
+                    salvo_core::http::response::Parts {
+                        status,
+                        // version,
+                        mut headers,
+                        // extensions,
+                        ..
+                    },
+                    body,
+                ) = response.into_parts();
+                let body = if self.buffer_response && status != StatusCode::SWITCHING_PROTOCOLS {
+                    match self.buffer_body(body).await {
+                        Ok(body) => ResBody::Once(body),
+                        Err(e) => {
+                            tracing::error!(error = ?e, "buffer upstream response failed");
+                            self.fail(res, StatusCode::BAD_GATEWAY, e);
+                            return;
+                        }
+                    }
+                } else {
+                    body
+                };
+                if let Some(mapper) = &self.response_headers_mapper {
+                    mapper(&mut headers, req);
+                }
+                res.status_code(status);
+                for (name, value) in headers {
+                    if let Some(name) = name {
+                        res.headers.insert(name, value);
+                    }
+                }
+                if let Some(hints) = early_hints {
+                    let hints = hints.lock().map(|mut hints| std::mem::take(&mut *hints));
+                    for hint in hints.unwrap_or_default() {
+                        if !res.headers.get_all(LINK).iter().any(|link| link == hint) {
+                            res.headers.append(LINK, hint);
+                        }
+                    }
+                }
+                res.body(body);
+            }
+            Err((_, e)) if is_body_too_large() => {
+                self.fail(res, StatusCode::PAYLOAD_TOO_LARGE, e);
+            }
+            Err((status_code, e)) => {
+                self.fail(res, status_code, e);
             }
         }
     }
+
+    async fn buffer_body(&self, body: ResBody) -> Result<Bytes, Error> {
+        if body
+            .size_hint()
+            .lower()
+            .try_into()
+            .is_ok_and(|len: usize| len > self.max_response_size)
+        {
+            return Err(Error::other("upstream response body too large"));
+        }
+        match Limited::new(body, self.max_response_size).collect().await {
+            Ok(body) => Ok(body.to_bytes()),
+            Err(e) if e.is::<LengthLimitError>() => {
+                Err(Error::other("upstream response body too large"))
+            }
+            Err(e) => Err(Error::other(e)),
+        }
+    }
+
+    fn fail(&self, res: &mut Response, status_code: StatusCode, error: Error) {
+        res.status_code(status_code);
+        if let Some(on_error) = &self.on_error {
+            on_error(&error, res);
+        }
+    }
+
+    async fn execute(
+        &self,
+        req: &Request,
+        proxied_request: HyperRequest,
+        upgraded: Option<OnUpgrade>,
+    ) -> Result<HyperResponse, (StatusCode, Error)> {
+        let execute = self.client.execute(proxied_request, upgraded);
+        let result = if let Some(timeout) = self.timeout {
+            match tokio::time::timeout(timeout, execute).await {
+                Ok(result) => result,
+                Err(_) => {
+                    tracing::error!(uri = ?req.uri(), "upstream request timed out");
+                    return Err((
+                        StatusCode::GATEWAY_TIMEOUT,
+                        Error::other("upstream request timed out"),
+                    ));
+                }
+            }
+        } else {
+            execute.await
+        };
+        result.map_err(|e| {
+            tracing::error!( error = ?e, uri = ?req.uri(), "get response data failed: {}", e);
+            (StatusCode::BAD_GATEWAY, Error::other(e))
+        })
+    }
 }
+// Hop-by-hop headers defined in RFC 9110, plus the non-standard `Proxy-Connection`.
+const HOP_BY_HOP_HEADERS: [HeaderName; 9] = [
+    CONNECTION,
+    HeaderName::from_static("keep-alive"),
+    HeaderName::from_static("proxy-connection"),
+    PROXY_AUTHENTICATE,
+    PROXY_AUTHORIZATION,
+    TE,
+    TRAILER,
+    TRANSFER_ENCODING,
+    UPGRADE,
+];
+
+#[inline]
+fn is_hop_by_hop_header(name: &HeaderName, value: &HeaderValue) -> bool {
+    // `TE: trailers` is kept, gRPC upstreams require it to send trailers.
+    if name == TE && value.as_bytes().eq_ignore_ascii_case(b"trailers") {
+        return false;
+    }
+    HOP_BY_HOP_HEADERS.contains(name)
+}
+
+/// Headers listed in `Connection` are specific to the client connection.
+fn connection_header_names(headers: &HeaderMap) -> Vec<HeaderName> {
+    headers
+        .get_all(CONNECTION)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(','))
+        .filter_map(|name| HeaderName::from_bytes(name.trim().as_bytes()).ok())
+        .collect()
+}
+
 #[inline]
-#[allow(dead_code)]
 fn get_upgrade_type(headers: &HeaderMap) -> Option<&str> {
     if headers
         .get(&CONNECTION)
@@ -375,6 +1265,7 @@ mod tests {
         let path = "/test/path";
         let encoded_path = encode_url_path(path);
         assert_eq!(encoded_path, "/test/path");
+        assert_eq!(encode_url_path("/100%off/a b?"), "/100%25off/a%20b%3F");
     }
 
     #[test]
@@ -385,4 +1276,837 @@ mod tests {
         let upgrade_type = get_upgrade_type(&headers);
         assert_eq!(upgrade_type, Some("websocket"));
     }
+
+    #[tokio::test]
+    async fn test_idempotency() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        use salvo_core::prelude::*;
+        use salvo_core::test::{ResponseExt, TestClient};
+
+        struct CountingClient(Arc<AtomicUsize>);
+        impl Client for CountingClient {
+            type Error = Infallible;
+            async fn execute(
+                &self,
+                _req: HyperRequest,
+                _upgraded: Option<OnUpgrade>,
+            ) -> Result<HyperResponse, Self::Error> {
+                let count = self.0.fetch_add(1, Ordering::SeqCst) + 1;
+                tokio::time::sleep(Duration::from_millis(50)).await;
+                Ok(hyper::Response::builder()
+                    .status(StatusCode::CREATED)
+                    .body(ResBody::Once(format!("order {count}").into()))
+                    .unwrap())
+            }
+        }
+
+        let count = Arc::new(AtomicUsize::new(0));
+        let proxy = Proxy::new("http://localhost", CountingClient(count.clone()))
+            .idempotency(MemoryIdempotencyStore::new(), Duration::from_secs(60));
+        let service = Service::new(Router::with_path("{**rest}").goal(proxy));
+
+        let send = |key: &'static str| {
+            TestClient::post("http://127.0.0.1:5801/orders")
+                .add_header(IDEMPOTENCY_KEY, key, true)
+                .send(&service)
+        };
+        let (mut first, mut second) = tokio::join!(send("abc"), send("abc"));
+        assert_eq!(first.status_code, Some(StatusCode::CREATED));
+        assert_eq!(second.status_code, Some(StatusCode::CREATED));
+        assert_eq!(first.take_string().await.unwrap(), "order 1");
+        assert_eq!(second.take_string().await.unwrap(), "order 1");
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+
+        let mut third = send("abc").await;
+        assert_eq!(third.take_string().await.unwrap(), "order 1");
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+
+        let mut other = send("def").await;
+        assert_eq!(other.take_string().await.unwrap(), "order 2");
+        assert_eq!(count.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_idempotency_cancelled() {
+        use salvo_core::test::TestClient;
+
+        struct PendingClient;
+        impl Client for PendingClient {
+            type Error = Infallible;
+            async fn execute(
+                &self,
+                _req: HyperRequest,
+                _upgraded: Option<OnUpgrade>,
+            ) -> Result<HyperResponse, Self::Error> {
+                std::future::pending().await
+            }
+        }
+
+        let proxy = Proxy::new("http://localhost", PendingClient)
+            .idempotency(MemoryIdempotencyStore::new(), Duration::from_secs(60));
+        let mut req = TestClient::post("http://127.0.0.1:5801/orders")
+            .add_header(IDEMPOTENCY_KEY, "abc", true)
+            .build();
+        let mut depot = Depot::new();
+        let mut res = Response::new();
+        let mut ctrl = FlowCtrl::new(vec![]);
+        let handle = proxy.handle(&mut req, &mut depot, &mut res, &mut ctrl);
+        assert!(tokio::time::timeout(Duration::from_millis(50), handle)
+            .await
+            .is_err());
+        let idempotency = proxy.idempotency.as_ref().unwrap();
+        assert_eq!(idempotency.in_flight_len(), 0);
+    }
+
+    #[tokio::test]
+    async fn test_idempotency_scope_and_limits() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        use salvo_core::prelude::*;
+        use salvo_core::test::{RequestBuilder, ResponseExt, TestClient};
+
+        struct CountingClient(Arc<AtomicUsize>);
+        impl Client for CountingClient {
+            type Error = Infallible;
+            async fn execute(
+                &self,
+                _req: HyperRequest,
+                _upgraded: Option<OnUpgrade>,
+            ) -> Result<HyperResponse, Self::Error> {
+                let count = self.0.fetch_add(1, Ordering::SeqCst) + 1;
+                Ok(hyper::Response::builder()
+                    .header("x-order", count.to_string())
+                    .body(ResBody::Once(format!("order {count}").into()))
+                    .unwrap())
+            }
+        }
+        #[handler]
+        async fn request_id(res: &mut Response) {
+            res.headers_mut()
+                .insert("x-request-id", HeaderValue::from_static("fresh"));
+        }
+
+        let count = Arc::new(AtomicUsize::new(0));
+        let service = Service::new(
+            Router::with_path("{**rest}").hoop(request_id).goal(
+                Proxy::new("http://localhost", CountingClient(count.clone()))
+                    .idempotency(MemoryIdempotencyStore::new(), Duration::from_secs(60)),
+            ),
+        );
+        let send = |method: Method, authorization: &'static str| {
+            RequestBuilder::new("http://127.0.0.1:5801/orders", method)
+                .add_header(IDEMPOTENCY_KEY, "abc", true)
+                .add_header(AUTHORIZATION, authorization, true)
+                .send(&service)
+        };
+
+        // Safe methods are not deduplicated.
+        assert_eq!(
+            send(Method::GET, "alice")
+                .await
+                .take_string()
+                .await
+                .unwrap(),
+            "order 1"
+        );
+        assert_eq!(
+            send(Method::GET, "alice")
+                .await
+                .take_string()
+                .await
+                .unwrap(),
+            "order 2"
+        );
+
+        let mut res = send(Method::POST, "alice").await;
+        assert_eq!(res.take_string().await.unwrap(), "order 3");
+        // Keys of other clients do not replay the response.
+        let mut res = send(Method::POST, "mallory").await;
+        assert_eq!(res.take_string().await.unwrap(), "order 4");
+        let mut res = send(Method::POST, "alice").await;
+        assert_eq!(res.headers().get("x-order").unwrap(), "3");
+        assert_eq!(res.headers().get("x-request-id").unwrap(), "fresh");
+        assert_eq!(res.take_string().await.unwrap(), "order 3");
+        assert_eq!(count.load(Ordering::SeqCst), 4);
+
+        let service = Service::new(
+            Router::with_path("{**rest}").goal(
+                Proxy::new("http://localhost", CountingClient(count.clone()))
+                    .idempotency(MemoryIdempotencyStore::new(), Duration::from_secs(60))
+                    .max_idempotent_response_size(4),
+            ),
+        );
+        let send = || {
+            TestClient::post("http://127.0.0.1:5801/orders")
+                .add_header(IDEMPOTENCY_KEY, "abc", true)
+                .send(&service)
+        };
+        // Responses larger than the limit are passed through but not saved.
+        assert_eq!(send().await.take_string().await.unwrap(), "order 5");
+        assert_eq!(send().await.take_string().await.unwrap(), "order 6");
+
+        let service = Service::new(
+            Router::with_path("{**rest}").goal(
+                Proxy::new("http://localhost", CountingClient(count.clone()))
+                    .idempotency(MemoryIdempotencyStore::new(), Duration::from_secs(60))
+                    .idempotency_scope(|_, _| None),
+            ),
+        );
+        let send = || {
+            TestClient::post("http://127.0.0.1:5801/orders")
+                .add_header(IDEMPOTENCY_KEY, "abc", true)
+                .send(&service)
+        };
+        assert_eq!(send().await.take_string().await.unwrap(), "order 7");
+        assert_eq!(send().await.take_string().await.unwrap(), "order 8");
+    }
+
+    #[tokio::test]
+    async fn test_upstream_body_dropped_on_disconnect() {
+        use std::sync::atomic::{AtomicBool, Ordering};
+
+        use futures_util::stream;
+        use salvo_core::http::body::BytesFrame;
+        use salvo_core::prelude::*;
+        use salvo_core::test::TestClient;
+
+        struct DropFlag(Arc<AtomicBool>);
+        impl Drop for DropFlag {
+            fn drop(&mut self) {
+                self.0.store(true, Ordering::SeqCst);
+            }
+        }
+
+        struct EndlessClient(Arc<AtomicBool>);
+        impl Client for EndlessClient {
+            type Error = Infallible;
+            async fn execute(
+                &self,
+                _req: HyperRequest,
+                _upgraded: Option<OnUpgrade>,
+            ) -> Result<HyperResponse, Self::Error> {
+                let flag = DropFlag(self.0.clone());
+                let chunks = stream::repeat_with(move || {
+                    let _ = &flag;
+                    Ok::<_, std::io::Error>(BytesFrame::data("chunk"))
+                });
+                Ok(hyper::Response::builder()
+                    .body(ResBody::stream(chunks))
+                    .unwrap())
+            }
+        }
+
+        let dropped = Arc::new(AtomicBool::new(false));
+        let proxy = Proxy::new("http://localhost", EndlessClient(dropped.clone()));
+        let service = Service::new(Router::with_path("{**rest}").goal(proxy));
+
+        let mut res = TestClient::get("http://127.0.0.1:5801/download")
+            .send(&service)
+            .await;
+        let mut body = res.take_body();
+        let frame = body.frame().await.unwrap().unwrap();
+        assert_eq!(frame.into_data().unwrap(), "chunk");
+        assert!(!dropped.load(Ordering::SeqCst));
+
+        // The client goes away in the middle of the download.
+        drop(body);
+        assert!(dropped.load(Ordering::SeqCst));
+    }
+
+    #[tokio::test]
+    async fn test_timeout() {
+        use salvo_core::prelude::*;
+        use salvo_core::test::TestClient;
+
+        struct SlowClient;
+        impl Client for SlowClient {
+            type Error = Infallible;
+            async fn execute(
+                &self,
+                _req: HyperRequest,
+                _upgraded: Option<OnUpgrade>,
+            ) -> Result<HyperResponse, Self::Error> {
+                tokio::time::sleep(Duration::from_secs(10)).await;
+                Ok(hyper::Response::builder()
+                    .body(ResBody::Once("late".into()))
+                    .unwrap())
+            }
+        }
+
+        let proxy = Proxy::new("http://localhost", SlowClient).timeout(Duration::from_millis(50));
+        let service = Service::new(Router::with_path("{**rest}").goal(proxy));
+        let res = TestClient::get("http://127.0.0.1:5801/slow")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::GATEWAY_TIMEOUT));
+    }
+
+    #[tokio::test]
+    async fn test_retries() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        use salvo_core::prelude::*;
+        use salvo_core::test::{ResponseExt, TestClient};
+
+        struct FlakyClient(Arc<AtomicUsize>);
+        impl Client for FlakyClient {
+            type Error = Infallible;
+            async fn execute(
+                &self,
+                req: HyperRequest,
+                _upgraded: Option<OnUpgrade>,
+            ) -> Result<HyperResponse, Self::Error> {
+                let host = req.uri().host().unwrap_or_default().to_owned();
+                let body = req.into_body().collect().await.unwrap().to_bytes();
+                if self.0.fetch_add(1, Ordering::SeqCst) < 2 {
+                    return Ok(hyper::Response::builder()
+                        .status(StatusCode::SERVICE_UNAVAILABLE)
+                        .body(ResBody::None)
+                        .unwrap());
+                }
+                let body = format!("{host} {}", String::from_utf8_lossy(&body));
+                Ok(hyper::Response::builder()
+                    .body(ResBody::Once(body.into()))
+                    .unwrap())
+            }
+        }
+
+        let count = Arc::new(AtomicUsize::new(0));
+        let proxy = Proxy::new(
+            RoundRobin::new(["http://a", "http://b", "http://c"]),
+            FlakyClient(count.clone()),
+        )
+        .retries(2)
+        .retry_backoff(Duration::from_millis(1));
+        let service = Service::new(Router::with_path("{**rest}").goal(proxy));
+        let mut res = TestClient::put("http://127.0.0.1:5801/items")
+            .body("payload")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        assert_eq!(res.take_string().await.unwrap(), "c payload");
+        assert_eq!(count.load(Ordering::SeqCst), 3);
+
+        count.store(0, Ordering::SeqCst);
+        let res = TestClient::post("http://127.0.0.1:5801/items")
+            .body("payload")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::SERVICE_UNAVAILABLE));
+        assert_eq!(count.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_strip_prefix() {
+        use salvo_core::prelude::*;
+        use salvo_core::test::{ResponseExt, TestClient};
+
+        struct EchoClient;
+        impl Client for EchoClient {
+            type Error = Infallible;
+            async fn execute(
+                &self,
+                req: HyperRequest,
+                _upgraded: Option<OnUpgrade>,
+            ) -> Result<HyperResponse, Self::Error> {
+                Ok(hyper::Response::builder()
+                    .body(ResBody::Once(req.uri().to_string().into()))
+                    .unwrap())
+            }
+        }
+
+        let proxy = Proxy::new("http://upstream", EchoClient).strip_prefix("/api/");
+        let service = Service::new(Router::with_path("{**rest}").goal(proxy));
+        let mut res = TestClient::get("http://127.0.0.1:5801/api/users/1?page=2")
+            .send(&service)
+            .await;
+        assert_eq!(
+            res.take_string().await.unwrap(),
+            "http://upstream/users/1?page=2"
+        );
+        let mut res = TestClient::get("http://127.0.0.1:5801/apis/users")
+            .send(&service)
+            .await;
+        assert_eq!(
+            res.take_string().await.unwrap(),
+            "http://upstream/apis/users"
+        );
+        let mut res = TestClient::get("http://127.0.0.1:5801/api/100%25off/a%20b%3F")
+            .send(&service)
+            .await;
+        assert_eq!(
+            res.take_string().await.unwrap(),
+            "http://upstream/100%25off/a%20b%3F"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_map_response_headers() {
+        use salvo_core::http::header::LOCATION;
+        use salvo_core::prelude::*;
+        use salvo_core::test::TestClient;
+
+        struct RedirectClient;
+        impl Client for RedirectClient {
+            type Error = Infallible;
+            async fn execute(
+                &self,
+                _req: HyperRequest,
+                _upgraded: Option<OnUpgrade>,
+            ) -> Result<HyperResponse, Self::Error> {
+                Ok(hyper::Response::builder()
+                    .status(StatusCode::FOUND)
+                    .header(LOCATION, "http://backend.internal:8080/login")
+                    .body(ResBody::None)
+                    .unwrap())
+            }
+        }
+
+        let proxy = Proxy::new("http://backend.internal:8080", RedirectClient)
+            .map_response_headers(|headers, req| {
+                let Some(location) = headers.get(LOCATION).and_then(|v| v.to_str().ok()) else {
+                    return;
+                };
+                let host = req.uri().host().unwrap_or_default();
+                let location = location.replace("backend.internal:8080", host);
+                headers.insert(LOCATION, HeaderValue::from_str(&location).unwrap());
+            });
+        let service = Service::new(Router::with_path("{**rest}").goal(proxy));
+        let res = TestClient::get("http://gateway.example.com/account")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::FOUND));
+        assert_eq!(
+            res.headers().get(LOCATION).unwrap(),
+            "http://gateway.example.com/login"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_add_forward_header() {
+        use salvo_core::prelude::*;
+        use salvo_core::test::{ResponseExt, TestClient};
+
+        struct HeadersClient;
+        impl Client for HeadersClient {
+            type Error = Infallible;
+            async fn execute(
+                &self,
+                req: HyperRequest,
+                _upgraded: Option<OnUpgrade>,
+            ) -> Result<HyperResponse, Self::Error> {
+                let header = |name: &str| {
+                    req.headers()
+                        .get_all(name)
+                        .iter()
+                        .map(|v| v.to_str().unwrap())
+                        .collect::<Vec<_>>()
+                        .join(",")
+                };
+                let body = format!(
+                    "{} {} {}",
+                    header("x-api-key"),
+                    header("x-user"),
+                    header("x-trace")
+                );
+                Ok(hyper::Response::builder()
+                    .body(ResBody::Once(body.into()))
+                    .unwrap())
+            }
+        }
+
+        #[handler]
+        async fn set_user(depot: &mut Depot) {
+            depot.insert("user", "alice");
+        }
+
+        let proxy = Proxy::new("http://upstream", HeadersClient)
+            .add_forward_header(
+                HeaderName::from_static("x-api-key"),
+                HeaderValue::from_static("secret"),
+            )
+            .add_forward_header_with(HeaderName::from_static("x-user"), |_, depot| {
+                depot
+                    .get::<&str>("user")
+                    .ok()
+                    .and_then(|user| HeaderValue::from_str(user).ok())
+            });
+        let service = Service::new(Router::with_path("{**rest}").hoop(set_user).goal(proxy));
+        let mut res = TestClient::get("http://127.0.0.1:5801/")
+            .add_header("x-api-key", "forged", true)
+            .add_header("x-trace", "abc", true)
+            .send(&service)
+            .await;
+        assert_eq!(res.take_string().await.unwrap(), "secret alice abc");
+    }
+
+    #[tokio::test]
+    async fn test_query_fidelity() {
+        use salvo_core::prelude::*;
+        use salvo_core::test::{ResponseExt, TestClient};
+
+        struct EchoClient;
+        impl Client for EchoClient {
+            type Error = Infallible;
+            async fn execute(
+                &self,
+                req: HyperRequest,
+                _upgraded: Option<OnUpgrade>,
+            ) -> Result<HyperResponse, Self::Error> {
+                Ok(hyper::Response::builder()
+                    .body(ResBody::Once(req.uri().to_string().into()))
+                    .unwrap())
+            }
+        }
+
+        let service = Service::new(
+            Router::with_path("{**rest}").goal(Proxy::new("http://upstream", EchoClient)),
+        );
+        for (url, expected) in [
+            ("/search?flag", "http://upstream/search?flag"),
+            ("/search?a=1&a=2", "http://upstream/search?a=1&a=2"),
+            ("/search?a=&b", "http://upstream/search?a=&b"),
+            ("/search?", "http://upstream/search?"),
+            (
+                "/search?q=a%20b%26c&x=%3F",
+                "http://upstream/search?q=a%20b%26c&x=%3F",
+            ),
+            ("/search?q=a+b", "http://upstream/search?q=a+b"),
+            ("/?flag", "http://upstream/?flag"),
+            ("/a%3Fb?c=d", "http://upstream/a%3Fb?c=d"),
+            ("/a%20b%23c?d", "http://upstream/a%20b%23c?d"),
+            ("/100%25off", "http://upstream/100%25off"),
+            ("/100%25%20off?d", "http://upstream/100%25%20off?d"),
+        ] {
+            let mut res = TestClient::get(format!("http://127.0.0.1:5801{url}"))
+                .send(&service)
+                .await;
+            assert_eq!(res.take_string().await.unwrap(), expected, "{url}");
+        }
+    }
+
+    #[tokio::test]
+    async fn test_strip_hop_by_hop_headers() {
+        use salvo_core::prelude::*;
+        use salvo_core::test::{ResponseExt, TestClient};
+
+        struct HeaderNamesClient;
+        impl Client for HeaderNamesClient {
+            type Error = Infallible;
+            async fn execute(
+                &self,
+                req: HyperRequest,
+                _upgraded: Option<OnUpgrade>,
+            ) -> Result<HyperResponse, Self::Error> {
+                let mut names = req
+                    .headers()
+                    .iter()
+                    .map(|(name, value)| format!("{name}={}", value.to_str().unwrap()))
+                    .collect::<Vec<_>>();
+                names.sort();
+                Ok(hyper::Response::builder()
+                    .body(ResBody::Once(names.join(" ").into()))
+                    .unwrap())
+            }
+        }
+
+        let send = |service: Service| async move {
+            TestClient::get("http://127.0.0.1:5801/")
+                .add_header("connection", "close, x-client-hint", true)
+                .add_header("keep-alive", "timeout=5", true)
+                .add_header("proxy-authorization", "Basic Zm9vOmJhcg==", true)
+                .add_header("te", "trailers", true)
+                .add_header("x-client-hint", "1", true)
+                .add_header("x-request-id", "abc", true)
+                .send(&service)
+                .await
+                .take_string()
+                .await
+                .unwrap()
+        };
+
+        let service = Service::new(
+            Router::with_path("{**rest}").goal(Proxy::new("http://upstream", HeaderNamesClient)),
+        );
+        assert_eq!(
+            send(service).await,
+            "host=upstream te=trailers x-request-id=abc"
+        );
+
+        let service = Service::new(Router::with_path("{**rest}").goal(
+            Proxy::new("http://upstream", HeaderNamesClient).strip_hop_by_hop_headers(false),
+        ));
+        assert_eq!(
+            send(service).await,
+            "connection=close, x-client-hint host=upstream keep-alive=timeout=5 \
+            proxy-authorization=Basic Zm9vOmJhcg== te=trailers x-client-hint=1 x-request-id=abc"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_proxy_depot_ext() {
+        use salvo_core::prelude::*;
+        use salvo_core::test::{ResponseExt, TestClient};
+
+        struct DelayClient;
+        impl Client for DelayClient {
+            type Error = Infallible;
+            async fn execute(
+                &self,
+                _req: HyperRequest,
+                _upgraded: Option<OnUpgrade>,
+            ) -> Result<HyperResponse, Self::Error> {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                Ok(hyper::Response::builder().body(ResBody::None).unwrap())
+            }
+        }
+
+        #[handler]
+        async fn log(
+            req: &mut Request,
+            depot: &mut Depot,
+            res: &mut Response,
+            ctrl: &mut FlowCtrl,
+        ) {
+            ctrl.call_next(req, depot, res).await;
+            let elapsed = depot.proxy_elapsed().unwrap();
+            assert!(elapsed >= Duration::from_millis(20));
+            res.render(depot.proxy_upstream().unwrap().to_owned());
+        }
+
+        let proxy = Proxy::new(RoundRobin::new(["http://a", "http://b"]), DelayClient);
+        let service = Service::new(Router::with_path("{**rest}").hoop(log).goal(proxy));
+        for upstream in ["http://a", "http://b"] {
+            let mut res = TestClient::get("http://127.0.0.1:5801/")
+                .send(&service)
+                .await;
+            assert_eq!(res.take_string().await.unwrap(), upstream);
+        }
+    }
+
+    #[tokio::test]
+    async fn test_on_error() {
+        use salvo_core::prelude::*;
+        use salvo_core::test::{ResponseExt, TestClient};
+
+        struct FailingClient;
+        impl Client for FailingClient {
+            type Error = std::io::Error;
+            async fn execute(
+                &self,
+                _req: HyperRequest,
+                _upgraded: Option<OnUpgrade>,
+            ) -> Result<HyperResponse, Self::Error> {
+                Err(std::io::Error::new(
+                    std::io::ErrorKind::ConnectionRefused,
+                    "connection refused",
+                ))
+            }
+        }
+
+        let service = Service::new(
+            Router::with_path("{**rest}").goal(Proxy::new("http://upstream", FailingClient)),
+        );
+        let res = TestClient::get("http://127.0.0.1:5801/")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::BAD_GATEWAY));
+
+        let service = Service::new(Router::with_path("{**rest}").goal(
+            Proxy::new(Vec::<String>::new(), FailingClient).on_error(|e, res| {
+                res.render(format!("proxy error: {e}"));
+            }),
+        ));
+        let mut res = TestClient::get("http://127.0.0.1:5801/")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::BAD_GATEWAY));
+        assert_eq!(
+            res.take_string().await.unwrap(),
+            "proxy error: upstreams is empty"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_max_body_size() {
+        use std::sync::atomic::AtomicUsize;
+
+        use futures_util::stream;
+        use salvo_core::prelude::*;
+        use salvo_core::test::{ResponseExt, TestClient};
+
+        struct ReadBodyClient(Arc<AtomicUsize>);
+        impl Client for ReadBodyClient {
+            type Error = Error;
+            async fn execute(
+                &self,
+                req: HyperRequest,
+                _upgraded: Option<OnUpgrade>,
+            ) -> Result<HyperResponse, Self::Error> {
+                self.0.fetch_add(1, Ordering::SeqCst);
+                let body = req.into_body().collect().await?.to_bytes();
+                Ok(hyper::Response::builder()
+                    .body(ResBody::Once(body))
+                    .unwrap())
+            }
+        }
+
+        let calls = Arc::new(AtomicUsize::new(0));
+        let proxy = Proxy::new("http://upstream", ReadBodyClient(calls.clone())).max_body_size(8);
+        let service = Service::new(Router::with_path("{**rest}").goal(proxy));
+
+        let mut res = TestClient::post("http://127.0.0.1:5801/")
+            .body("small")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        assert_eq!(res.take_string().await.unwrap(), "small");
+
+        let res = TestClient::post("http://127.0.0.1:5801/")
+            .add_header("content-length", "14", true)
+            .body("much too large")
+            .send(&service)
+            .await;
+        assert_eq!(res.status_code, Some(StatusCode::PAYLOAD_TOO_LARGE));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+
+        let chunks = stream::iter(["much ", "too ", "large"].map(Ok::<_, std::io::Error>));
+        let mut req = TestClient::post("http://127.0.0.1:5801/").build();
+        req.replace_body(ReqBody::Boxed {
+            inner: Box::pin(http_body_util::StreamBody::new(
+                futures_util::StreamExt::map(chunks, |chunk| {
+                    chunk
+                        .map(|chunk| hyper::body::Frame::data(bytes::Bytes::from(chunk)))
+                        .map_err(Into::into)
+                }),
+            )),
+            fusewire: None,
+        });
+        let res = service.handle(req).await;
+        assert_eq!(res.status_code, Some(StatusCode::PAYLOAD_TOO_LARGE));
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_buffer_response() {
+        use futures_util::stream;
+        use salvo_core::http::body::BytesFrame;
+        use salvo_core::prelude::*;
+        use salvo_core::test::{ResponseExt, TestClient};
+
+        struct ChunksClient;
+        impl Client for ChunksClient {
+            type Error = Infallible;
+            async fn execute(
+                &self,
+                _req: HyperRequest,
+                _upgraded: Option<OnUpgrade>,
+            ) -> Result<HyperResponse, Self::Error> {
+                let chunks = stream::iter(
+                    ["chunk1", "chunk2", "chunk3"]
+                        .map(|chunk| Ok::<_, std::io::Error>(BytesFrame::data(chunk))),
+                );
+                Ok(hyper::Response::builder()
+                    .body(ResBody::stream(chunks))
+                    .unwrap())
+            }
+        }
+
+        let send = |proxy: Proxy<&'static str, ChunksClient>| async move {
+            let service = Service::new(Router::with_path("{**rest}").goal(proxy));
+            TestClient::get("http://127.0.0.1:5801/")
+                .send(&service)
+                .await
+        };
+
+        let mut res = send(Proxy::new("http://upstream", ChunksClient)).await;
+        assert!(res.body.is_stream());
+        assert_eq!(res.take_string().await.unwrap(), "chunk1chunk2chunk3");
+
+        let proxy = Proxy::new("http://upstream", ChunksClient).buffer_response(true);
+        assert_eq!(proxy.max_response_size, DEFAULT_MAX_RESPONSE_SIZE);
+        let mut res = send(proxy).await;
+        assert!(matches!(res.body, ResBody::Once(_)));
+        assert_eq!(res.take_string().await.unwrap(), "chunk1chunk2chunk3");
+
+        let mut res = send(
+            Proxy::new("http://upstream", ChunksClient)
+                .buffer_response(true)
+                .max_response_size(18),
+        )
+        .await;
+        assert_eq!(res.status_code, Some(StatusCode::OK));
+        assert_eq!(res.take_string().await.unwrap(), "chunk1chunk2chunk3");
+
+        let res = send(
+            Proxy::new("http://upstream", ChunksClient)
+                .buffer_response(true)
+                .max_response_size(10),
+        )
+        .await;
+        assert_eq!(res.status_code, Some(StatusCode::BAD_GATEWAY));
+    }
+
+    #[tokio::test]
+    async fn test_forwarded_headers() {
+        use salvo_core::prelude::*;
+        use salvo_core::test::{ResponseExt, TestClient};
+
+        struct ForwardedClient;
+        impl Client for ForwardedClient {
+            type Error = Infallible;
+            async fn execute(
+                &self,
+                req: HyperRequest,
+                _upgraded: Option<OnUpgrade>,
+            ) -> Result<HyperResponse, Self::Error> {
+                let body = [
+                    "x-forwarded-for",
+                    "x-forwarded-proto",
+                    "x-forwarded-host",
+                    "forwarded",
+                ]
+                .map(|name| {
+                    req.headers()
+                        .get(name)
+                        .map(|v| v.to_str().unwrap())
+                        .unwrap_or("-")
+                })
+                .join("\n");
+                Ok(hyper::Response::builder()
+                    .body(ResBody::Once(body.into()))
+                    .unwrap())
+            }
+        }
+
+        let proxy = Proxy::new("http://upstream", ForwardedClient)
+            .forwarded_headers(true)
+            .standard_forwarded(true);
+        let service = Service::new(Router::with_path("{**rest}").goal(proxy));
+        let mut req = TestClient::get("http://gateway.example.com:8080/")
+            .add_header("host", "gateway.example.com:8080", true)
+            .add_header("x-forwarded-for", "203.0.113.7", true)
+            .build();
+        *req.remote_addr_mut() = "198.51.100.1:4000"
+            .parse::<std::net::SocketAddr>()
+            .unwrap()
+            .into();
+        let mut res = service.handle(req).await;
+        assert_eq!(
+            res.take_string().await.unwrap(),
+            "203.0.113.7, 198.51.100.1\n\
+            http\n\
+            gateway.example.com:8080\n\
+            for=198.51.100.1;proto=http;host=\"gateway.example.com:8080\""
+        );
+
+        let service = Service::new(
+            Router::with_path("{**rest}").goal(Proxy::new("http://upstream", ForwardedClient)),
+        );
+        let mut res = TestClient::get("http://gateway.example.com/")
+            .send(&service)
+            .await;
+        assert_eq!(res.take_string().await.unwrap(), "-\n-\n-\n-");
+    }
 }
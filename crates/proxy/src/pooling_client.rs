@@ -0,0 +1,503 @@
+use std::collections::HashMap;
+use std::pin::Pin;
+use std::sync::{Arc, Mutex};
+use std::task::{ready, Context, Poll};
+use std::time::{Duration, Instant};
+
+use hyper::body::{Body, Frame, Incoming, SizeHint};
+use hyper::client::conn::{http1, http2};
+use hyper::upgrade::OnUpgrade;
+use hyper::Uri;
+use hyper_util::rt::TokioExecutor;
+use salvo_core::http::header::{HeaderValue, HOST};
+use salvo_core::http::{ReqBody, ResBody};
+use salvo_core::rt::tokio::TokioIo;
+use salvo_core::Error;
+use tokio::net::TcpStream;
+
+use crate::{BoxedError, Client, HyperRequest, HyperResponse, Proxy, Upstreams};
+
+/// A [`Client`] implementation which keeps connections to upstreams alive and reuses them.
+///
+/// Idle connections are pooled per upstream authority (`host:port`), at most
+/// [`max_idle_per_upstream`](PoolingClientBuilder::max_idle_per_upstream) of them are kept and
+/// they are closed once they have been idle for longer than
+/// [`idle_timeout`](PoolingClientBuilder::idle_timeout). With
+/// [`http2_only`](PoolingClientBuilder::http2_only) a single HTTP/2 connection is shared by all the
+/// requests to an upstream.
+///
+/// Only `http` upstreams are supported, use [`HyperClient`](crate::HyperClient) for `https`
+/// upstreams. Upgrade requests, e.g. WebSocket, always use a new connection.
+///
+/// # Example
+///
+/// ```
+/// use std::time::Duration;
+///
+/// use salvo_proxy::{PoolingClient, Proxy};
+///
+/// let client = PoolingClient::builder()
+///     .max_idle_per_upstream(16)
+///     .idle_timeout(Duration::from_secs(30))
+///     .build();
+/// let proxy = Proxy::new("http://127.0.0.1:8080", client);
+/// ```
+#[derive(Clone, Debug)]
+pub struct PoolingClient {
+    pool: Arc<Pool>,
+}
+
+impl Default for PoolingClient {
+    fn default() -> Self {
+        PoolingClientBuilder::new().build()
+    }
+}
+
+/// Builder for [`PoolingClient`] to tune the connection pool and protocol.
+#[derive(Clone, Debug)]
+#[non_exhaustive]
+pub struct PoolingClientBuilder {
+    /// Maximum idle connections kept per upstream, the default is 32.
+    pub max_idle_per_upstream: usize,
+    /// Timeout for idle connections being kept alive, `None` keeps them until the upstream closes
+    /// them. The default is 90 seconds.
+    pub idle_timeout: Option<Duration>,
+    /// Whether upstreams are connected with HTTP/2 prior knowledge instead of HTTP/1.
+    pub http2_only: bool,
+}
+
+impl Default for PoolingClientBuilder {
+    fn default() -> Self {
+        Self {
+            max_idle_per_upstream: 32,
+            idle_timeout: Some(Duration::from_secs(90)),
+            http2_only: false,
+        }
+    }
+}
+
+impl PoolingClientBuilder {
+    /// Create new `PoolingClientBuilder`.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets maximum idle connections kept per upstream.
+    #[inline]
+    pub fn max_idle_per_upstream(mut self, max_idle: usize) -> Self {
+        self.max_idle_per_upstream = max_idle;
+        self
+    }
+
+    /// Sets timeout for idle connections being kept alive.
+    #[inline]
+    pub fn idle_timeout(mut self, timeout: impl Into<Option<Duration>>) -> Self {
+        self.idle_timeout = timeout.into();
+        self
+    }
+
+    /// Sets whether upstreams are connected with HTTP/2 prior knowledge.
+    ///
+    /// All the requests to an upstream are multiplexed over one connection, the upstreams must
+    /// support HTTP/2 without TLS.
+    #[inline]
+    pub fn http2_only(mut self, http2_only: bool) -> Self {
+        self.http2_only = http2_only;
+        self
+    }
+
+    /// Build the [`PoolingClient`].
+    pub fn build(self) -> PoolingClient {
+        PoolingClient {
+            pool: Arc::new(Pool {
+                config: self,
+                idle: Mutex::new(HashMap::new()),
+            }),
+        }
+    }
+}
+
+impl<U> Proxy<U, PoolingClient>
+where
+    U: Upstreams,
+    U::Error: Into<BoxedError>,
+{
+    /// Create new `Proxy` which use default pooling client.
+    pub fn use_pooling_client(upstreams: U) -> Self {
+        Proxy::new(upstreams, PoolingClient::default())
+    }
+}
+
+impl PoolingClient {
+    /// Create a new `PoolingClient` with default settings.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a [`PoolingClientBuilder`] to configure the connection pool and protocol.
+    #[inline]
+    pub fn builder() -> PoolingClientBuilder {
+        PoolingClientBuilder::new()
+    }
+
+    /// Returns the number of idle connections kept for the upstream `authority`, e.g.
+    /// `127.0.0.1:8080`.
+    pub fn idle_count(&self, authority: &str) -> usize {
+        let idle = self.pool.idle.lock().unwrap_or_else(|e| e.into_inner());
+        idle.get(authority).map(Vec::len).unwrap_or_default()
+    }
+}
+
+#[derive(Debug)]
+enum Sender {
+    Http1(http1::SendRequest<ReqBody>),
+    Http2(http2::SendRequest<ReqBody>),
+}
+
+impl Sender {
+    fn is_closed(&self) -> bool {
+        match self {
+            Self::Http1(sender) => sender.is_closed(),
+            Self::Http2(sender) => sender.is_closed(),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Idle {
+    sender: Sender,
+    idle_at: Instant,
+}
+
+#[derive(Debug)]
+struct Pool {
+    config: PoolingClientBuilder,
+    idle: Mutex<HashMap<String, Vec<Idle>>>,
+}
+
+impl Pool {
+    fn is_expired(&self, idle: &Idle, now: Instant) -> bool {
+        idle.sender.is_closed()
+            || self
+                .config
+                .idle_timeout
+                .is_some_and(|timeout| now.duration_since(idle.idle_at) > timeout)
+    }
+
+    /// Takes an idle connection to `authority`, HTTP/2 connections stay in the pool and are shared.
+    fn checkout(&self, authority: &str) -> Option<Sender> {
+        let now = Instant::now();
+        let mut idle = self.idle.lock().unwrap_or_else(|e| e.into_inner());
+        idle.retain(|_, list| {
+            list.retain(|item| !self.is_expired(item, now));
+            !list.is_empty()
+        });
+        let list = idle.get_mut(authority)?;
+        if self.config.http2_only {
+            let item = list.first_mut()?;
+            item.idle_at = now;
+            match &item.sender {
+                Sender::Http2(sender) => Some(Sender::Http2(sender.clone())),
+                Sender::Http1(_) => None,
+            }
+        } else {
+            list.pop().map(|item| item.sender)
+        }
+    }
+
+    fn checkin(&self, authority: String, sender: Sender) {
+        if sender.is_closed() || self.config.max_idle_per_upstream == 0 {
+            return;
+        }
+        let mut idle = self.idle.lock().unwrap_or_else(|e| e.into_inner());
+        let list = idle.entry(authority).or_default();
+        if list.len() < self.config.max_idle_per_upstream {
+            list.push(Idle {
+                sender,
+                idle_at: Instant::now(),
+            });
+        }
+    }
+
+    async fn connect(&self, uri: &Uri, upgrade: bool) -> Result<Sender, Error> {
+        let host = uri.host().ok_or_else(|| Error::other("upstream has no host"))?;
+        let host = host.trim_start_matches('[').trim_end_matches(']');
+        let stream = TcpStream::connect((host, uri.port_u16().unwrap_or(80))).await?;
+        let _ = stream.set_nodelay(true);
+        let io = TokioIo::new(stream);
+        if self.config.http2_only && !upgrade {
+            let (sender, conn) = http2::handshake(TokioExecutor::new(), io)
+                .await
+                .map_err(Error::other)?;
+            tokio::spawn(async move {
+                if let Err(e) = conn.await {
+                    tracing::debug!(error = ?e, "upstream connection closed");
+                }
+            });
+            Ok(Sender::Http2(sender))
+        } else {
+            let (sender, conn) = http1::handshake(io).await.map_err(Error::other)?;
+            tokio::spawn(async move {
+                if let Err(e) = conn.with_upgrades().await {
+                    tracing::debug!(error = ?e, "upstream connection closed");
+                }
+            });
+            Ok(Sender::Http1(sender))
+        }
+    }
+
+    /// Returns a connection ready to send a request, reusing an idle one if possible.
+    async fn ready_sender(&self, authority: &str, uri: &Uri, upgrade: bool) -> Result<Sender, Error> {
+        if !upgrade {
+            while let Some(mut sender) = self.checkout(authority) {
+                let ready = match &mut sender {
+                    Sender::Http1(sender) => sender.ready().await,
+                    Sender::Http2(sender) => sender.ready().await,
+                };
+                if ready.is_ok() {
+                    return Ok(sender);
+                }
+            }
+        }
+        let mut sender = self.connect(uri, upgrade).await?;
+        match &mut sender {
+            Sender::Http1(sender) => sender.ready().await.map_err(Error::other)?,
+            Sender::Http2(sender) => sender.ready().await.map_err(Error::other)?,
+        }
+        if let Sender::Http2(http2) = &sender {
+            self.checkin(authority.to_owned(), Sender::Http2(http2.clone()));
+        }
+        Ok(sender)
+    }
+}
+
+/// Response body which returns the HTTP/1 connection to the pool once it has been read to the end.
+struct PooledBody {
+    body: Incoming,
+    checkin: Option<(Arc<Pool>, String, http1::SendRequest<ReqBody>)>,
+}
+
+impl PooledBody {
+    fn release(&mut self) {
+        if let Some((pool, authority, sender)) = self.checkin.take() {
+            pool.checkin(authority, Sender::Http1(sender));
+        }
+    }
+}
+
+impl Body for PooledBody {
+    type Data = bytes::Bytes;
+    type Error = BoxedError;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let frame = ready!(Pin::new(&mut self.body).poll_frame(cx));
+        match &frame {
+            None => self.release(),
+            Some(Ok(_)) if self.body.is_end_stream() => self.release(),
+            Some(Ok(_)) => {}
+            // The connection is in an unknown state, it is closed when the sender is dropped.
+            Some(Err(_)) => self.checkin = None,
+        }
+        Poll::Ready(frame.map(|frame| frame.map_err(Into::into)))
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.body.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.body.size_hint()
+    }
+}
+
+impl Client for PoolingClient {
+    type Error = salvo_core::Error;
+
+    async fn execute(
+        &self,
+        mut proxied_request: HyperRequest,
+        request_upgraded: Option<OnUpgrade>,
+    ) -> Result<HyperResponse, Self::Error> {
+        let uri = proxied_request.uri().clone();
+        if uri.scheme_str() != Some("http") {
+            return Err(Error::other("pooling client only supports http upstreams"));
+        }
+        let authority = uri
+            .authority()
+            .map(|authority| authority.as_str().to_owned())
+            .ok_or_else(|| Error::other("upstream has no authority"))?;
+        let request_upgrade_type = crate::get_upgrade_type(proxied_request.headers()).map(|s| s.to_owned());
+        let upgrade = request_upgrade_type.is_some();
+
+        let sender = self.pool.ready_sender(&authority, &uri, upgrade).await?;
+        let mut response = match sender {
+            Sender::Http1(mut sender) => {
+                // The origin-form request target has no authority, so it must be in the `Host` header.
+                if !proxied_request.headers().contains_key(HOST) {
+                    let host = HeaderValue::from_str(&authority).map_err(Error::other)?;
+                    proxied_request.headers_mut().insert(HOST, host);
+                }
+                if let Some(path_and_query) = uri.path_and_query() {
+                    *proxied_request.uri_mut() = Uri::from(path_and_query.clone());
+                }
+                let response = sender.send_request(proxied_request).await.map_err(Error::other)?;
+                if upgrade {
+                    response.map(ResBody::Hyper)
+                } else {
+                    let (parts, body) = response.into_parts();
+                    let mut body = PooledBody {
+                        body,
+                        checkin: Some((self.pool.clone(), authority, sender)),
+                    };
+                    if body.is_end_stream() {
+                        body.release();
+                    }
+                    HyperResponse::from_parts(parts, ResBody::Boxed(Box::pin(body)))
+                }
+            }
+            Sender::Http2(mut sender) => sender
+                .send_request(proxied_request)
+                .await
+                .map_err(Error::other)?
+                .map(ResBody::Hyper),
+        };
+
+        crate::upgrade::upgrade_response(request_upgrade_type.as_deref(), request_upgraded, &mut response).await?;
+        Ok(response)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::convert::Infallible;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    use bytes::Bytes;
+    use http_body_util::{BodyExt, Full};
+    use hyper::service::service_fn;
+    use salvo_core::prelude::*;
+    use salvo_core::test::*;
+    use tokio::net::TcpListener;
+
+    use super::*;
+
+    // Starts an upstream echoing the request path, returns its authority and the count of accepted connections.
+    async fn serve(http2: bool) -> (String, Arc<AtomicUsize>) {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let authority = listener.local_addr().unwrap().to_string();
+        let accepted = Arc::new(AtomicUsize::new(0));
+        let counter = accepted.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                counter.fetch_add(1, Ordering::SeqCst);
+                tokio::spawn(async move {
+                    let service = service_fn(|req: hyper::Request<Incoming>| async move {
+                        Ok::<_, Infallible>(hyper::Response::new(Full::new(Bytes::from(req.uri().path().to_owned()))))
+                    });
+                    let io = TokioIo::new(stream);
+                    if http2 {
+                        let builder = hyper::server::conn::http2::Builder::new(TokioExecutor::new());
+                        let _ = builder.serve_connection(io, service).await;
+                    } else {
+                        let _ = hyper::server::conn::http1::Builder::new().serve_connection(io, service).await;
+                    }
+                });
+            }
+        });
+        (authority, accepted)
+    }
+
+    async fn send_three(authority: &str, client: PoolingClient) {
+        let proxy = Proxy::new(format!("http://{authority}"), client);
+        let service = Service::new(Router::with_path("{**rest}").goal(proxy));
+        for path in ["one", "two", "three"] {
+            let content = TestClient::get(format!("http://127.0.0.1:5801/{path}"))
+                .send(&service)
+                .await
+                .take_string()
+                .await
+                .unwrap();
+            assert_eq!(content, format!("/{path}"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_pooling_client_reuses_http1_connection() {
+        let (authority, accepted) = serve(false).await;
+        let client = PoolingClient::new();
+        send_three(&authority, client.clone()).await;
+        assert_eq!(accepted.load(Ordering::SeqCst), 1);
+        assert_eq!(client.idle_count(&authority), 1);
+
+        let (authority, accepted) = serve(false).await;
+        send_three(&authority, PoolingClient::builder().max_idle_per_upstream(0).build()).await;
+        assert_eq!(accepted.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_pooling_client_reuses_http2_connection() {
+        let (authority, accepted) = serve(true).await;
+        let client = PoolingClient::builder().http2_only(true).build();
+        send_three(&authority, client.clone()).await;
+        assert_eq!(accepted.load(Ordering::SeqCst), 1);
+        assert_eq!(client.idle_count(&authority), 1);
+    }
+
+    #[tokio::test]
+    async fn test_pooling_client_idle_timeout() {
+        let (authority, accepted) = serve(false).await;
+        let client = PoolingClient::builder()
+            .idle_timeout(Duration::from_millis(50))
+            .build();
+        send_three(&authority, client.clone()).await;
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        send_three(&authority, client).await;
+        assert_eq!(accepted.load(Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_pooling_client_sets_host() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let authority = listener.local_addr().unwrap().to_string();
+        tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            let service = service_fn(|req: hyper::Request<Incoming>| async move {
+                let response = match req.headers().get(HOST) {
+                    Some(host) => hyper::Response::new(Full::new(Bytes::copy_from_slice(host.as_bytes()))),
+                    None => hyper::Response::builder()
+                        .status(StatusCode::BAD_REQUEST)
+                        .body(Full::default())
+                        .unwrap(),
+                };
+                Ok::<_, Infallible>(response)
+            });
+            let _ = hyper::server::conn::http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), service)
+                .await;
+        });
+
+        let req = hyper::Request::builder()
+            .uri(format!("http://{authority}/"))
+            .body(ReqBody::None)
+            .unwrap();
+        let res = PoolingClient::new().execute(req, None).await.unwrap();
+        assert_eq!(res.status(), StatusCode::OK);
+        let host = res.into_body().collect().await.unwrap().to_bytes();
+        assert_eq!(host, authority.as_bytes());
+    }
+
+    #[tokio::test]
+    async fn test_pooling_client_rejects_https() {
+        let client = PoolingClient::new();
+        let req = hyper::Request::builder()
+            .uri("https://www.example.com/")
+            .body(ReqBody::None)
+            .unwrap();
+        assert!(client.execute(req, None).await.is_err());
+    }
+}
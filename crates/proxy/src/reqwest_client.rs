@@ -2,9 +2,7 @@ use futures_util::TryStreamExt;
 use hyper::upgrade::OnUpgrade;
 use reqwest::Client as InnerClient;
 use salvo_core::http::{ResBody, StatusCode};
-use salvo_core::rt::tokio::TokioIo;
 use salvo_core::Error;
-use tokio::io::copy_bidirectional;
 
 use crate::{Client, HyperRequest, BoxedError, Proxy, Upstreams, HyperResponse};
 
@@ -59,25 +57,12 @@ impl Client for ReqwestClient {
             let response_upgrade_type = crate::get_upgrade_type(response.headers());
 
             if request_upgrade_type == response_upgrade_type.map(|s| s.to_lowercase()) {
-                let mut response_upgraded = response
+                let response_upgraded = response
                     .upgrade()
                     .await
                     .map_err(|e| Error::other(format!("response does not have an upgrade extension. {}", e)))?;
                 if let Some(request_upgraded) = request_upgraded {
-                    tokio::spawn(async move {
-                        match request_upgraded.await {
-                            Ok(request_upgraded) => {
-                                let mut request_upgraded = TokioIo::new(request_upgraded);
-                                if let Err(e) = copy_bidirectional(&mut response_upgraded, &mut request_upgraded).await
-                                {
-                                    tracing::error!(error = ?e, "coping between upgraded connections failed");
-                                }
-                            }
-                            Err(e) => {
-                                tracing::error!(error = ?e, "upgrade request failed");
-                            }
-                        }
-                    });
+                    crate::upgrade::bridge_upgraded(request_upgraded, response_upgraded);
                 } else {
                     return Err(Error::other("request does not have an upgrade extension"));
                 }
@@ -108,7 +93,7 @@ mod tests {
     async fn test_upstreams_elect() {
         let upstreams = vec!["https://www.example.com", "https://www.example2.com"];
         let proxy = Proxy::new(upstreams.clone(), ReqwestClient::default());
-        let elected_upstream = proxy.upstreams().elect().await.unwrap();
+        let elected_upstream = proxy.upstreams().elect(&Request::new(), &Depot::new()).await.unwrap();
         assert!(upstreams.contains(&elected_upstream));
     }
 
@@ -0,0 +1,106 @@
+use hyper::upgrade::OnUpgrade;
+use salvo_core::http::ResBody;
+use salvo_core::rt::tokio::TokioIo;
+use salvo_core::Error;
+use tokio::net::UnixStream;
+
+use crate::{BoxedError, Client, HyperRequest, HyperResponse, Proxy, UnixSocketPath, Upstreams};
+
+/// A [`Client`] implementation which connects to upstreams over unix sockets.
+///
+/// Upstreams have the form `unix:/path/to/app.sock` (or `unix:///path/to/app.sock`), the request
+/// path and query are appended as usual, e.g. `/users?page=2` is sent to the server listening on
+/// `/path/to/app.sock`. A new connection is opened for each request.
+#[derive(Clone, Debug, Default)]
+#[non_exhaustive]
+pub struct UnixSockClient;
+
+impl<U> Proxy<U, UnixSockClient>
+where
+    U: Upstreams,
+    U::Error: Into<BoxedError>,
+{
+    /// Create new `Proxy` which use unix socket client.
+    pub fn use_unix_sock_client(upstreams: U) -> Self {
+        Proxy::new(upstreams, UnixSockClient)
+    }
+}
+
+impl UnixSockClient {
+    /// Create a new `UnixSockClient`.
+    pub fn new() -> Self {
+        Self
+    }
+}
+
+impl Client for UnixSockClient {
+    type Error = salvo_core::Error;
+
+    async fn execute(
+        &self,
+        proxied_request: HyperRequest,
+        request_upgraded: Option<OnUpgrade>,
+    ) -> Result<HyperResponse, Self::Error> {
+        let Some(UnixSocketPath(socket)) = proxied_request.extensions().get::<UnixSocketPath>().cloned() else {
+            return Err(Error::other("upstream is not a unix socket"));
+        };
+        let request_upgrade_type = crate::get_upgrade_type(proxied_request.headers()).map(|s| s.to_owned());
+
+        let stream = UnixStream::connect(&socket).await?;
+        let (mut sender, conn) = hyper::client::conn::http1::handshake(TokioIo::new(stream))
+            .await
+            .map_err(Error::other)?;
+        tokio::spawn(async move {
+            if let Err(e) = conn.with_upgrades().await {
+                tracing::error!(error = ?e, "unix socket connection failed");
+            }
+        });
+        let mut response = sender.send_request(proxied_request).await.map_err(Error::other)?;
+
+        crate::upgrade::upgrade_response(request_upgrade_type.as_deref(), request_upgraded, &mut response).await?;
+        Ok(response.map(ResBody::Hyper))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use salvo_core::prelude::*;
+    use salvo_core::test::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::UnixListener;
+
+    use super::*;
+
+    #[tokio::test]
+    async fn test_unix_sock_client() {
+        let dir = std::env::temp_dir().join(format!("salvo-proxy-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let socket = dir.join("app.sock");
+        let _ = std::fs::remove_file(&socket);
+        let listener = UnixListener::bind(&socket).unwrap();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+            let mut buf = [0; 1024];
+            let n = stream.read(&mut buf).await.unwrap();
+            let head = String::from_utf8_lossy(&buf[..n]).to_string();
+            let request_line = head.lines().next().unwrap_or_default().to_owned();
+            let response = format!(
+                "HTTP/1.1 200 OK\r\ncontent-length: {}\r\n\r\n{}",
+                request_line.len(),
+                request_line
+            );
+            stream.write_all(response.as_bytes()).await.unwrap();
+        });
+
+        let upstream = format!("unix:{}", socket.display());
+        let router = Router::with_path("api/{**rest}").goal(Proxy::use_unix_sock_client(upstream));
+        let content = TestClient::get("http://127.0.0.1:5801/api/users?page=2")
+            .send(router)
+            .await
+            .take_string()
+            .await
+            .unwrap();
+        assert_eq!(content, "GET /users?page=2 HTTP/1.1");
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
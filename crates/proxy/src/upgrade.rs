@@ -0,0 +1,52 @@
+use hyper::upgrade::OnUpgrade;
+use salvo_core::rt::tokio::TokioIo;
+use tokio::io::{copy_bidirectional, AsyncRead, AsyncWrite};
+
+/// Bridges the upgraded client connection with the upgraded upstream connection in a new task,
+/// until either side closes.
+pub(crate) fn bridge_upgraded<T>(request_upgraded: OnUpgrade, mut response_upgraded: T)
+where
+    T: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    tokio::spawn(async move {
+        match request_upgraded.await {
+            Ok(request_upgraded) => {
+                let mut request_upgraded = TokioIo::new(request_upgraded);
+                if let Err(e) = copy_bidirectional(&mut response_upgraded, &mut request_upgraded).await {
+                    tracing::error!(error = ?e, "copying between upgraded connections failed");
+                }
+            }
+            Err(e) => {
+                tracing::error!(error = ?e, "upgrade request failed");
+            }
+        }
+    });
+}
+
+/// Bridges the connections of a `101 Switching Protocols` response got by a hyper based client.
+///
+/// It does nothing for other responses, and fails if the upgrade types of the request and the
+/// response differ.
+#[cfg(any(feature = "hyper-client", all(unix, feature = "unix-sock-client"), feature = "pooling-client"))]
+pub(crate) async fn upgrade_response<B>(
+    request_upgrade_type: Option<&str>,
+    request_upgraded: Option<OnUpgrade>,
+    response: &mut hyper::Response<B>,
+) -> Result<(), salvo_core::Error> {
+    if response.status() != salvo_core::http::StatusCode::SWITCHING_PROTOCOLS {
+        return Ok(());
+    }
+    let matched = match (request_upgrade_type, crate::get_upgrade_type(response.headers())) {
+        (Some(request_type), Some(response_type)) => request_type.eq_ignore_ascii_case(response_type),
+        (request_type, response_type) => request_type == response_type,
+    };
+    if !matched {
+        return Err(salvo_core::Error::other("upgrade type mismatch"));
+    }
+    let Some(request_upgraded) = request_upgraded else {
+        return Err(salvo_core::Error::other("request does not have an upgrade extension"));
+    };
+    let response_upgraded = hyper::upgrade::on(response).await?;
+    bridge_upgraded(request_upgraded, TokioIo::new(response_upgraded));
+    Ok(())
+}
@@ -0,0 +1,736 @@
+use std::hash::Hasher;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex, RwLock, Weak};
+use std::time::Duration;
+
+use salvo_core::http::header::{HeaderName, COOKIE, HOST};
+use salvo_core::http::ReqBody;
+use salvo_core::{Depot, Error, Request};
+use tokio::task::JoinHandle;
+
+use crate::{Client, FnvHasher, Upstreams};
+
+/// Upstreams which are elected in turn.
+///
+/// Unlike the random election of `Vec<T>`, every upstream gets the same share of requests even
+/// under low request counts.
+#[derive(Debug)]
+pub struct RoundRobin<T> {
+    upstreams: Vec<T>,
+    cursor: AtomicUsize,
+}
+
+impl<T> RoundRobin<T>
+where
+    T: AsRef<str> + Send + Sync + 'static,
+{
+    /// Create new `RoundRobin` upstreams.
+    #[inline]
+    pub fn new(upstreams: impl IntoIterator<Item = T>) -> Self {
+        Self {
+            upstreams: upstreams.into_iter().collect(),
+            cursor: AtomicUsize::new(0),
+        }
+    }
+
+    /// Get upstreams list.
+    #[inline]
+    pub fn upstreams(&self) -> &[T] {
+        &self.upstreams
+    }
+}
+
+impl<T> Upstreams for RoundRobin<T>
+where
+    T: AsRef<str> + Send + Sync + 'static,
+{
+    type Error = Error;
+    async fn elect(&self, _req: &Request, _depot: &Depot) -> Result<&str, Self::Error> {
+        if self.upstreams.is_empty() {
+            return Err(Error::other("upstreams is empty"));
+        }
+        let index = self.cursor.fetch_add(1, Ordering::Relaxed) % self.upstreams.len();
+        Ok(self.upstreams[index].as_ref())
+    }
+}
+
+/// Upstreams which are elected in proportion to their weights.
+///
+/// It uses smooth weighted round-robin, so the election is deterministic and upstreams with heavy
+/// weights are interleaved with others instead of being elected in bursts. Upstreams with zero
+/// weight are never elected.
+#[derive(Debug)]
+pub struct Weighted {
+    upstreams: Vec<(String, u32)>,
+    current: Mutex<Vec<i64>>,
+}
+
+impl Weighted {
+    /// Create new `Weighted` upstreams from `(upstream, weight)` pairs.
+    pub fn new<S>(upstreams: impl IntoIterator<Item = (S, u32)>) -> Self
+    where
+        S: Into<String>,
+    {
+        let upstreams: Vec<(String, u32)> = upstreams
+            .into_iter()
+            .map(|(upstream, weight)| (upstream.into(), weight))
+            .collect();
+        let current = Mutex::new(vec![0; upstreams.len()]);
+        Self { upstreams, current }
+    }
+
+    /// Get upstreams list with their weights.
+    #[inline]
+    pub fn upstreams(&self) -> &[(String, u32)] {
+        &self.upstreams
+    }
+}
+
+impl Upstreams for Weighted {
+    type Error = Error;
+    async fn elect(&self, _req: &Request, _depot: &Depot) -> Result<&str, Self::Error> {
+        let total: i64 = self.upstreams.iter().map(|(_, w)| i64::from(*w)).sum();
+        if total == 0 {
+            return Err(Error::other("upstreams is empty"));
+        }
+        let mut current = self
+            .current
+            .lock()
+            .map_err(|_| Error::other("weighted upstreams lock poisoned"))?;
+        let mut best = 0;
+        for (index, (_, weight)) in self.upstreams.iter().enumerate() {
+            current[index] += i64::from(*weight);
+            if current[index] > current[best] {
+                best = index;
+            }
+        }
+        current[best] -= total;
+        Ok(&self.upstreams[best].0)
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+struct HealthState {
+    healthy: bool,
+    failures: usize,
+    successes: usize,
+}
+
+#[derive(Clone, Debug)]
+struct HealthCheckConfig {
+    path: String,
+    interval: Duration,
+    timeout: Duration,
+    unhealthy_threshold: usize,
+    healthy_threshold: usize,
+}
+
+/// Upstreams which are probed periodically, unhealthy upstreams are removed from rotation.
+///
+/// Each upstream is probed with a `GET` request to its health path, a probe succeeds when the
+/// upstream responds with a success status within the timeout. An upstream is ejected after
+/// `unhealthy_threshold` consecutive failed probes and comes back after `healthy_threshold`
+/// consecutive successful probes. All upstreams are healthy initially, healthy upstreams are
+/// elected in turn.
+///
+/// Probes run in a background task started by [`spawn_check`](Self::spawn_check), the task stops
+/// when the upstreams are dropped.
+#[derive(Debug)]
+pub struct HealthCheckedUpstreams {
+    upstreams: Arc<Vec<String>>,
+    states: Arc<RwLock<Vec<HealthState>>>,
+    cursor: AtomicUsize,
+    config: HealthCheckConfig,
+}
+
+impl HealthCheckedUpstreams {
+    /// Create new `HealthCheckedUpstreams`.
+    pub fn new<S>(upstreams: impl IntoIterator<Item = S>) -> Self
+    where
+        S: Into<String>,
+    {
+        let upstreams: Vec<String> = upstreams.into_iter().map(Into::into).collect();
+        let states = vec![
+            HealthState {
+                healthy: true,
+                failures: 0,
+                successes: 0,
+            };
+            upstreams.len()
+        ];
+        Self {
+            upstreams: Arc::new(upstreams),
+            states: Arc::new(RwLock::new(states)),
+            cursor: AtomicUsize::new(0),
+            config: HealthCheckConfig {
+                path: "/health".into(),
+                interval: Duration::from_secs(10),
+                timeout: Duration::from_secs(5),
+                unhealthy_threshold: 3,
+                healthy_threshold: 2,
+            },
+        }
+    }
+
+    /// Sets the path which is probed, default is `/health`.
+    #[inline]
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.config.path = path.into();
+        self
+    }
+
+    /// Sets the interval between probes, default is 10 seconds.
+    #[inline]
+    pub fn interval(mut self, interval: Duration) -> Self {
+        self.config.interval = interval;
+        self
+    }
+
+    /// Sets the timeout of each probe, default is 5 seconds.
+    #[inline]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.config.timeout = timeout;
+        self
+    }
+
+    /// Sets the number of consecutive failed probes to eject an upstream, default is 3.
+    #[inline]
+    pub fn unhealthy_threshold(mut self, threshold: usize) -> Self {
+        self.config.unhealthy_threshold = threshold.max(1);
+        self
+    }
+
+    /// Sets the number of consecutive successful probes to restore an upstream, default is 2.
+    #[inline]
+    pub fn healthy_threshold(mut self, threshold: usize) -> Self {
+        self.config.healthy_threshold = threshold.max(1);
+        self
+    }
+
+    /// Get upstreams list.
+    #[inline]
+    pub fn upstreams(&self) -> &[String] {
+        &self.upstreams
+    }
+
+    /// Returns whether the upstream is healthy.
+    pub fn is_healthy(&self, upstream: &str) -> bool {
+        let Ok(states) = self.states.read() else {
+            return false;
+        };
+        self.upstreams
+            .iter()
+            .zip(states.iter())
+            .any(|(u, state)| u == upstream && state.healthy)
+    }
+
+    /// Probes all upstreams once with `client`.
+    pub async fn check<C: Client>(&self, client: &C) {
+        check_upstreams(&self.upstreams, &self.states, &self.config, client).await;
+    }
+
+    /// Spawns a background task which probes all upstreams every interval with `client`.
+    pub fn spawn_check<C: Client>(&self, client: C) -> JoinHandle<()> {
+        let upstreams = self.upstreams.clone();
+        let states: Weak<RwLock<Vec<HealthState>>> = Arc::downgrade(&self.states);
+        let config = self.config.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(config.interval);
+            loop {
+                interval.tick().await;
+                let Some(states) = states.upgrade() else {
+                    break;
+                };
+                check_upstreams(&upstreams, &states, &config, &client).await;
+            }
+        })
+    }
+}
+
+async fn check_upstreams<C: Client>(
+    upstreams: &[String],
+    states: &RwLock<Vec<HealthState>>,
+    config: &HealthCheckConfig,
+    client: &C,
+) {
+    let probes = upstreams
+        .iter()
+        .map(|upstream| probe(upstream, config, client));
+    let results = futures_util::future::join_all(probes).await;
+    let Ok(mut states) = states.write() else {
+        return;
+    };
+    for ((upstream, state), ok) in upstreams.iter().zip(states.iter_mut()).zip(results) {
+        if ok {
+            state.failures = 0;
+            state.successes += 1;
+            if !state.healthy && state.successes >= config.healthy_threshold {
+                tracing::info!(upstream, "upstream is healthy again");
+                state.healthy = true;
+            }
+        } else {
+            state.successes = 0;
+            state.failures += 1;
+            if state.healthy && state.failures >= config.unhealthy_threshold {
+                tracing::warn!(upstream, "upstream is unhealthy, ejected from rotation");
+                state.healthy = false;
+            }
+        }
+    }
+}
+
+async fn probe<C: Client>(upstream: &str, config: &HealthCheckConfig, client: &C) -> bool {
+    let url = format!(
+        "{}/{}",
+        upstream.trim_end_matches('/'),
+        config.path.trim_start_matches('/')
+    );
+    let request = match hyper::Request::get(url).body(ReqBody::None) {
+        Ok(request) => request,
+        Err(e) => {
+            tracing::error!(error = ?e, upstream, "build health check request failed");
+            return false;
+        }
+    };
+    match tokio::time::timeout(config.timeout, client.execute(request, None)).await {
+        Ok(Ok(response)) => response.status().is_success(),
+        Ok(Err(e)) => {
+            tracing::debug!(error = ?e, upstream, "health check failed");
+            false
+        }
+        Err(_) => {
+            tracing::debug!(upstream, "health check timed out");
+            false
+        }
+    }
+}
+
+impl Upstreams for HealthCheckedUpstreams {
+    type Error = Error;
+    async fn elect(&self, _req: &Request, _depot: &Depot) -> Result<&str, Self::Error> {
+        let healthy: Vec<usize> = {
+            let states = self
+                .states
+                .read()
+                .map_err(|_| Error::other("upstreams health lock poisoned"))?;
+            states
+                .iter()
+                .enumerate()
+                .filter(|(_, state)| state.healthy)
+                .map(|(index, _)| index)
+                .collect()
+        };
+        if healthy.is_empty() {
+            return Err(Error::other("no healthy upstreams"));
+        }
+        let index = healthy[self.cursor.fetch_add(1, Ordering::Relaxed) % healthy.len()];
+        Ok(&self.upstreams[index])
+    }
+}
+
+#[derive(Debug)]
+enum StickyKey {
+    Cookie(String),
+    Header(HeaderName),
+}
+
+/// Upstreams which send requests of the same client to the same upstream.
+///
+/// The value of a configured cookie or header is hashed to elect an upstream, so requests with
+/// the same value always reach the same upstream as long as the upstreams list does not change.
+/// Sources are checked in the order they are added, a random upstream is elected for requests
+/// without any of them.
+///
+/// # Example
+///
+/// ```
+/// use salvo_proxy::StickyUpstreams;
+///
+/// let upstreams = StickyUpstreams::new(["http://app1.internal", "http://app2.internal"])
+///     .cookie("session_id")
+///     .header("x-user-id");
+/// ```
+#[derive(Debug)]
+pub struct StickyUpstreams<T> {
+    upstreams: Vec<T>,
+    keys: Vec<StickyKey>,
+}
+
+impl<T> StickyUpstreams<T>
+where
+    T: AsRef<str> + Send + Sync + 'static,
+{
+    /// Create new `StickyUpstreams`, requests are elected randomly until a source is added.
+    #[inline]
+    pub fn new(upstreams: impl IntoIterator<Item = T>) -> Self {
+        Self {
+            upstreams: upstreams.into_iter().collect(),
+            keys: vec![],
+        }
+    }
+
+    /// Elect upstreams by the value of the cookie named `name`.
+    #[inline]
+    pub fn cookie(mut self, name: impl Into<String>) -> Self {
+        self.keys.push(StickyKey::Cookie(name.into()));
+        self
+    }
+
+    /// Elect upstreams by the value of the header `name`.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `name` is not a valid header name.
+    #[inline]
+    pub fn header<N>(mut self, name: N) -> Self
+    where
+        N: TryInto<HeaderName>,
+        N::Error: std::fmt::Debug,
+    {
+        self.keys.push(StickyKey::Header(
+            name.try_into().expect("invalid header name"),
+        ));
+        self
+    }
+
+    /// Get upstreams list.
+    #[inline]
+    pub fn upstreams(&self) -> &[T] {
+        &self.upstreams
+    }
+
+    fn sticky_value<'a>(&self, req: &'a Request) -> Option<&'a str> {
+        self.keys.iter().find_map(|key| match key {
+            StickyKey::Cookie(name) => request_cookie(req, name),
+            StickyKey::Header(name) => req
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .filter(|value| !value.is_empty()),
+        })
+    }
+}
+
+fn request_cookie<'a>(req: &'a Request, name: &str) -> Option<&'a str> {
+    req.headers()
+        .get_all(COOKIE)
+        .iter()
+        .filter_map(|value| value.to_str().ok())
+        .flat_map(|value| value.split(';'))
+        .find_map(|pair| {
+            let (key, value) = pair.trim().split_once('=')?;
+            (key == name && !value.is_empty()).then_some(value)
+        })
+}
+
+impl<T> Upstreams for StickyUpstreams<T>
+where
+    T: AsRef<str> + Send + Sync + 'static,
+{
+    type Error = Error;
+    async fn elect(&self, req: &Request, _depot: &Depot) -> Result<&str, Self::Error> {
+        if self.upstreams.is_empty() {
+            return Err(Error::other("upstreams is empty"));
+        }
+        let index = match self.sticky_value(req) {
+            Some(value) => {
+                // FNV-1a is fully specified, unlike `DefaultHasher` whose algorithm may change
+                // between Rust releases, so the election is stable across restarts and builds.
+                let mut hasher = FnvHasher::default();
+                hasher.write(value.as_bytes());
+                (hasher.finish() % self.upstreams.len() as u64) as usize
+            }
+            None => fastrand::usize(..self.upstreams.len()),
+        };
+        Ok(self.upstreams[index].as_ref())
+    }
+}
+
+#[derive(Debug)]
+enum Rule {
+    PathPrefix(String),
+    Host(String),
+}
+
+/// Upstreams which are elected by matching the request path or host against rules.
+///
+/// Rules are checked in the order they are added, the upstream of the first matching rule is
+/// elected. The default upstream is elected if no rule matches, an error is returned if there is
+/// no default upstream.
+///
+/// # Example
+///
+/// ```
+/// use salvo_proxy::RuleUpstreams;
+///
+/// let upstreams = RuleUpstreams::new()
+///     .when_path_prefix("/users", "http://users.internal")
+///     .when_host("admin.example.com", "http://admin.internal")
+///     .default_upstream("http://web.internal");
+/// ```
+#[derive(Debug, Default)]
+pub struct RuleUpstreams {
+    rules: Vec<(Rule, String)>,
+    default: Option<String>,
+}
+
+impl RuleUpstreams {
+    /// Create new empty `RuleUpstreams`.
+    #[inline]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Elect `upstream` for requests whose path starts with `prefix`.
+    ///
+    /// The prefix only matches at a segment boundary, `/users` matches `/users` and `/users/1`
+    /// but not `/usersettings`.
+    #[inline]
+    pub fn when_path_prefix(
+        mut self,
+        prefix: impl Into<String>,
+        upstream: impl Into<String>,
+    ) -> Self {
+        let prefix = prefix.into();
+        let prefix = format!("/{}", prefix.trim_matches('/'));
+        self.rules.push((Rule::PathPrefix(prefix), upstream.into()));
+        self
+    }
+
+    /// Elect `upstream` for requests whose host is `host`, the port is ignored.
+    #[inline]
+    pub fn when_host(mut self, host: impl Into<String>, upstream: impl Into<String>) -> Self {
+        self.rules.push((
+            Rule::Host(host.into().to_ascii_lowercase()),
+            upstream.into(),
+        ));
+        self
+    }
+
+    /// Elect `upstream` for requests which do not match any rule.
+    #[inline]
+    pub fn default_upstream(mut self, upstream: impl Into<String>) -> Self {
+        self.default = Some(upstream.into());
+        self
+    }
+}
+
+fn request_host(req: &Request) -> Option<&str> {
+    let host = req
+        .headers()
+        .get(HOST)
+        .and_then(|host| host.to_str().ok())
+        .or_else(|| req.uri().host())?;
+    // Strip the port, ipv6 addresses are enclosed in brackets.
+    match host.rsplit_once(':') {
+        Some((host, port)) if !port.contains(']') => Some(host),
+        _ => Some(host),
+    }
+}
+
+impl Upstreams for RuleUpstreams {
+    type Error = Error;
+    async fn elect(&self, req: &Request, _depot: &Depot) -> Result<&str, Self::Error> {
+        let path = req.uri().path();
+        let host = request_host(req);
+        let matched = self.rules.iter().find(|(rule, _)| match rule {
+            Rule::PathPrefix(prefix) => {
+                prefix == "/"
+                    || path
+                        .strip_prefix(prefix.as_str())
+                        .is_some_and(|rest| rest.is_empty() || rest.starts_with('/'))
+            }
+            Rule::Host(expected) => host.is_some_and(|host| host.eq_ignore_ascii_case(expected)),
+        });
+        matched
+            .map(|(_, upstream)| upstream.as_str())
+            .or(self.default.as_deref())
+            .ok_or_else(|| Error::other("no upstream matches the request"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_round_robin() {
+        let (req, depot) = (Request::new(), Depot::new());
+        let upstreams = RoundRobin::new(["http://a", "http://b", "http://c"]);
+        let mut elected = vec![];
+        for _ in 0..6 {
+            elected.push(upstreams.elect(&req, &depot).await.unwrap());
+        }
+        assert_eq!(
+            elected,
+            ["http://a", "http://b", "http://c", "http://a", "http://b", "http://c"]
+        );
+        assert!(RoundRobin::<String>::new(vec![])
+            .elect(&req, &depot)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_weighted() {
+        let (req, depot) = (Request::new(), Depot::new());
+        let upstreams = Weighted::new([("http://a", 5), ("http://b", 1), ("http://c", 1)]);
+        let mut elected = vec![];
+        for _ in 0..7 {
+            elected.push(upstreams.elect(&req, &depot).await.unwrap());
+        }
+        assert_eq!(
+            elected,
+            ["http://a", "http://a", "http://b", "http://a", "http://c", "http://a", "http://a"]
+        );
+
+        let upstreams = Weighted::new([("http://a", 0), ("http://b", 2)]);
+        for _ in 0..4 {
+            assert_eq!(upstreams.elect(&req, &depot).await.unwrap(), "http://b");
+        }
+        assert!(Weighted::new([("http://a", 0)])
+            .elect(&req, &depot)
+            .await
+            .is_err());
+    }
+
+    #[tokio::test]
+    async fn test_health_checked_upstreams() {
+        let (req, depot) = (Request::new(), Depot::new());
+        use std::convert::Infallible;
+        use std::sync::atomic::AtomicBool;
+
+        use hyper::upgrade::OnUpgrade;
+        use salvo_core::http::{ResBody, StatusCode};
+
+        use crate::{HyperRequest, HyperResponse};
+
+        struct HealthClient(Arc<AtomicBool>);
+        impl Client for HealthClient {
+            type Error = Infallible;
+            async fn execute(
+                &self,
+                req: HyperRequest,
+                _upgraded: Option<OnUpgrade>,
+            ) -> Result<HyperResponse, Self::Error> {
+                assert_eq!(req.uri().path(), "/ping");
+                let status = if req.uri().host() == Some("b") && !self.0.load(Ordering::SeqCst) {
+                    StatusCode::SERVICE_UNAVAILABLE
+                } else {
+                    StatusCode::OK
+                };
+                Ok(hyper::Response::builder()
+                    .status(status)
+                    .body(ResBody::None)
+                    .unwrap())
+            }
+        }
+
+        let b_up = Arc::new(AtomicBool::new(false));
+        let client = HealthClient(b_up.clone());
+        let upstreams = HealthCheckedUpstreams::new(["http://a", "http://b"])
+            .path("/ping")
+            .unhealthy_threshold(2)
+            .healthy_threshold(1);
+
+        upstreams.check(&client).await;
+        assert!(upstreams.is_healthy("http://b"));
+        upstreams.check(&client).await;
+        assert!(!upstreams.is_healthy("http://b"));
+        for _ in 0..4 {
+            assert_eq!(upstreams.elect(&req, &depot).await.unwrap(), "http://a");
+        }
+
+        b_up.store(true, Ordering::SeqCst);
+        upstreams.check(&client).await;
+        assert!(upstreams.is_healthy("http://b"));
+        let mut elected = vec![
+            upstreams.elect(&req, &depot).await.unwrap(),
+            upstreams.elect(&req, &depot).await.unwrap(),
+        ];
+        elected.sort();
+        assert_eq!(elected, ["http://a", "http://b"]);
+    }
+
+    #[tokio::test]
+    async fn test_rule_upstreams() {
+        use salvo_core::test::TestClient;
+
+        let upstreams = RuleUpstreams::new()
+            .when_path_prefix("/users/", "http://users")
+            .when_host("admin.example.com", "http://admin")
+            .default_upstream("http://web");
+        let depot = Depot::new();
+        for (url, expected) in [
+            ("http://example.com/users", "http://users"),
+            ("http://example.com/users/1?page=2", "http://users"),
+            ("http://example.com/usersettings", "http://web"),
+            ("http://admin.example.com:8080/", "http://admin"),
+            ("http://ADMIN.example.com/users", "http://users"),
+            ("http://example.com/", "http://web"),
+        ] {
+            let req = TestClient::get(url).build();
+            assert_eq!(
+                upstreams.elect(&req, &depot).await.unwrap(),
+                expected,
+                "{url}"
+            );
+        }
+
+        let req = TestClient::get("http://example.com/").build();
+        let upstreams = RuleUpstreams::new().when_path_prefix("/users", "http://users");
+        assert!(upstreams.elect(&req, &depot).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_sticky_upstreams() {
+        use salvo_core::test::TestClient;
+
+        let upstreams = StickyUpstreams::new(["http://a", "http://b", "http://c", "http://d"])
+            .cookie("session_id")
+            .header("x-user-id");
+        let depot = Depot::new();
+        let elect = |cookie: Option<String>, user: Option<&'static str>| {
+            let mut client = TestClient::get("http://example.com/");
+            if let Some(cookie) = cookie {
+                client = client.add_header("cookie", cookie, true);
+            }
+            if let Some(user) = user {
+                client = client.add_header("x-user-id", user, true);
+            }
+            let req = client.build();
+            let upstreams = &upstreams;
+            let depot = &depot;
+            async move { upstreams.elect(&req, depot).await.unwrap().to_owned() }
+        };
+
+        let mut elected = std::collections::HashSet::new();
+        for session in 0..32 {
+            let cookie = format!("theme=dark; session_id=s{session}");
+            let first = elect(Some(cookie.clone()), None).await;
+            for _ in 0..4 {
+                assert_eq!(elect(Some(cookie.clone()), None).await, first);
+            }
+            // The cookie is checked before the header.
+            assert_eq!(elect(Some(cookie), Some("user")).await, first);
+            elected.insert(first);
+        }
+        // Different clients are spread over the upstreams.
+        assert!(elected.len() > 1);
+
+        let by_header = elect(None, Some("user")).await;
+        for _ in 0..4 {
+            assert_eq!(
+                elect(Some("theme=dark".into()), Some("user")).await,
+                by_header
+            );
+        }
+        // The election only depends on the value, so it is the same across restarts.
+        assert_eq!(by_header, "http://c");
+
+        assert!(StickyUpstreams::<String>::new(vec![])
+            .elect(&TestClient::get("http://example.com/").build(), &depot)
+            .await
+            .is_err());
+    }
+}
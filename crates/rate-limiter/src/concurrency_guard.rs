@@ -0,0 +1,133 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+use std::sync::{Arc, Mutex};
+
+use salvo_core::handler::{none_skipper, Skipper};
+use salvo_core::http::{Request, Response, StatusCode, StatusError};
+use salvo_core::{async_trait, Depot, FlowCtrl, Handler};
+
+use crate::RateIssuer;
+
+/// `ConcurrencyGuard` limits the number of in-flight requests for every key.
+///
+/// Unlike [`RateLimiter`](crate::RateLimiter) which limits how many requests are accepted in a
+/// time window, `ConcurrencyGuard` tracks requests which are currently being handled. A permit is
+/// taken when the request enters and is released when the rest of the handlers are done, so both
+/// limiters can be combined on the same router.
+///
+/// Requests exceeding the cap are rejected with `429 Too Many Requests` by default, use
+/// [`ConcurrencyGuard::status_code`] to change it, for example to `503 Service Unavailable`.
+pub struct ConcurrencyGuard<I: RateIssuer> {
+    issuer: I,
+    max_concurrency: usize,
+    status_code: StatusCode,
+    active: Arc<Mutex<HashMap<I::Key, usize>>>,
+    skipper: Box<dyn Skipper>,
+}
+
+impl<I: RateIssuer> ConcurrencyGuard<I> {
+    /// Create a new `ConcurrencyGuard` which allows at most `max_concurrency` in-flight requests
+    /// for every key issued by `issuer`.
+    #[inline]
+    pub fn new(issuer: I, max_concurrency: usize) -> Self {
+        Self {
+            issuer,
+            max_concurrency,
+            status_code: StatusCode::TOO_MANY_REQUESTS,
+            active: Arc::new(Mutex::new(HashMap::new())),
+            skipper: Box::new(none_skipper),
+        }
+    }
+
+    /// Sets skipper and returns new `ConcurrencyGuard`.
+    #[inline]
+    pub fn with_skipper(mut self, skipper: impl Skipper) -> Self {
+        self.skipper = Box::new(skipper);
+        self
+    }
+
+    /// Sets the status code used to reject requests exceeding the cap and returns new `ConcurrencyGuard`.
+    #[inline]
+    pub fn status_code(mut self, status_code: StatusCode) -> Self {
+        self.status_code = status_code;
+        self
+    }
+
+    /// Returns the number of in-flight requests for the key.
+    pub fn active(&self, key: &I::Key) -> usize {
+        self.active
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .get(key)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
+impl<I> ConcurrencyGuard<I>
+where
+    I: RateIssuer,
+    I::Key: Clone,
+{
+    fn acquire(&self, key: I::Key) -> Option<Permit<I::Key>> {
+        let mut active = self.active.lock().unwrap_or_else(|e| e.into_inner());
+        if active.get(&key).copied().unwrap_or_default() >= self.max_concurrency {
+            return None;
+        }
+        *active.entry(key.clone()).or_default() += 1;
+        Some(Permit {
+            key,
+            active: self.active.clone(),
+        })
+    }
+}
+
+/// Releases the taken slot when dropped, even if the rest handlers panic.
+struct Permit<K: Hash + Eq> {
+    key: K,
+    active: Arc<Mutex<HashMap<K, usize>>>,
+}
+impl<K: Hash + Eq> Drop for Permit<K> {
+    fn drop(&mut self) {
+        let mut active = self.active.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(count) = active.get_mut(&self.key) {
+            *count = count.saturating_sub(1);
+            if *count == 0 {
+                active.remove(&self.key);
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<I> Handler for ConcurrencyGuard<I>
+where
+    I: RateIssuer,
+    I::Key: Clone,
+{
+    async fn handle(
+        &self,
+        req: &mut Request,
+        depot: &mut Depot,
+        res: &mut Response,
+        ctrl: &mut FlowCtrl,
+    ) {
+        if self.skipper.skipped(req, depot) {
+            return;
+        }
+        let key = match self.issuer.issue(req, depot).await {
+            Some(key) => key,
+            None => {
+                res.render(StatusError::bad_request().brief("Invalid identifier."));
+                ctrl.skip_rest();
+                return;
+            }
+        };
+        let Some(_permit) = self.acquire(key) else {
+            res.status_code(self.status_code);
+            ctrl.skip_rest();
+            return;
+        };
+        ctrl.call_next(req, depot, res).await;
+    }
+}
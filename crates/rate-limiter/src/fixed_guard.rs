@@ -1,14 +1,20 @@
 use serde::{Deserialize, Serialize};
-use time::OffsetDateTime;
+use time::{Duration, OffsetDateTime};
 
 use super::{BasicQuota, RateGuard};
 
 /// Fixed window implement.
+///
+/// By default a window starts at the first request and lasts for the period of the quota. If
+/// [`aligned`](FixedGuard::aligned) is set, windows are aligned to wall-clock boundaries
+/// instead, e.g. a quota per minute resets at the top of every minute.
 #[derive(Deserialize, Serialize, Clone, Debug)]
 pub struct FixedGuard {
     reset: OffsetDateTime,
     count: usize,
     quota: Option<BasicQuota>,
+    #[serde(default)]
+    aligned: bool,
 }
 
 impl Default for FixedGuard {
@@ -24,8 +30,28 @@ impl FixedGuard {
             reset: OffsetDateTime::now_utc(),
             count: 0,
             quota: None,
+            aligned: false,
         }
     }
+
+    /// Sets whether windows are aligned to wall-clock boundaries.
+    ///
+    /// Aligned windows are multiples of the quota period since the Unix epoch, so a quota per
+    /// hour resets at the top of every hour, no matter when the first request arrived.
+    #[inline]
+    pub fn aligned(mut self, aligned: bool) -> Self {
+        self.aligned = aligned;
+        self
+    }
+
+    fn window_end(&self, now: OffsetDateTime, period: Duration) -> OffsetDateTime {
+        let period_nanos = period.whole_nanoseconds();
+        if !self.aligned || period_nanos <= 0 {
+            return now + period;
+        }
+        let elapsed = Duration::nanoseconds_i128(now.unix_timestamp_nanos().rem_euclid(period_nanos));
+        now - elapsed + period
+    }
 }
 
 impl RateGuard for FixedGuard {
@@ -39,7 +65,7 @@ impl RateGuard for FixedGuard {
                 }
                 self.quota = Some(quota);
             }
-            self.reset = OffsetDateTime::now_utc() + quota.period;
+            self.reset = self.window_end(OffsetDateTime::now_utc(), quota.period);
             self.count = 1;
             true
         } else if self.count < quota.limit {
@@ -62,3 +88,49 @@ impl RateGuard for FixedGuard {
         quota.limit
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_aligned_window_end() {
+        // 2024-05-01 10:15:00 UTC
+        let minute = OffsetDateTime::from_unix_timestamp(1_714_558_500).unwrap();
+        let next_minute = minute + Duration::minutes(1);
+        let next_hour = minute + Duration::minutes(45);
+
+        let guard = FixedGuard::new().aligned(true);
+        for offset in [
+            Duration::ZERO,
+            Duration::milliseconds(1),
+            Duration::seconds(42),
+            Duration::milliseconds(59_999),
+        ] {
+            let now = minute + offset;
+            assert_eq!(guard.window_end(now, Duration::minutes(1)), next_minute);
+            assert_eq!(guard.window_end(now, Duration::hours(1)), next_hour);
+        }
+
+        let now = minute + Duration::seconds(42);
+        assert_eq!(
+            FixedGuard::new().window_end(now, Duration::minutes(1)),
+            now + Duration::minutes(1)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_aligned_reset() {
+        let mut guard = FixedGuard::new().aligned(true);
+        let quota = BasicQuota::per_minute(2);
+        let now = OffsetDateTime::now_utc().unix_timestamp();
+        assert!(guard.verify(&quota).await);
+        let reset = guard.reset(&quota).await;
+        assert_eq!(reset % 60, 0);
+        assert!(reset > now && reset <= now + 60);
+
+        assert!(guard.verify(&quota).await);
+        assert_eq!(guard.reset(&quota).await, reset);
+        assert!(!guard.verify(&quota).await);
+    }
+}
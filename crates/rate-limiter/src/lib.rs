@@ -8,7 +8,12 @@
 //!
 //! [`QuotaGetter`] is used to get quota for every key.
 //!
-//! [`RateGuard`] is strategy to verify is the request exceeded quota.
+//! [`RateGuard`] is strategy to verify is the request exceeded quota. [`FixedGuard`] and
+//! [`SlidingGuard`] count requests in windows, [`TokenBucketGuard`] allows short bursts and then
+//! a steady throughput.
+//!
+//! [`ConcurrencyGuard`] limits the number of in-flight requests for every key, it can be combined
+//! with [`RateLimiter`] to protect backends from both request bursts and slow requests.
 //!
 //! Read more: <https://salvo.rs>
 #![doc(html_favicon_url = "https://salvo.rs/favicon-32x32.png")]
@@ -26,7 +31,7 @@ use salvo_core::http::{HeaderValue, Request, Response, StatusCode, StatusError};
 use salvo_core::{async_trait, Depot, FlowCtrl, Handler};
 
 mod quota;
-pub use quota::{BasicQuota, CelledQuota, QuotaGetter};
+pub use quota::{BasicQuota, BucketQuota, CelledQuota, QuotaGetter};
 #[macro_use]
 mod cfg;
 
@@ -51,6 +56,38 @@ cfg_feature! {
     pub use sliding_guard::SlidingGuard;
 }
 
+cfg_feature! {
+    #![feature = "token-bucket-guard"]
+
+    mod token_bucket_guard;
+    pub use token_bucket_guard::TokenBucketGuard;
+}
+
+cfg_feature! {
+    #![feature = "concurrency-guard"]
+
+    mod concurrency_guard;
+    pub use concurrency_guard::ConcurrencyGuard;
+}
+
+/// Key used to record in depot that the request exceeded quota while the limiter is in dry-run mode.
+pub const DRY_RUN_THROTTLED_KEY: &str = "::salvo::rate_limiter::dry_run_throttled";
+
+/// Extension for Depot.
+pub trait RateLimiterDepotExt {
+    /// Returns `true` if the request would have been throttled by a dry-run [`RateLimiter`].
+    fn dry_run_throttled(&self) -> bool;
+}
+
+impl RateLimiterDepotExt for Depot {
+    #[inline]
+    fn dry_run_throttled(&self) -> bool {
+        self.get::<bool>(DRY_RUN_THROTTLED_KEY)
+            .copied()
+            .unwrap_or_default()
+    }
+}
+
 /// Issuer is used to identify every request.
 pub trait RateIssuer: Send + Sync + 'static {
     /// The key is used to identify the rate limit.
@@ -135,6 +172,8 @@ pub struct RateLimiter<G, S, I, Q> {
     issuer: I,
     quota_getter: Q,
     add_headers: bool,
+    soft_limit_ratio: Option<f32>,
+    dry_run: bool,
     skipper: Box<dyn Skipper>,
 }
 
@@ -148,6 +187,8 @@ impl<G: RateGuard, S: RateStore, I: RateIssuer, P: QuotaGetter<I::Key>> RateLimi
             issuer,
             quota_getter,
             add_headers: false,
+            soft_limit_ratio: None,
+            dry_run: false,
             skipper: Box::new(none_skipper),
         }
     }
@@ -166,6 +207,37 @@ impl<G: RateGuard, S: RateStore, I: RateIssuer, P: QuotaGetter<I::Key>> RateLimi
         self.add_headers = add_headers;
         self
     }
+
+    /// Sets `soft_limit_ratio` and returns new `RateLimiter`.
+    ///
+    /// When the remaining quota drops below `ratio` of the limit, the request is still allowed
+    /// but a `X-RateLimit-Warning: approaching-limit` header is added to the response, so clients
+    /// can back off before being throttled.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `ratio` is not in `(0, 1]`.
+    #[inline]
+    pub fn soft_limit_ratio(mut self, ratio: f32) -> Self {
+        assert!(
+            ratio > 0.0 && ratio <= 1.0,
+            "soft_limit_ratio must be in (0, 1], got {ratio}"
+        );
+        self.soft_limit_ratio = Some(ratio);
+        self
+    }
+
+    /// Sets `dry_run` and returns new `RateLimiter`.
+    ///
+    /// In dry-run mode quota is verified and headers are added as usual, but requests exceeding
+    /// quota are not throttled. They are logged and marked in depot instead, see
+    /// [`RateLimiterDepotExt::dry_run_throttled`]. It is useful to observe a new limit before
+    /// enforcing it.
+    #[inline]
+    pub fn dry_run(mut self, dry_run: bool) -> Self {
+        self.dry_run = dry_run;
+        self
+    }
 }
 
 #[async_trait]
@@ -231,9 +303,24 @@ where
                     .expect("Invalid header value"),
             );
         }
+        if let (true, Some(ratio)) = (verified, self.soft_limit_ratio) {
+            let remaining = guard.remaining(&quota).await as f64;
+            let limit = guard.limit(&quota).await as f64;
+            if remaining < limit * ratio as f64 {
+                res.headers_mut().insert(
+                    "X-RateLimit-Warning",
+                    HeaderValue::from_static("approaching-limit"),
+                );
+            }
+        }
         if !verified {
-            res.status_code(StatusCode::TOO_MANY_REQUESTS);
-            ctrl.skip_rest();
+            if self.dry_run {
+                tracing::info!("RateLimiter dry run: request exceeded quota");
+                depot.insert(DRY_RUN_THROTTLED_KEY, true);
+            } else {
+                res.status_code(StatusCode::TOO_MANY_REQUESTS);
+                ctrl.skip_rest();
+            }
         }
         if let Err(e) = self.store.save_guard(key, guard).await {
             tracing::error!(error = ?e, "RateLimiter save guard failed");
@@ -424,4 +511,180 @@ mod tests {
         assert_eq!(respone.status_code, Some(StatusCode::OK));
         assert_eq!(respone.take_string().await.unwrap(), "Limited page");
     }
+
+    #[tokio::test]
+    async fn test_token_bucket_guard() {
+        let limiter = RateLimiter::new(
+            TokenBucketGuard::default(),
+            MokaStore::default(),
+            UserIssuer,
+            BucketQuota::new(3, 1, time::Duration::milliseconds(300)),
+        )
+        .add_headers(true);
+        let router = Router::new().push(Router::with_path("limited").hoop(limiter).get(limited));
+        let service = Service::new(router);
+
+        async fn status(service: &Service) -> (StatusCode, String) {
+            let response = TestClient::get("http://127.0.0.1:5800/limited?user=user1")
+                .send(service)
+                .await;
+            let remaining = response
+                .headers()
+                .get("X-RateLimit-Remaining")
+                .map(|v| v.to_str().unwrap().to_owned())
+                .unwrap_or_default();
+            (response.status_code.unwrap(), remaining)
+        }
+
+        // A burst up to the capacity is allowed.
+        for remaining in ["2", "1", "0"] {
+            assert_eq!(
+                status(&service).await,
+                (StatusCode::OK, remaining.to_owned())
+            );
+        }
+        assert_eq!(status(&service).await.0, StatusCode::TOO_MANY_REQUESTS);
+
+        // Then tokens are refilled one by one.
+        tokio::time::sleep(tokio::time::Duration::from_millis(350)).await;
+        assert_eq!(status(&service).await, (StatusCode::OK, "0".to_owned()));
+        assert_eq!(status(&service).await.0, StatusCode::TOO_MANY_REQUESTS);
+
+        tokio::time::sleep(tokio::time::Duration::from_millis(650)).await;
+        assert_eq!(status(&service).await.0, StatusCode::OK);
+        assert_eq!(status(&service).await.0, StatusCode::OK);
+        assert_eq!(status(&service).await.0, StatusCode::TOO_MANY_REQUESTS);
+    }
+
+    #[tokio::test]
+    async fn test_soft_limit_warning() {
+        let limiter = RateLimiter::new(
+            FixedGuard::default(),
+            MokaStore::default(),
+            UserIssuer,
+            BasicQuota::per_minute(4),
+        )
+        .soft_limit_ratio(0.5);
+        let router = Router::new().push(Router::with_path("limited").hoop(limiter).get(limited));
+        let service = Service::new(router);
+
+        let mut warnings = Vec::new();
+        for _ in 0..4 {
+            let mut response = TestClient::get("http://127.0.0.1:5800/limited?user=user1")
+                .send(&service)
+                .await;
+            assert_eq!(response.status_code, Some(StatusCode::OK));
+            assert_eq!(response.take_string().await.unwrap(), "Limited page");
+            warnings.push(
+                response
+                    .headers()
+                    .get("X-RateLimit-Warning")
+                    .map(|v| v.to_str().unwrap().to_owned()),
+            );
+        }
+        assert_eq!(
+            warnings,
+            [
+                None,
+                None,
+                Some("approaching-limit".to_owned()),
+                Some("approaching-limit".to_owned())
+            ]
+        );
+
+        let response = TestClient::get("http://127.0.0.1:5800/limited?user=user1")
+            .send(&service)
+            .await;
+        assert_eq!(response.status_code, Some(StatusCode::TOO_MANY_REQUESTS));
+        assert!(response.headers().get("X-RateLimit-Warning").is_none());
+    }
+
+    #[test]
+    #[should_panic(expected = "soft_limit_ratio must be in (0, 1]")]
+    fn test_soft_limit_ratio_out_of_range() {
+        let _ = RateLimiter::new(
+            FixedGuard::default(),
+            MokaStore::<String, FixedGuard>::default(),
+            UserIssuer,
+            BasicQuota::per_minute(4),
+        )
+        .soft_limit_ratio(f32::NAN);
+    }
+
+    #[tokio::test]
+    async fn test_dry_run() {
+        #[handler]
+        async fn observed(depot: &mut Depot) -> String {
+            format!("throttled: {}", depot.dry_run_throttled())
+        }
+
+        let limiter = RateLimiter::new(
+            FixedGuard::default(),
+            MokaStore::default(),
+            UserIssuer,
+            BasicQuota::per_minute(1),
+        )
+        .add_headers(true)
+        .dry_run(true);
+        let router = Router::new().push(Router::with_path("limited").hoop(limiter).get(observed));
+        let service = Service::new(router);
+
+        let mut response = TestClient::get("http://127.0.0.1:5800/limited?user=user1")
+            .send(&service)
+            .await;
+        assert_eq!(response.status_code, Some(StatusCode::OK));
+        assert_eq!(response.take_string().await.unwrap(), "throttled: false");
+
+        let mut response = TestClient::get("http://127.0.0.1:5800/limited?user=user1")
+            .send(&service)
+            .await;
+        assert_eq!(response.status_code, Some(StatusCode::OK));
+        assert_eq!(response.take_string().await.unwrap(), "throttled: true");
+        assert_eq!(
+            response.headers().get("X-RateLimit-Remaining").unwrap(),
+            "0"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_concurrency_guard() {
+        #[handler]
+        async fn slow() -> &'static str {
+            tokio::time::sleep(std::time::Duration::from_millis(300)).await;
+            "Slow page"
+        }
+
+        let guard =
+            ConcurrencyGuard::new(UserIssuer, 2).status_code(StatusCode::SERVICE_UNAVAILABLE);
+        let router = Router::new().push(Router::with_path("slow").hoop(guard).get(slow));
+        let service = std::sync::Arc::new(Service::new(router));
+
+        let mut tasks = Vec::new();
+        for _ in 0..5 {
+            let service = service.clone();
+            tasks.push(tokio::spawn(async move {
+                TestClient::get("http://127.0.0.1:5800/slow?user=user1")
+                    .send(&*service)
+                    .await
+                    .status_code
+            }));
+        }
+        let mut ok = 0;
+        let mut rejected = 0;
+        for task in tasks {
+            match task.await.unwrap() {
+                Some(StatusCode::OK) => ok += 1,
+                Some(StatusCode::SERVICE_UNAVAILABLE) => rejected += 1,
+                status => panic!("unexpected status: {status:?}"),
+            }
+        }
+        assert_eq!(ok, 2);
+        assert_eq!(rejected, 3);
+
+        let mut response = TestClient::get("http://127.0.0.1:5800/slow?user=user1")
+            .send(&*service)
+            .await;
+        assert_eq!(response.status_code, Some(StatusCode::OK));
+        assert_eq!(response.take_string().await.unwrap(), "Slow page");
+    }
 }
@@ -113,6 +113,42 @@ impl CelledQuota {
     }
 }
 
+/// A quota of token bucket, it allows bursts up to `capacity` requests and refills `refill`
+/// tokens every `period`.
+#[non_exhaustive]
+#[derive(Serialize, Deserialize, Eq, PartialEq, Clone, Debug)]
+pub struct BucketQuota {
+    /// The max tokens of the bucket, which is the max burst of requests.
+    pub capacity: usize,
+    /// The tokens added to the bucket every period.
+    pub refill: usize,
+    /// The period of refilling.
+    pub period: Duration,
+}
+impl BucketQuota {
+    /// Create new `BucketQuota`.
+    pub const fn new(capacity: usize, refill: usize, period: Duration) -> Self {
+        Self {
+            capacity,
+            refill,
+            period,
+        }
+    }
+
+    /// Sets the capacity of the bucket which is refilled `refill` tokens per second.
+    pub const fn per_second(capacity: usize, refill: usize) -> Self {
+        Self::new(capacity, refill, Duration::seconds(1))
+    }
+    /// Sets the capacity of the bucket which is refilled `refill` tokens per minute.
+    pub const fn per_minute(capacity: usize, refill: usize) -> Self {
+        Self::new(capacity, refill, Duration::seconds(60))
+    }
+    /// Sets the capacity of the bucket which is refilled `refill` tokens per hour.
+    pub const fn per_hour(capacity: usize, refill: usize) -> Self {
+        Self::new(capacity, refill, Duration::seconds(3600))
+    }
+}
+
 impl<Key, T> QuotaGetter<Key> for T
 where
     Key: Hash + Eq + Send + Sync + 'static,
@@ -193,4 +229,18 @@ mod tests {
         assert_eq!(quota.cells, 6);
         assert_eq!(quota.period, Duration::seconds(7200));
     }
+
+    #[test]
+    fn test_bucket_quota() {
+        let quota = BucketQuota::per_second(10, 2);
+        assert_eq!(quota.capacity, 10);
+        assert_eq!(quota.refill, 2);
+        assert_eq!(quota.period, Duration::seconds(1));
+
+        let quota = BucketQuota::per_minute(10, 3);
+        assert_eq!(quota.period, Duration::seconds(60));
+
+        let quota = BucketQuota::per_hour(10, 3);
+        assert_eq!(quota.period, Duration::seconds(3600));
+    }
 }
@@ -0,0 +1,111 @@
+use serde::{Deserialize, Serialize};
+use time::{Duration, OffsetDateTime};
+
+use super::{BucketQuota, RateGuard};
+
+/// Token bucket implement.
+///
+/// Every request takes a token from the bucket, and tokens are refilled at a steady rate up to
+/// the capacity of the bucket. So short bursts up to the capacity are allowed, then the
+/// throughput is limited by the refill rate.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+pub struct TokenBucketGuard {
+    tokens: usize,
+    refilled: OffsetDateTime,
+    quota: Option<BucketQuota>,
+}
+
+impl Default for TokenBucketGuard {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TokenBucketGuard {
+    /// Create a new `TokenBucketGuard`.
+    pub fn new() -> Self {
+        Self {
+            tokens: 0,
+            refilled: OffsetDateTime::now_utc(),
+            quota: None,
+        }
+    }
+
+    fn normalize(quota: &BucketQuota) -> BucketQuota {
+        let mut quota = quota.clone();
+        if quota.capacity == 0 {
+            quota.capacity = 1;
+        }
+        if quota.refill == 0 {
+            quota.refill = 1;
+        }
+        quota
+    }
+
+    fn interval(quota: &BucketQuota) -> Duration {
+        quota.period / (quota.refill.min(u32::MAX as usize) as u32)
+    }
+
+    /// Returns the available tokens and the time of the last refilled token at `now`.
+    fn available(&self, quota: &BucketQuota, now: OffsetDateTime) -> (usize, OffsetDateTime) {
+        if self.quota.is_none() || self.tokens >= quota.capacity {
+            return (quota.capacity, now);
+        }
+        let interval = Self::interval(quota);
+        let elapsed = now - self.refilled;
+        if interval <= Duration::ZERO {
+            return (quota.capacity, now);
+        }
+        if elapsed < interval {
+            return (self.tokens, self.refilled);
+        }
+        let added = (elapsed / interval) as usize;
+        if self.tokens + added >= quota.capacity {
+            (quota.capacity, now)
+        } else {
+            (self.tokens + added, self.refilled + interval * (added as u32))
+        }
+    }
+}
+
+impl RateGuard for TokenBucketGuard {
+    type Quota = BucketQuota;
+    async fn verify(&mut self, quota: &Self::Quota) -> bool {
+        let quota = Self::normalize(quota);
+        let now = OffsetDateTime::now_utc();
+        if self.quota.as_ref() != Some(&quota) {
+            self.tokens = quota.capacity;
+            self.refilled = now;
+            self.quota = Some(quota.clone());
+        }
+        let (tokens, refilled) = self.available(&quota, now);
+        self.refilled = refilled;
+        if tokens > 0 {
+            self.tokens = tokens - 1;
+            true
+        } else {
+            self.tokens = 0;
+            false
+        }
+    }
+
+    async fn remaining(&self, quota: &Self::Quota) -> usize {
+        let quota = Self::normalize(quota);
+        self.available(&quota, OffsetDateTime::now_utc()).0
+    }
+
+    async fn reset(&self, quota: &Self::Quota) -> i64 {
+        let quota = Self::normalize(quota);
+        let now = OffsetDateTime::now_utc();
+        let (tokens, refilled) = self.available(&quota, now);
+        if tokens >= quota.capacity {
+            now.unix_timestamp()
+        } else {
+            (refilled + Self::interval(&quota)).unix_timestamp()
+        }
+    }
+
+    async fn limit(&self, quota: &Self::Quota) -> usize {
+        quota.capacity
+    }
+}
@@ -200,7 +200,7 @@ pub mod prelude {
     }
     cfg_feature! {
         #![feature ="compression"]
-        pub use salvo_compression::{Compression, CompressionAlgo, CompressionLevel};
+        pub use salvo_compression::{Compression, CompressionAlgo, CompressionDepotExt, CompressionLevel};
     }
     cfg_feature! {
         #![feature ="craft"]
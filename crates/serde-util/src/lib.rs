@@ -32,6 +32,8 @@ pub struct SerdeValue {
     pub skip: bool,
     /// Rename field.
     pub rename: Option<String>,
+    /// Alternative names accepted when deserializing.
+    pub aliases: Vec<String>,
     /// Is default value.
     pub is_default: bool,
     /// Flatten field.
@@ -66,6 +68,11 @@ impl SerdeValue {
                             value.rename = Some(literal)
                         };
                     }
+                    TokenTree::Ident(ident) if ident == "alias" => {
+                        if let Some((literal, _)) = parse_next_lit_str(next) {
+                            value.aliases.push(literal)
+                        };
+                    }
                     TokenTree::Ident(ident) if ident == "default" => value.is_default = true,
                     _ => (),
                 }
@@ -227,7 +234,7 @@ pub fn parse_value(attributes: &[Attribute]) -> Option<SerdeValue> {
         .filter(|attribute| attribute.path().is_ident("serde"))
         .map(|serde_attribute| serde_attribute.parse_args_with(SerdeValue::parse))
         .try_fold(SerdeValue::default(), |mut acc, value| {
-            let Ok(value) = value else {
+            let Ok(mut value) = value else {
                 return Some(acc);
             };
             if value.skip {
@@ -239,6 +246,7 @@ pub fn parse_value(attributes: &[Attribute]) -> Option<SerdeValue> {
             if value.rename.is_some() {
                 acc.rename = value.rename;
             }
+            acc.aliases.append(&mut value.aliases);
             if value.flatten {
                 acc.flatten = value.flatten;
             }
@@ -288,7 +296,7 @@ pub fn parse_container(attributes: &[Attribute]) -> Option<SerdeContainer> {
 
 #[cfg(test)]
 mod tests {
-    use super::{case::RENAME_RULES, parse_container, RenameRule, SerdeContainer};
+    use super::{case::RENAME_RULES, parse_container, parse_value, RenameRule, SerdeContainer};
     use syn::{parse_quote, Attribute};
 
     #[test]
@@ -322,6 +330,18 @@ mod tests {
         assert_eq!(expected, result);
     }
 
+    #[test]
+    fn test_serde_parse_value_aliases() {
+        let attributes: &[Attribute] = &[
+            parse_quote! { #[serde(rename = "name", alias = "full_name")] },
+            parse_quote! { #[serde(alias = "nick")] },
+        ];
+
+        let value = parse_value(attributes).unwrap();
+        assert_eq!(value.rename.as_deref(), Some("name"));
+        assert_eq!(value.aliases, ["full_name", "nick"]);
+    }
+
     #[test]
     fn test_serde_rename_rule_from_str() {
         for (s, _) in RENAME_RULES {
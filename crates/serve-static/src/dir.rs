@@ -10,7 +10,7 @@ use std::time::SystemTime;
 
 use salvo_core::fs::NamedFile;
 use salvo_core::handler::Handler;
-use salvo_core::http::header::ACCEPT_ENCODING;
+use salvo_core::http::header::{ACCEPT_ENCODING, ACCEPT_RANGES, RANGE};
 use salvo_core::http::{self, HeaderValue, Request, Response, StatusCode, StatusError};
 use salvo_core::writing::Text;
 use salvo_core::{async_trait, Depot, FlowCtrl, IntoVecString};
@@ -123,6 +123,19 @@ where
 }
 
 /// Handler that serves a directory.
+///
+/// # Compression and ranges
+///
+/// Byte ranges are always relative to the identity (uncompressed) file:
+///
+/// * A request with a `Range` header is served from the identity file, even if a
+///   [compressed variation](Self::compressed_variations) is accepted by the client.
+/// * A compressed variation is sent with `Accept-Ranges: none`, so clients do not resume the
+///   download of compressed content with a range of the identity file.
+///
+/// The `Compression` middleware of `salvo-compression` follows the same rules when it
+/// compresses files on the fly: partial responses are passed through as is, and compressed
+/// responses are streamed without `Content-Length` and with `Accept-Ranges: none`.
 #[non_exhaustive]
 pub struct StaticDir {
     /// Static roots.
@@ -140,6 +153,8 @@ pub struct StaticDir {
     exclude_filters: Vec<Box<dyn Fn(&str) -> bool + Send + Sync>>,
     /// Auto list the directory if default file not found.
     pub auto_list: bool,
+    #[allow(clippy::type_complexity)]
+    auto_list_filter: Option<Box<dyn Fn(&Depot) -> bool + Send + Sync>>,
     /// Compressed variations.
     ///
     /// The key is the compression algorithm, and the value is the file extension.
@@ -166,6 +181,7 @@ impl StaticDir {
             include_dot_files: false,
             exclude_filters: vec![],
             auto_list: false,
+            auto_list_filter: None,
             compressed_variations,
             defaults: vec![],
             fallback: None,
@@ -198,6 +214,20 @@ impl StaticDir {
         self
     }
 
+    /// Enables auto list only when the predicate returns true for the current request,
+    /// e.g. when the user is authenticated.
+    ///
+    /// Otherwise directories without default file are treated as not found.
+    #[inline]
+    pub fn auto_list_when<F>(mut self, filter: F) -> Self
+    where
+        F: Fn(&Depot) -> bool + Send + Sync + 'static,
+    {
+        self.auto_list = true;
+        self.auto_list_filter = Some(Box::new(filter));
+        self
+    }
+
     /// Sets compressed_variations.
     #[inline]
     pub fn compressed_variation<A>(mut self, algo: A, exts: &str) -> Self
@@ -300,10 +330,16 @@ impl Handler for StaticDir {
     async fn handle(
         &self,
         req: &mut Request,
-        _depot: &mut Depot,
+        depot: &mut Depot,
         res: &mut Response,
         _ctrl: &mut FlowCtrl,
     ) {
+        let auto_list = self.auto_list
+            && self
+                .auto_list_filter
+                .as_ref()
+                .map(|filter| filter(depot))
+                .unwrap_or(true);
         let req_path = req.uri().path();
         let rel_path = if let Some(rest) = req.params().tail() {
             rest
@@ -346,7 +382,7 @@ impl Handler for StaticDir {
                         }
                     }
 
-                    if self.auto_list && abs_path.is_none() {
+                    if auto_list && abs_path.is_none() {
                         abs_path = Some(path.to_path_buf());
                     }
                     if abs_path.is_some() {
@@ -393,7 +429,7 @@ impl Handler for StaticDir {
                 .unwrap_or(false);
             let mut content_encoding = None;
             let named_path = if !is_compressed_ext {
-                if !self.compressed_variations.is_empty() {
+                if !self.compressed_variations.is_empty() && !req.headers().contains_key(RANGE) {
                     let mut new_abs_path = None;
                     let header = req
                         .headers()
@@ -436,8 +472,8 @@ impl Handler for StaticDir {
                     mime_infer::from_ext(ext.as_deref().unwrap_or_default())
                         .first_or_octet_stream(),
                 );
-                if let Some(content_encoding) = content_encoding {
-                    builder = builder.content_encoding(content_encoding);
+                if let Some(content_encoding) = &content_encoding {
+                    builder = builder.content_encoding(content_encoding.clone());
                 }
                 if let Some(size) = self.chunk_size {
                     builder = builder.buffer_size(size);
@@ -447,6 +483,10 @@ impl Handler for StaticDir {
             if let Ok(named_file) = builder.build().await {
                 let headers = req.headers();
                 named_file.send(headers, res).await;
+                if content_encoding.is_some() {
+                    res.headers_mut()
+                        .insert(ACCEPT_RANGES, HeaderValue::from_static("none"));
+                }
             } else {
                 res.render(StatusError::internal_server_error().brief("Read file failed."));
             }
@@ -197,6 +197,36 @@ mod tests {
         assert!(content == "copy3");
     }
 
+    #[tokio::test]
+    async fn test_serve_static_dir_auto_list_when() {
+        #[handler]
+        async fn auth(req: &mut Request, depot: &mut Depot) {
+            if req.header::<String>("x-user").as_deref() == Some("admin") {
+                depot.insert("admin", true);
+            }
+        }
+
+        let router = Router::with_path("{*path}").hoop(auth).get(
+            StaticDir::new(vec!["test/static"])
+                .auto_list_when(|depot| depot.get::<bool>("admin").is_ok()),
+        );
+        let service = Service::new(router);
+
+        let response = TestClient::get("http://127.0.0.1:5801/dir1/")
+            .send(&service)
+            .await;
+        assert_eq!(response.status_code.unwrap(), StatusCode::NOT_FOUND);
+
+        let mut response = TestClient::get("http://127.0.0.1:5801/dir1/")
+            .add_header("x-user", "admin", true)
+            .add_header("accept", "text/plain", true)
+            .send(&service)
+            .await;
+        assert_eq!(response.status_code.unwrap(), StatusCode::OK);
+        let content = response.take_string().await.unwrap();
+        assert!(content.contains("test3.txt") && content.contains("dir2"));
+    }
+
     #[tokio::test]
     async fn test_serve_static_file() {
         let router = Router::new()
@@ -221,6 +251,96 @@ mod tests {
         assert_eq!(response.status_code.unwrap(), StatusCode::NOT_FOUND);
     }
 
+    #[tokio::test]
+    async fn test_serve_static_file_preconditions() {
+        let router = Router::with_path("test1.txt").get(StaticFile::new("test/static/test1.txt"));
+        let service = Service::new(router);
+
+        let response = TestClient::get("http://127.0.0.1:5801/test1.txt")
+            .send(&service)
+            .await;
+        let etag = response.headers().get("etag").unwrap().clone();
+
+        let response = TestClient::get("http://127.0.0.1:5801/test1.txt")
+            .add_header("if-match", "\"stale\"", true)
+            .add_header("range", "bytes=2-", true)
+            .send(&service)
+            .await;
+        assert_eq!(
+            response.status_code.unwrap(),
+            StatusCode::PRECONDITION_FAILED
+        );
+
+        let response = TestClient::get("http://127.0.0.1:5801/test1.txt")
+            .add_header("if-unmodified-since", "Sat, 01 Jan 2000 00:00:00 GMT", true)
+            .send(&service)
+            .await;
+        assert_eq!(
+            response.status_code.unwrap(),
+            StatusCode::PRECONDITION_FAILED
+        );
+
+        let mut response = TestClient::get("http://127.0.0.1:5801/test1.txt")
+            .add_header("if-match", etag, true)
+            .add_header("range", "bytes=2-", true)
+            .send(&service)
+            .await;
+        assert_eq!(response.status_code.unwrap(), StatusCode::PARTIAL_CONTENT);
+        assert_eq!(response.take_string().await.unwrap(), "py1");
+    }
+
+    #[tokio::test]
+    async fn test_serve_static_dir_compression_and_ranges() {
+        use salvo_compression::Compression;
+        use salvo_core::http::header::{
+            ACCEPT_ENCODING, ACCEPT_RANGES, CONTENT_ENCODING, CONTENT_LENGTH, RANGE,
+        };
+
+        let router = Router::with_hoop(Compression::new().min_length(1))
+            .push(Router::with_path("{*path}").get(StaticDir::new(vec!["test/static"])));
+        let service = Service::new(router);
+
+        // `test1.txt` has no compressed variation, it is compressed on the fly.
+        let mut response = TestClient::get("http://127.0.0.1:5801/test1.txt")
+            .add_header(ACCEPT_ENCODING, "gzip", true)
+            .send(&service)
+            .await;
+        assert_eq!(response.status_code.unwrap(), StatusCode::OK);
+        assert_eq!(response.headers().get(CONTENT_ENCODING).unwrap(), "gzip");
+        assert_eq!(response.headers().get(ACCEPT_RANGES).unwrap(), "none");
+        assert!(response.headers().get(CONTENT_LENGTH).is_none());
+        assert_eq!(response.take_string().await.unwrap(), "copy1");
+
+        let mut response = TestClient::get("http://127.0.0.1:5801/test1.txt")
+            .add_header(ACCEPT_ENCODING, "gzip", true)
+            .add_header(RANGE, "bytes=2-", true)
+            .send(&service)
+            .await;
+        assert_eq!(response.status_code.unwrap(), StatusCode::PARTIAL_CONTENT);
+        assert!(response.headers().get(CONTENT_ENCODING).is_none());
+        assert_eq!(response.headers().get(ACCEPT_RANGES).unwrap(), "bytes");
+        assert_eq!(response.take_string().await.unwrap(), "py1");
+
+        // `test2.txt` has a precompressed `test2.txt.gz` variation.
+        let mut response = TestClient::get("http://127.0.0.1:5801/test2.txt")
+            .add_header(ACCEPT_ENCODING, "gzip", true)
+            .send(&service)
+            .await;
+        assert_eq!(response.status_code.unwrap(), StatusCode::OK);
+        assert_eq!(response.headers().get(CONTENT_ENCODING).unwrap(), "gzip");
+        assert_eq!(response.headers().get(ACCEPT_RANGES).unwrap(), "none");
+        assert_eq!(response.take_string().await.unwrap(), "copy2");
+
+        let mut response = TestClient::get("http://127.0.0.1:5801/test2.txt")
+            .add_header(ACCEPT_ENCODING, "gzip", true)
+            .add_header(RANGE, "bytes=2-", true)
+            .send(&service)
+            .await;
+        assert_eq!(response.status_code.unwrap(), StatusCode::PARTIAL_CONTENT);
+        assert!(response.headers().get(CONTENT_ENCODING).is_none());
+        assert_eq!(response.take_string().await.unwrap(), "py2");
+    }
+
     #[cfg(feature = "embed")]
     #[tokio::test]
     async fn test_serve_embed_files() {
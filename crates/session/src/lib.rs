@@ -61,7 +61,11 @@ Any session store other than the cookie store will accumulate stale
 sessions. Although the salvo session handler ensures that they
 will not be used as valid sessions, For most session stores, it is the
 salvo application's responsibility to call cleanup on the session
-store if it requires it.
+store if it requires it. For stores implementing `SessionCleanup`,
+`SessionHandler::spawn_cleanup` spawns a task doing it periodically.
+
+With the `postgres-store` feature, `PostgresStore` saves sessions in a PostgreSQL
+table through a sqlx pool.
 
 Read more: <https://salvo.rs>
 */
@@ -71,18 +75,30 @@ Read more: <https://salvo.rs>
 
 pub use async_session::{CookieStore, MemoryStore, Session, SessionStore};
 
+#[cfg(feature = "postgres-store")]
+mod postgres_store;
+#[cfg(feature = "postgres-store")]
+#[cfg_attr(docsrs, doc(cfg(feature = "postgres-store")))]
+pub use postgres_store::PostgresStore;
+
 use std::fmt::{self, Formatter};
+use std::future::Future;
 use std::time::Duration;
 
-use async_session::base64;
 use async_session::hmac::{Hmac, Mac, NewMac};
 use async_session::sha2::Sha256;
-use cookie::{Cookie, Key, SameSite};
+use async_session::{base64, serde_json};
+use cookie::time::OffsetDateTime;
+use cookie::{Cookie, CookieBuilder, Key, SameSite};
+use salvo_core::http::header::{HeaderName, HeaderValue};
 use salvo_core::http::uri::Scheme;
 use salvo_core::{async_trait, Depot, Error, FlowCtrl, Handler, Request, Response};
+use tokio::task::JoinHandle;
 
 /// Key for store data in depot.
 pub const SESSION_KEY: &str = "::salvo::session";
+const SESSION_REGENERATE_KEY: &str = "::salvo::session::regenerate";
+const SESSION_LAZY_KEY: &str = "::salvo::session::lazy";
 const BASE64_DIGEST_LEN: usize = 44;
 
 /// Trait for `Depot` to get and set session.
@@ -93,8 +109,17 @@ pub trait SessionDepotExt {
     fn take_session(&mut self) -> Option<Session>;
     /// Get session reference
     fn session(&self) -> Option<&Session>;
-    /// Get session mutable reference
+    /// Get session mutable reference.
+    ///
+    /// If the session handler is [lazy](HandlerBuilder::lazy) and the request has no session,
+    /// a new session is created.
     fn session_mut(&mut self) -> Option<&mut Session>;
+    /// Marks the session to be issued a new id when the response is sent.
+    ///
+    /// The session data is kept, the old id is destroyed in the store and a new cookie is set.
+    /// Call it when the privileges of the session change, e.g. on login, to prevent session
+    /// fixation.
+    fn regenerate_session(&mut self) -> &mut Self;
 }
 
 impl SessionDepotExt for Depot {
@@ -113,21 +138,97 @@ impl SessionDepotExt for Depot {
     }
     #[inline]
     fn session_mut(&mut self) -> Option<&mut Session> {
+        if !self.contains_key(SESSION_KEY) && self.remove::<bool>(SESSION_LAZY_KEY).is_ok() {
+            self.insert(SESSION_KEY, Session::new());
+        }
         self.get_mut(SESSION_KEY).ok()
     }
+    #[inline]
+    fn regenerate_session(&mut self) -> &mut Self {
+        self.insert(SESSION_REGENERATE_KEY, true);
+        self
+    }
+}
+
+/// A [`SessionStore`] which is able to remove expired sessions, see
+/// [`SessionHandler::spawn_cleanup`].
+pub trait SessionCleanup: SessionStore {
+    /// Removes expired sessions from the store.
+    fn cleanup(&self) -> impl Future<Output = Result<(), async_session::Error>> + Send;
+}
+impl SessionCleanup for MemoryStore {
+    #[inline]
+    async fn cleanup(&self) -> Result<(), async_session::Error> {
+        MemoryStore::cleanup(self).await
+    }
 }
 
+/// Function customizing the session cookie, see [`HandlerBuilder::cookie_builder`].
+pub type CookieBuilderFn =
+    Box<dyn Fn(CookieBuilder<'static>) -> Cookie<'static> + Send + Sync + 'static>;
+
+/// Callback invoked with the session right before it is destroyed.
+pub type DestroyCallback = Box<dyn Fn(&Session) + Send + Sync + 'static>;
+
+/// How the session id is transported between the client and the server.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SessionTransport {
+    /// The session id is sent in a cookie, this is the default.
+    #[default]
+    Cookie,
+    /// The session id is read from and written to the header with the given name, e.g.
+    /// `x-session-id`. This is useful for non-browser clients.
+    Header(HeaderName),
+}
+
+/// Session lifecycle event, each variant carries the session id, which is a digest of the cookie
+/// value.
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum SessionEvent {
+    /// A new session is created because the request has no valid session cookie.
+    Created(String),
+    /// An existing session is loaded from the store.
+    Loaded(String),
+    /// The session is stored.
+    Stored(String),
+    /// The session is destroyed, either explicitly or because it is regenerated.
+    Destroyed(String),
+}
+impl SessionEvent {
+    /// Returns the id of the session the event refers to.
+    #[inline]
+    pub fn session_id(&self) -> &str {
+        match self {
+            Self::Created(id) | Self::Loaded(id) | Self::Stored(id) | Self::Destroyed(id) => id,
+        }
+    }
+}
+
+/// Callback invoked on session lifecycle events.
+pub type EventCallback = Box<dyn Fn(SessionEvent) + Send + Sync + 'static>;
+
 /// `HandlerBuilder` is a builder for [`SessionHandler`].
 pub struct HandlerBuilder<S> {
     store: S,
     cookie_path: String,
     cookie_name: String,
+    fallback_cookie_names: Vec<String>,
     cookie_domain: Option<String>,
     session_ttl: Option<Duration>,
     save_unchanged: bool,
+    rolling: bool,
+    lazy: bool,
+    max_size: Option<usize>,
+    partitioned: bool,
     same_site_policy: SameSite,
+    transport: SessionTransport,
     key: Key,
     fallback_keys: Vec<Key>,
+    on_destroy: Option<DestroyCallback>,
+    cookie_builder: Option<CookieBuilderFn>,
+    on_event: Option<EventCallback>,
 }
 impl<S: SessionStore> fmt::Debug for HandlerBuilder<S> {
     fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
@@ -135,12 +236,18 @@ impl<S: SessionStore> fmt::Debug for HandlerBuilder<S> {
             .field("store", &self.store)
             .field("cookie_path", &self.cookie_path)
             .field("cookie_name", &self.cookie_name)
+            .field("fallback_cookie_names", &self.fallback_cookie_names)
             .field("cookie_domain", &self.cookie_domain)
             .field("session_ttl", &self.session_ttl)
             .field("same_site_policy", &self.same_site_policy)
+            .field("transport", &self.transport)
             .field("key", &"..")
             .field("fallback_keys", &"..")
             .field("save_unchanged", &self.save_unchanged)
+            .field("rolling", &self.rolling)
+            .field("lazy", &self.lazy)
+            .field("max_size", &self.max_size)
+            .field("partitioned", &self.partitioned)
             .finish()
     }
 }
@@ -155,13 +262,22 @@ where
         Self {
             store,
             save_unchanged: true,
+            rolling: false,
+            lazy: false,
+            max_size: None,
+            partitioned: false,
             cookie_path: "/".into(),
             cookie_name: "salvo.session.id".into(),
+            fallback_cookie_names: Vec::new(),
             cookie_domain: None,
             same_site_policy: SameSite::Lax,
+            transport: SessionTransport::Cookie,
             session_ttl: Some(Duration::from_secs(24 * 60 * 60)),
             key: Key::from(secret),
             fallback_keys: vec![],
+            on_destroy: None,
+            cookie_builder: None,
+            on_event: None,
         }
     }
 
@@ -190,12 +306,26 @@ where
     /// If you are running multiple tide applications on the same
     /// domain, you will need different values for each
     /// application. The default value is "salvo.session_id".
+    ///
+    /// Names with the `__Secure-` or `__Host-` prefix always produce a `Secure` cookie. A
+    /// `__Host-` cookie must also use the path `/` and no domain, otherwise [`build`](Self::build)
+    /// returns an error.
     #[inline]
     pub fn cookie_name(mut self, cookie_name: impl Into<String>) -> Self {
         self.cookie_name = cookie_name.into();
         self
     }
 
+    /// Sets cookie names the session is read from when the cookie named `cookie_name` is absent.
+    ///
+    /// This allows renaming the session cookie without logging users out. A session read from a
+    /// fallback cookie is re-issued under `cookie_name`, the old cookie is left to expire.
+    #[inline]
+    pub fn fallback_cookie_names(mut self, names: Vec<impl Into<String>>) -> Self {
+        self.fallback_cookie_names = names.into_iter().map(Into::into).collect();
+        self
+    }
+
     /// Sets the `save_unchanged` value.
     ///
     /// When `save_unchanged` is enabled, a session will cookie will always be set.
@@ -211,6 +341,54 @@ where
         self
     }
 
+    /// Sets whether the session expiry is refreshed on every response.
+    ///
+    /// When enabled, the session is stored and the cookie is re-issued with a fresh expiry on each
+    /// response even if the session data did not change, so the session only expires after
+    /// `session_ttl` of inactivity. It takes effect regardless of `save_unchanged`. Without it,
+    /// the cookie is only issued when a session is created, so it expires `session_ttl` after
+    /// login even if the session is in use. The default is `false`.
+    #[inline]
+    pub fn rolling(mut self, value: bool) -> Self {
+        self.rolling = value;
+        self
+    }
+
+    /// Sets whether new sessions are only created when they are used.
+    ///
+    /// When enabled, requests without a valid session have no session in the depot until
+    /// [`SessionDepotExt::session_mut`] or [`SessionDepotExt::set_session`] is called, and
+    /// nothing is stored if the session is never used, regardless of `save_unchanged`. This
+    /// avoids storing empty sessions for anonymous traffic such as bots. Sessions of requests
+    /// with a valid cookie are still loaded eagerly. The default is `false`.
+    #[inline]
+    pub fn lazy(mut self, value: bool) -> Self {
+        self.lazy = value;
+        self
+    }
+
+    /// Sets the maximum size in bytes of the serialized session.
+    ///
+    /// Sessions exceeding it are not stored and an error is logged, the changes made while
+    /// handling the request are lost. This is useful with [`CookieStore`], as browsers silently
+    /// drop oversized cookies. There is no limit by default.
+    #[inline]
+    pub fn max_size(mut self, max_size: usize) -> Self {
+        self.max_size = Some(max_size);
+        self
+    }
+
+    /// Sets whether the session cookie has the `Partitioned` attribute (CHIPS).
+    ///
+    /// Browsers require partitioned cookies to be used in cross-site contexts such as third-party
+    /// iframes, usually together with `SameSite::None`. A partitioned cookie is always sent with
+    /// the `Secure` attribute. The default is `false`.
+    #[inline]
+    pub fn partitioned(mut self, value: bool) -> Self {
+        self.partitioned = value;
+        self
+    }
+
     /// Sets the same site policy for the session cookie. Defaults to
     /// SameSite::Lax. See [incrementally better
     /// cookies](https://tools.ietf.org/html/draft-west-cookie-incrementalism-01)
@@ -221,6 +399,17 @@ where
         self
     }
 
+    /// Sets how the session id is transported, the default is [`SessionTransport::Cookie`].
+    ///
+    /// With [`SessionTransport::Header`], the signed session id is read from the request header
+    /// and written to the response header whenever a cookie would have been set. The cookie
+    /// options such as `Secure`, `SameSite`, path, domain and expiry only apply to cookies.
+    #[inline]
+    pub fn transport(mut self, transport: SessionTransport) -> Self {
+        self.transport = transport;
+        self
+    }
+
     /// Sets the domain of the cookie.
     #[inline]
     pub fn cookie_domain(mut self, cookie_domain: impl AsRef<str>) -> Self {
@@ -241,19 +430,77 @@ where
         self
     }
 
+    /// Sets a function producing the session cookie from a builder with the configured
+    /// attributes applied, so that attributes can be added or changed.
+    ///
+    /// The cookie value is signed after the function is called, so it should not be changed.
+    #[inline]
+    pub fn cookie_builder<F>(mut self, cookie_builder: F) -> Self
+    where
+        F: Fn(CookieBuilder<'static>) -> Cookie<'static> + Send + Sync + 'static,
+    {
+        self.cookie_builder = Some(Box::new(cookie_builder));
+        self
+    }
+
+    /// Sets a callback invoked with the session right before it is destroyed, e.g. on logout.
+    ///
+    /// This can be used for audit logging or to clean up state related to the session.
+    #[inline]
+    pub fn on_destroy<F>(mut self, on_destroy: F) -> Self
+    where
+        F: Fn(&Session) + Send + Sync + 'static,
+    {
+        self.on_destroy = Some(Box::new(on_destroy));
+        self
+    }
+
+    /// Sets a callback invoked when a session is created, loaded, stored or destroyed.
+    ///
+    /// This can be used for auditing and metrics without wrapping the [`SessionStore`].
+    #[inline]
+    pub fn on_event<F>(mut self, on_event: F) -> Self
+    where
+        F: Fn(SessionEvent) + Send + Sync + 'static,
+    {
+        self.on_event = Some(Box::new(on_event));
+        self
+    }
+
     /// Build `SessionHandler`
     pub fn build(self) -> Result<SessionHandler<S>, Error> {
         let Self {
             store,
             save_unchanged,
+            rolling,
+            lazy,
+            max_size,
+            partitioned,
             cookie_path,
             cookie_name,
+            fallback_cookie_names,
             cookie_domain,
             session_ttl,
             same_site_policy,
+            transport,
             key,
             fallback_keys,
+            on_destroy,
+            cookie_builder,
+            on_event,
         } = self;
+        if cookie_name.starts_with("__Host-") {
+            if cookie_domain.is_some() {
+                return Err(Error::Other(
+                    "cookie with `__Host-` prefix must not have a domain".into(),
+                ));
+            }
+            if cookie_path != "/" {
+                return Err(Error::Other(
+                    "cookie with `__Host-` prefix must have the path `/`".into(),
+                ));
+            }
+        }
         let hmac = Hmac::<Sha256>::new_from_slice(key.signing())
             .map_err(|_| Error::Other("invalid key length".into()))?;
         let fallback_hmacs = fallback_keys
@@ -264,13 +511,22 @@ where
         Ok(SessionHandler {
             store,
             save_unchanged,
+            rolling,
+            lazy,
+            max_size,
+            partitioned,
             cookie_path,
             cookie_name,
+            fallback_cookie_names,
             cookie_domain,
             session_ttl,
             same_site_policy,
+            transport,
             hmac,
             fallback_hmacs,
+            on_destroy,
+            cookie_builder,
+            on_event,
         })
     }
 }
@@ -280,12 +536,21 @@ pub struct SessionHandler<S> {
     store: S,
     cookie_path: String,
     cookie_name: String,
+    fallback_cookie_names: Vec<String>,
     cookie_domain: Option<String>,
     session_ttl: Option<Duration>,
     save_unchanged: bool,
+    rolling: bool,
+    lazy: bool,
+    max_size: Option<usize>,
+    partitioned: bool,
     same_site_policy: SameSite,
+    transport: SessionTransport,
     hmac: Hmac<Sha256>,
     fallback_hmacs: Vec<Hmac<Sha256>>,
+    on_destroy: Option<DestroyCallback>,
+    cookie_builder: Option<CookieBuilderFn>,
+    on_event: Option<EventCallback>,
 }
 impl<S: SessionStore> fmt::Debug for SessionHandler<S> {
     #[inline]
@@ -294,12 +559,18 @@ impl<S: SessionStore> fmt::Debug for SessionHandler<S> {
             .field("store", &self.store)
             .field("cookie_path", &self.cookie_path)
             .field("cookie_name", &self.cookie_name)
+            .field("fallback_cookie_names", &self.fallback_cookie_names)
             .field("cookie_domain", &self.cookie_domain)
             .field("session_ttl", &self.session_ttl)
             .field("same_site_policy", &self.same_site_policy)
+            .field("transport", &self.transport)
             .field("key", &"..")
             .field("fallback_keys", &"..")
             .field("save_unchanged", &self.save_unchanged)
+            .field("rolling", &self.rolling)
+            .field("lazy", &self.lazy)
+            .field("max_size", &self.max_size)
+            .field("partitioned", &self.partitioned)
             .finish()
     }
 }
@@ -315,35 +586,133 @@ where
         res: &mut Response,
         ctrl: &mut FlowCtrl,
     ) {
-        let cookie = req.cookies().get(&self.cookie_name);
-        let cookie_value = cookie.and_then(|cookie| self.verify_signature(cookie.value()).ok());
-
-        let mut session = self.load_or_create(cookie_value).await;
+        // Whether the session id is read from a verified fallback cookie.
+        let mut from_fallback = false;
+        let cookie_value = match &self.transport {
+            SessionTransport::Cookie => {
+                let cookies = req.cookies();
+                match cookies.get(&self.cookie_name) {
+                    Some(cookie) => self.verify_signature(cookie.value()).ok(),
+                    None => self.fallback_cookie_names.iter().find_map(|name| {
+                        let value = self.verify_signature(cookies.get(name)?.value()).ok()?;
+                        from_fallback = true;
+                        Some(value)
+                    }),
+                }
+            }
+            SessionTransport::Header(name) => req
+                .headers()
+                .get(name)
+                .and_then(|v| v.to_str().ok())
+                .and_then(|value| self.verify_signature(value).ok()),
+        };
 
-        if let Some(ttl) = self.session_ttl {
-            session.expire_in(ttl);
+        let loaded = self.load(cookie_value.clone()).await;
+        // A session loaded from a fallback cookie must be re-issued under the primary name.
+        let migrate = from_fallback && loaded.is_some();
+        let session = match loaded {
+            Some(session) => {
+                self.emit(|| SessionEvent::Loaded(session.id().to_owned()));
+                Some(session)
+            }
+            None if self.lazy => None,
+            None => {
+                let session = Session::new();
+                self.emit(|| SessionEvent::Created(session.id().to_owned()));
+                Some(session)
+            }
+        };
+        let lazy = session.is_none();
+        if let Some(mut session) = session {
+            if let Some(ttl) = self.session_ttl {
+                session.expire_in(ttl);
+            }
+            depot.set_session(session);
+        } else {
+            depot.insert(SESSION_LAZY_KEY, true);
         }
 
-        depot.set_session(session);
-
         ctrl.call_next(req, depot, res).await;
         if ctrl.is_ceased() {
             return;
         }
 
-        let session = depot.take_session().expect("session should exist in depot");
+        let Some(mut session) = depot.take_session() else {
+            depot.remove::<bool>(SESSION_LAZY_KEY).ok();
+            return;
+        };
+        if lazy && depot.remove::<bool>(SESSION_LAZY_KEY).is_err() {
+            // The session is created on first access while handling the request.
+            self.emit(|| SessionEvent::Created(session.id().to_owned()));
+            if let Some(ttl) = self.session_ttl {
+                session.expire_in(ttl);
+            }
+        }
+        let regenerate = depot
+            .remove::<bool>(SESSION_REGENERATE_KEY)
+            .unwrap_or_default();
         if session.is_destroyed() {
-            if let Err(e) = self.store.destroy_session(session).await {
-                tracing::error!(error = ?e, "unable to destroy session");
+            if let Some(on_destroy) = &self.on_destroy {
+                on_destroy(&session);
+            }
+            let session_id = session.id().to_owned();
+            match self.store.destroy_session(session).await {
+                Ok(()) => self.emit(|| SessionEvent::Destroyed(session_id)),
+                Err(e) => tracing::error!(error = ?e, "unable to destroy session"),
+            }
+            if self.transport == SessionTransport::Cookie {
+                res.remove_cookie(&self.cookie_name);
+            }
+        } else if regenerate
+            || self.rolling
+            || migrate
+            || self.save_unchanged
+            || session.data_changed()
+        {
+            if let Some(max_size) = self.max_size {
+                let size = serde_json::to_vec(&session)
+                    .map(|data| data.len())
+                    .unwrap_or_default();
+                if size > max_size {
+                    tracing::error!(size, max_size, "session is too large and is not stored");
+                    return;
+                }
             }
-            res.remove_cookie(&self.cookie_name);
-        } else if self.save_unchanged || session.data_changed() {
+            if regenerate {
+                let old_session = session.clone();
+                session.regenerate();
+                let old_session_id = old_session.id().to_owned();
+                match self.store.destroy_session(old_session).await {
+                    Ok(()) => self.emit(|| SessionEvent::Destroyed(old_session_id)),
+                    Err(e) => tracing::error!(error = ?e, "unable to destroy regenerated session"),
+                }
+            }
+            let session_id = session.id().to_owned();
             match self.store.store_session(session).await {
-                Ok(cookie_value) => {
+                Ok(new_cookie_value) => {
+                    self.emit(|| SessionEvent::Stored(session_id));
+                    // Loaded sessions keep their cookie value, it is re-issued to refresh the
+                    // cookie expiry when rolling or to move it to the primary cookie name.
+                    let cookie_value = new_cookie_value
+                        .or(cookie_value.filter(|_| (self.rolling || migrate) && !regenerate));
                     if let Some(cookie_value) = cookie_value {
-                        let secure_cookie = req.uri().scheme() == Some(&Scheme::HTTPS);
-                        let cookie = self.build_cookie(secure_cookie, cookie_value);
-                        res.add_cookie(cookie);
+                        match &self.transport {
+                            SessionTransport::Cookie => {
+                                let secure_cookie = req.uri().scheme() == Some(&Scheme::HTTPS);
+                                let cookie = self.build_cookie(secure_cookie, cookie_value);
+                                res.add_cookie(cookie);
+                            }
+                            SessionTransport::Header(name) => {
+                                match HeaderValue::from_str(&self.sign_value(&cookie_value)) {
+                                    Ok(value) => {
+                                        res.headers_mut().insert(name.clone(), value);
+                                    }
+                                    Err(e) => {
+                                        tracing::error!(error = ?e, "invalid session header value");
+                                    }
+                                }
+                            }
+                        }
                     }
                 }
                 Err(e) => {
@@ -362,16 +731,40 @@ where
     pub fn builder(store: S, secret: &[u8]) -> HandlerBuilder<S> {
         HandlerBuilder::new(store, secret)
     }
+
+    /// Spawns a task which removes expired sessions from the store every `interval`.
+    ///
+    /// The task runs until the returned handle is aborted. Errors are logged and do not stop
+    /// the task.
+    pub fn spawn_cleanup(&self, interval: Duration) -> JoinHandle<()>
+    where
+        S: SessionCleanup,
+    {
+        let store = self.store.clone();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(interval);
+            interval.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            loop {
+                interval.tick().await;
+                if let Err(e) = store.cleanup().await {
+                    tracing::error!(error = ?e, "session cleanup failed");
+                }
+            }
+        })
+    }
     #[inline]
-    async fn load_or_create(&self, cookie_value: Option<String>) -> Session {
+    async fn load(&self, cookie_value: Option<String>) -> Option<Session> {
         let session = match cookie_value {
             Some(cookie_value) => self.store.load_session(cookie_value).await.ok().flatten(),
             None => None,
         };
-
-        session
-            .and_then(|session| session.validate())
-            .unwrap_or_default()
+        session.and_then(|session| session.validate())
+    }
+    #[inline]
+    fn emit(&self, event: impl FnOnce() -> SessionEvent) {
+        if let Some(on_event) = &self.on_event {
+            on_event(event());
+        }
     }
     // the following is reused verbatim from
     // https://github.com/SergioBenitez/cookie-rs/blob/master/src/secure/signed.rs#L51-L66
@@ -406,21 +799,25 @@ where
         Err(Error::Other("value did not verify".into()))
     }
     fn build_cookie(&self, secure: bool, cookie_value: String) -> Cookie<'static> {
-        let mut cookie = Cookie::build((self.cookie_name.clone(), cookie_value))
+        let mut builder = Cookie::build((self.cookie_name.clone(), cookie_value))
             .http_only(true)
             .same_site(self.same_site_policy)
-            .secure(secure)
-            .path(self.cookie_path.clone())
-            .build();
+            .secure(secure || self.partitioned || has_secure_prefix(&self.cookie_name))
+            .partitioned(self.partitioned)
+            .path(self.cookie_path.clone());
 
         if let Some(ttl) = self.session_ttl {
-            cookie.set_expires(Some((std::time::SystemTime::now() + ttl).into()));
+            builder = builder.expires(OffsetDateTime::from(std::time::SystemTime::now() + ttl));
         }
 
         if let Some(cookie_domain) = self.cookie_domain.clone() {
-            cookie.set_domain(cookie_domain)
+            builder = builder.domain(cookie_domain);
         }
 
+        let mut cookie = match &self.cookie_builder {
+            Some(cookie_builder) => cookie_builder(builder),
+            None => builder.build(),
+        };
         self.sign_cookie(&mut cookie);
 
         cookie
@@ -429,17 +826,26 @@ where
     // https://github.com/SergioBenitez/cookie-rs/blob/master/src/secure/signed.rs#L37-46
     /// signs the cookie's value providing integrity and authenticity.
     fn sign_cookie(&self, cookie: &mut Cookie<'_>) {
-        // Compute HMAC-SHA256 of the cookie's value.
+        let new_value = self.sign_value(cookie.value());
+        cookie.set_value(new_value);
+    }
+    /// Signs the value, the signed value is [MAC | original-value].
+    fn sign_value(&self, value: &str) -> String {
+        // Compute HMAC-SHA256 of the value.
         let mut mac = self.hmac.clone();
-        mac.update(cookie.value().as_bytes());
+        mac.update(value.as_bytes());
 
-        // Cookie's new value is [MAC | original-value].
         let mut new_value = base64::encode(mac.finalize().into_bytes());
-        new_value.push_str(cookie.value());
-        cookie.set_value(new_value);
+        new_value.push_str(value);
+        new_value
     }
 }
 
+/// Cookies with these name prefixes are rejected by browsers unless they are `Secure`.
+fn has_secure_prefix(cookie_name: &str) -> bool {
+    cookie_name.starts_with("__Secure-") || cookie_name.starts_with("__Host-")
+}
+
 #[cfg(test)]
 mod tests {
     use salvo_core::http::header::*;
@@ -542,4 +948,534 @@ mod tests {
             .await;
         assert_eq!(respone.take_string().await.unwrap(), "home");
     }
+
+    #[tokio::test]
+    async fn test_session_on_destroy() {
+        use std::sync::{Arc, Mutex};
+
+        #[handler]
+        pub async fn login(depot: &mut Depot) {
+            let mut session = Session::new();
+            session.insert("username", "salvo").unwrap();
+            depot.set_session(session);
+        }
+
+        #[handler]
+        pub async fn logout(depot: &mut Depot) {
+            if let Some(session) = depot.session_mut() {
+                session.destroy();
+            }
+        }
+
+        let destroyed = Arc::new(Mutex::new(Vec::new()));
+        let collector = destroyed.clone();
+        let session_handler = SessionHandler::builder(
+            MemoryStore::new(),
+            b"secretabsecretabsecretabsecretabsecretabsecretabsecretabsecretab",
+        )
+        .on_destroy(move |session| {
+            collector
+                .lock()
+                .unwrap()
+                .push(session.get::<String>("username"));
+        })
+        .build()
+        .unwrap();
+        let router = Router::new()
+            .hoop(session_handler)
+            .push(Router::with_path("login").post(login))
+            .push(Router::with_path("logout").get(logout));
+        let service = Service::new(router);
+
+        let respone = TestClient::post("http://127.0.0.1:5800/login")
+            .send(&service)
+            .await;
+        let cookie = respone.headers().get(SET_COOKIE).unwrap();
+        assert!(destroyed.lock().unwrap().is_empty());
+
+        TestClient::get("http://127.0.0.1:5800/logout")
+            .add_header(COOKIE, cookie, true)
+            .send(&service)
+            .await;
+        assert_eq!(*destroyed.lock().unwrap(), vec![Some("salvo".to_owned())]);
+    }
+
+    #[tokio::test]
+    async fn test_session_regenerate() {
+        #[handler]
+        pub async fn login(depot: &mut Depot) {
+            if let Some(session) = depot.session_mut() {
+                session.insert("username", "salvo").unwrap();
+            }
+            depot.regenerate_session();
+        }
+
+        #[handler]
+        pub async fn home(depot: &mut Depot) -> String {
+            depot
+                .session()
+                .and_then(|session| session.get::<String>("username"))
+                .unwrap_or_else(|| "guest".into())
+        }
+
+        let session_handler = SessionHandler::builder(
+            MemoryStore::new(),
+            b"secretabsecretabsecretabsecretabsecretabsecretabsecretabsecretab",
+        )
+        .build()
+        .unwrap();
+        let router = Router::new()
+            .hoop(session_handler)
+            .get(home)
+            .push(Router::with_path("login").post(login));
+        let service = Service::new(router);
+
+        let respone = TestClient::get("http://127.0.0.1:5800/")
+            .send(&service)
+            .await;
+        let old_cookie = respone.headers().get(SET_COOKIE).unwrap().clone();
+        let old_cookie = old_cookie.to_str().unwrap().split(';').next().unwrap();
+
+        let respone = TestClient::post("http://127.0.0.1:5800/login")
+            .add_header(COOKIE, old_cookie, true)
+            .send(&service)
+            .await;
+        let new_cookie = respone.headers().get(SET_COOKIE).unwrap().clone();
+        let new_cookie = new_cookie.to_str().unwrap().split(';').next().unwrap();
+        assert_ne!(old_cookie, new_cookie);
+
+        let mut respone = TestClient::get("http://127.0.0.1:5800/")
+            .add_header(COOKIE, new_cookie, true)
+            .send(&service)
+            .await;
+        assert_eq!(respone.take_string().await.unwrap(), "salvo");
+
+        let mut respone = TestClient::get("http://127.0.0.1:5800/")
+            .add_header(COOKIE, old_cookie, true)
+            .send(&service)
+            .await;
+        assert_eq!(respone.take_string().await.unwrap(), "guest");
+    }
+
+    #[tokio::test]
+    async fn test_session_rolling() {
+        #[handler]
+        pub async fn home() -> &'static str {
+            "home"
+        }
+
+        for rolling in [false, true] {
+            let session_handler = SessionHandler::builder(
+                MemoryStore::new(),
+                b"secretabsecretabsecretabsecretabsecretabsecretabsecretabsecretab",
+            )
+            .rolling(rolling)
+            .build()
+            .unwrap();
+            let service = Service::new(Router::new().hoop(session_handler).get(home));
+
+            let respone = TestClient::get("http://127.0.0.1:5800/")
+                .send(&service)
+                .await;
+            let cookie = respone.headers().get(SET_COOKIE).unwrap().clone();
+            let cookie = cookie.to_str().unwrap().split(';').next().unwrap();
+
+            let respone = TestClient::get("http://127.0.0.1:5800/")
+                .add_header(COOKIE, cookie, true)
+                .send(&service)
+                .await;
+            let refreshed = respone
+                .headers()
+                .get(SET_COOKIE)
+                .map(|v| v.to_str().unwrap().to_owned());
+            if rolling {
+                let refreshed = refreshed.unwrap();
+                assert!(refreshed.starts_with(cookie));
+                assert!(refreshed.contains("Expires="));
+            } else {
+                assert!(refreshed.is_none());
+            }
+        }
+    }
+
+    #[tokio::test]
+    async fn test_session_partitioned() {
+        #[handler]
+        pub async fn home() -> &'static str {
+            "home"
+        }
+
+        let session_handler = SessionHandler::builder(
+            MemoryStore::new(),
+            b"secretabsecretabsecretabsecretabsecretabsecretabsecretabsecretab",
+        )
+        .same_site_policy(SameSite::None)
+        .partitioned(true)
+        .build()
+        .unwrap();
+        let router = Router::new().hoop(session_handler).get(home);
+
+        let respone = TestClient::get("http://127.0.0.1:5800/").send(router).await;
+        let cookie = respone.headers().get(SET_COOKIE).unwrap().to_str().unwrap();
+        assert!(cookie.contains("SameSite=None"));
+        assert!(cookie.contains("Secure"));
+        assert!(cookie.contains("Partitioned"));
+    }
+
+    #[tokio::test]
+    async fn test_session_cookie_prefixes() {
+        #[handler]
+        pub async fn home() -> &'static str {
+            "home"
+        }
+        let secret = b"secretabsecretabsecretabsecretabsecretabsecretabsecretabsecretab";
+
+        assert!(SessionHandler::builder(MemoryStore::new(), secret)
+            .cookie_name("__Host-session")
+            .cookie_domain("example.com")
+            .build()
+            .is_err());
+        assert!(SessionHandler::builder(MemoryStore::new(), secret)
+            .cookie_name("__Host-session")
+            .cookie_path("/app")
+            .build()
+            .is_err());
+        assert!(SessionHandler::builder(MemoryStore::new(), secret)
+            .cookie_name("__Secure-session")
+            .cookie_domain("example.com")
+            .cookie_path("/app")
+            .build()
+            .is_ok());
+
+        for name in ["__Host-session", "__Secure-session"] {
+            let session_handler = SessionHandler::builder(MemoryStore::new(), secret)
+                .cookie_name(name)
+                .build()
+                .unwrap();
+            let router = Router::new().hoop(session_handler).get(home);
+            let respone = TestClient::get("http://127.0.0.1:5800/").send(router).await;
+            let cookie = respone.headers().get(SET_COOKIE).unwrap().to_str().unwrap();
+            assert!(cookie.starts_with(name));
+            assert!(cookie.contains("Secure"));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_session_on_event() {
+        use std::sync::{Arc, Mutex};
+
+        #[handler]
+        pub async fn login(depot: &mut Depot) {
+            let session = depot.session_mut().unwrap();
+            session.insert("username", "salvo").unwrap();
+        }
+
+        #[handler]
+        pub async fn logout(depot: &mut Depot) {
+            if let Some(session) = depot.session_mut() {
+                session.destroy();
+            }
+        }
+
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let collector = events.clone();
+        let session_handler = SessionHandler::builder(
+            MemoryStore::new(),
+            b"secretabsecretabsecretabsecretabsecretabsecretabsecretabsecretab",
+        )
+        .on_event(move |event| collector.lock().unwrap().push(event))
+        .build()
+        .unwrap();
+        let router = Router::new()
+            .hoop(session_handler)
+            .push(Router::with_path("login").post(login))
+            .push(Router::with_path("logout").get(logout));
+        let service = Service::new(router);
+
+        let respone = TestClient::post("http://127.0.0.1:5800/login")
+            .send(&service)
+            .await;
+        let cookie = respone.headers().get(SET_COOKIE).unwrap();
+        TestClient::get("http://127.0.0.1:5800/logout")
+            .add_header(COOKIE, cookie, true)
+            .send(&service)
+            .await;
+
+        let events = events.lock().unwrap();
+        let id = events[0].session_id().to_owned();
+        assert_eq!(
+            *events,
+            vec![
+                SessionEvent::Created(id.clone()),
+                SessionEvent::Stored(id.clone()),
+                SessionEvent::Loaded(id.clone()),
+                SessionEvent::Destroyed(id),
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_session_header_transport() {
+        #[handler]
+        pub async fn login(depot: &mut Depot) {
+            let session = depot.session_mut().unwrap();
+            session.insert("username", "salvo").unwrap();
+        }
+
+        #[handler]
+        pub async fn home(depot: &mut Depot) -> String {
+            depot
+                .session()
+                .and_then(|s| s.get::<String>("username"))
+                .unwrap_or_default()
+        }
+
+        let session_handler = SessionHandler::builder(
+            MemoryStore::new(),
+            b"secretabsecretabsecretabsecretabsecretabsecretabsecretabsecretab",
+        )
+        .transport(SessionTransport::Header(HeaderName::from_static(
+            "x-session-id",
+        )))
+        .build()
+        .unwrap();
+        let router = Router::new()
+            .hoop(session_handler)
+            .get(home)
+            .push(Router::with_path("login").post(login));
+        let service = Service::new(router);
+
+        let respone = TestClient::post("http://127.0.0.1:5800/login")
+            .send(&service)
+            .await;
+        assert!(respone.headers().get(SET_COOKIE).is_none());
+        let session_id = respone.headers().get("x-session-id").unwrap().clone();
+
+        let mut respone = TestClient::get("http://127.0.0.1:5800/")
+            .add_header("x-session-id", session_id, true)
+            .send(&service)
+            .await;
+        assert_eq!(respone.take_string().await.unwrap(), "salvo");
+
+        let mut respone = TestClient::get("http://127.0.0.1:5800/")
+            .add_header("x-session-id", "tampered", true)
+            .send(&service)
+            .await;
+        assert_eq!(respone.take_string().await.unwrap(), "");
+    }
+
+    #[tokio::test]
+    async fn test_session_max_size() {
+        #[handler]
+        pub async fn small(depot: &mut Depot) {
+            let session = depot.session_mut().unwrap();
+            session.insert("data", "x").unwrap();
+        }
+
+        #[handler]
+        pub async fn large(depot: &mut Depot) {
+            let session = depot.session_mut().unwrap();
+            session.insert("data", "x".repeat(1024)).unwrap();
+        }
+
+        let session_handler = SessionHandler::builder(
+            MemoryStore::new(),
+            b"secretabsecretabsecretabsecretabsecretabsecretabsecretabsecretab",
+        )
+        .max_size(512)
+        .build()
+        .unwrap();
+        let router = Router::new()
+            .hoop(session_handler)
+            .push(Router::with_path("small").get(small))
+            .push(Router::with_path("large").get(large));
+        let service = Service::new(router);
+
+        let respone = TestClient::get("http://127.0.0.1:5800/small")
+            .send(&service)
+            .await;
+        assert!(respone.headers().get(SET_COOKIE).is_some());
+        let respone = TestClient::get("http://127.0.0.1:5800/large")
+            .send(&service)
+            .await;
+        assert!(respone.headers().get(SET_COOKIE).is_none());
+    }
+
+    #[tokio::test]
+    async fn test_session_lazy() {
+        #[handler]
+        pub async fn home(depot: &mut Depot) -> &'static str {
+            assert!(depot.session().is_none());
+            "home"
+        }
+
+        #[handler]
+        pub async fn visit(depot: &mut Depot) -> String {
+            let session = depot.session_mut().unwrap();
+            let count = session.get::<u32>("count").unwrap_or_default() + 1;
+            session.insert("count", count).unwrap();
+            count.to_string()
+        }
+
+        let session_handler = SessionHandler::builder(
+            MemoryStore::new(),
+            b"secretabsecretabsecretabsecretabsecretabsecretabsecretabsecretab",
+        )
+        .lazy(true)
+        .build()
+        .unwrap();
+        let router = Router::new()
+            .hoop(session_handler)
+            .get(home)
+            .push(Router::with_path("visit").get(visit));
+        let service = Service::new(router);
+
+        let respone = TestClient::get("http://127.0.0.1:5800/")
+            .send(&service)
+            .await;
+        assert!(respone.headers().get(SET_COOKIE).is_none());
+
+        let respone = TestClient::get("http://127.0.0.1:5800/visit")
+            .send(&service)
+            .await;
+        let cookie = respone.headers().get(SET_COOKIE).unwrap().clone();
+        let mut respone = TestClient::get("http://127.0.0.1:5800/visit")
+            .add_header(COOKIE, cookie, true)
+            .send(&service)
+            .await;
+        assert_eq!(respone.take_string().await.unwrap(), "2");
+    }
+
+    #[tokio::test]
+    async fn test_session_spawn_cleanup() {
+        let store = MemoryStore::new();
+        let session_handler = SessionHandler::builder(
+            store.clone(),
+            b"secretabsecretabsecretabsecretabsecretabsecretabsecretabsecretab",
+        )
+        .build()
+        .unwrap();
+        let mut session = Session::new();
+        session.expire_in(Duration::from_millis(1));
+        store.store_session(session).await.unwrap();
+        assert_eq!(store.count().await, 1);
+
+        let cleanup = session_handler.spawn_cleanup(Duration::from_millis(10));
+        tokio::time::sleep(Duration::from_millis(50)).await;
+        cleanup.abort();
+        assert_eq!(store.count().await, 0);
+    }
+
+    #[tokio::test]
+    async fn test_session_cookie_builder() {
+        #[handler]
+        pub async fn home(depot: &mut Depot) -> String {
+            depot
+                .session()
+                .and_then(|s| s.get::<String>("username"))
+                .unwrap_or_default()
+        }
+
+        #[handler]
+        pub async fn login(depot: &mut Depot) {
+            let session = depot.session_mut().unwrap();
+            session.insert("username", "salvo").unwrap();
+        }
+
+        let session_handler = SessionHandler::builder(
+            MemoryStore::new(),
+            b"secretabsecretabsecretabsecretabsecretabsecretabsecretabsecretab",
+        )
+        .cookie_builder(|builder| {
+            builder
+                .http_only(false)
+                .max_age(cookie::time::Duration::hours(1))
+                .build()
+        })
+        .build()
+        .unwrap();
+        let router = Router::new()
+            .hoop(session_handler)
+            .get(home)
+            .push(Router::with_path("login").post(login));
+        let service = Service::new(router);
+
+        let respone = TestClient::post("http://127.0.0.1:5800/login")
+            .send(&service)
+            .await;
+        let cookie = respone.headers().get(SET_COOKIE).unwrap().clone();
+        let cookie_str = cookie.to_str().unwrap();
+        assert!(!cookie_str.contains("HttpOnly"));
+        assert!(cookie_str.contains("Max-Age=3600"));
+
+        let mut respone = TestClient::get("http://127.0.0.1:5800/")
+            .add_header(COOKIE, cookie_str.split(';').next().unwrap(), true)
+            .send(&service)
+            .await;
+        assert_eq!(respone.take_string().await.unwrap(), "salvo");
+    }
+
+    #[tokio::test]
+    async fn test_session_fallback_cookie_names() {
+        #[handler]
+        pub async fn login(depot: &mut Depot) {
+            let session = depot.session_mut().unwrap();
+            session.insert("username", "salvo").unwrap();
+        }
+
+        #[handler]
+        pub async fn home(depot: &mut Depot) -> String {
+            depot
+                .session()
+                .and_then(|s| s.get::<String>("username"))
+                .unwrap_or_default()
+        }
+
+        let store = MemoryStore::new();
+        let secret = b"secretabsecretabsecretabsecretabsecretabsecretabsecretabsecretab";
+        let old_handler = SessionHandler::builder(store.clone(), secret)
+            .cookie_name("old.sid")
+            .save_unchanged(false)
+            .build()
+            .unwrap();
+        let respone = TestClient::post("http://127.0.0.1:5800/")
+            .send(Router::new().hoop(old_handler).post(login))
+            .await;
+        let old_cookie = respone.headers().get(SET_COOKIE).unwrap().clone();
+        let old_cookie = old_cookie.to_str().unwrap().split(';').next().unwrap();
+        assert!(old_cookie.starts_with("old.sid="));
+
+        let new_handler = SessionHandler::builder(store, secret)
+            .cookie_name("new.sid")
+            .fallback_cookie_names(vec!["old.sid"])
+            .save_unchanged(false)
+            .build()
+            .unwrap();
+        let service = Service::new(Router::new().hoop(new_handler).get(home));
+        let mut respone = TestClient::get("http://127.0.0.1:5800/")
+            .add_header(COOKIE, old_cookie, true)
+            .send(&service)
+            .await;
+        let new_cookie = respone.headers().get(SET_COOKIE).unwrap().clone();
+        let new_cookie = new_cookie.to_str().unwrap().split(';').next().unwrap();
+        assert!(new_cookie.starts_with("new.sid="));
+        assert_eq!(respone.take_string().await.unwrap(), "salvo");
+
+        let mut respone = TestClient::get("http://127.0.0.1:5800/")
+            .add_header(COOKIE, new_cookie, true)
+            .send(&service)
+            .await;
+        assert!(respone.headers().get(SET_COOKIE).is_none());
+        assert_eq!(respone.take_string().await.unwrap(), "salvo");
+
+        let (name, value) = old_cookie.split_once('=').unwrap();
+        let mut forged = value.to_owned();
+        forged.replace_range(..1, if value.starts_with('A') { "B" } else { "A" });
+        let mut respone = TestClient::get("http://127.0.0.1:5800/")
+            .add_header(COOKIE, format!("{name}={forged}"), true)
+            .send(&service)
+            .await;
+        assert!(respone.headers().get(SET_COOKIE).is_none());
+        assert_eq!(respone.take_string().await.unwrap(), "");
+    }
 }
@@ -65,7 +65,10 @@ async fn index(req: &mut Request, depot: &mut Depot, res: &mut Response) -> anyh
             JwtAuthState::Unauthorized => {
                 res.render(Text::Html(LOGIN_HTML));
             }
-            JwtAuthState::Forbidden => {
+            JwtAuthState::Expired => {
+                res.render(StatusError::unauthorized().brief("The token has expired."));
+            }
+            _ => {
                 res.render(StatusError::forbidden());
             }
         }
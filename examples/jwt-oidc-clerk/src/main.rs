@@ -27,7 +27,7 @@ async fn main() {
         .push(Router::with_hoop(auth_handler).path("welcome").get(welcome))
         .push(Router::with_path("{**rest}").goal(Proxy::new(
             vec!["http://localhost:5801"],
-            HyperClient::default(),
+            HyperClient::builder().allow_http(true).build(),
         )));
     Server::new(acceptor).serve(router).await;
 }
@@ -7,7 +7,7 @@ async fn main() {
 
     let router = Router::with_path("{**rest}").goal(Proxy::new(
         vec!["http://localhost:3000"],
-        HyperClient::default(),
+        HyperClient::builder().allow_http(true).build(),
     ));
     println!("{:?}", router);
 
@@ -7,7 +7,7 @@ async fn main() {
 
     let router = Router::with_path("{**rest}").goal(Proxy::new(
         vec!["http://localhost:5800"],
-        HyperClient::default(),
+        HyperClient::builder().allow_http(true).build(),
     ));
     println!("{:?}", router);
     tracing::info!("Run `cargo run --bin example-websocket-chat` to start websocket chat server");